@@ -1,76 +1,244 @@
 use crate::keys::double_public_key::DoublePublicKey;
 use crate::{
   blsct_obj::{self, BlsctObj},
+  chain::{self, with_chain, with_current_chain, Chain},
   ffi::{decode_address, encode_address, free_obj, AddressEncoding},
 };
-use std::ffi::{c_char, c_void, CStr, CString, NulError};
+use std::{
+  ffi::{c_char, c_void, CStr, CString, NulError},
+  fmt,
+  str::{FromStr, Utf8Error},
+  sync::OnceLock,
+};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
-pub enum Error<'a> {
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
   #[error("Failed to allocate memory for {0:?}")]
   FailedToAllocateMemory(&'static str),
 
-  #[error("Failed to construct BlsctRetVal: {0:?}")]
-  FailedToConstructBlsctRetVal(blsct_obj::Error<'a>),
+  #[error("Failed to construct BlsctRetVal")]
+  FailedToConstructBlsctRetVal(#[from] blsct_obj::Error),
 
   #[error("Failed to encode address: {0:?}")]
-  FailedToEncodeAddress(&'a DoublePublicKey),
+  FailedToEncodeAddress(DoublePublicKey),
 
   #[error("Failed to decode address: {0:?}")]
-  FailedToDecodeAddress(&'a str),
+  FailedToDecodeAddress(String),
+
+  #[error("Failed to construct CString")]
+  FailedToConstructCString(#[source] NulError),
+
+  #[error("Unknown address HRP: {0:?}")]
+  UnknownHrp(String),
+
+  #[error("Address belongs to {actual:?}, but {expected:?} is expected")]
+  ChainMismatch { expected: Chain, actual: Chain },
+
+  #[error("Address may belong to any of {0:?}")]
+  AmbiguousChain(Vec<Chain>),
 
-  #[error("Failed to construct CString: {0:?}")]
-  FailedToConstructCString(NulError),
+  #[error("Failed to get the current chain")]
+  ChainError(#[source] chain::Error),
+
+  #[error("Failed to convert CStr to &str")]
+  FailedToConvertCStrToStr(#[source] Utf8Error),
 }
 
-pub struct Address();
+// an address bound to the chain and the encoding it was created for.
+// it is encoded once on creation so that displaying it cannot fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+  dpk: DoublePublicKey,
+  chain: Chain,
+  encoding: AddressEncoding,
+  addr_str: String,
+}
 
 impl Address {
-  pub fn encode(
-    addr_dpk: &DoublePublicKey,
-    encoding: AddressEncoding,
-  ) -> Result<String, Error<'_>> {
-    let rv = unsafe { encode_address(addr_dpk.value(), encoding) };
-    if rv.is_null() {
-      unsafe { free_obj(rv as *mut c_void) };
-      Err(Error::FailedToAllocateMemory("BlsctRetVal"))
-    } else if unsafe { (*rv).result != 0 } {
-      unsafe { free_obj(rv as *mut c_void) };
-      Err(Error::FailedToEncodeAddress(addr_dpk))
-    } else {
-      let addr_c_str = unsafe { CStr::from_ptr((*rv).value as *const c_char) };
-      let addr = addr_c_str.to_str().unwrap().to_string();
-      unsafe { free_obj((*rv).value as *mut c_void) };
-      Ok(addr)
+  pub fn new(dpk: DoublePublicKey, chain: Chain, encoding: AddressEncoding) -> Result<Self, Error> {
+    let addr_str = with_chain(chain, || encode_dpk(&dpk, encoding))?;
+    Ok(Address {
+      dpk,
+      chain,
+      encoding,
+      addr_str,
+    })
+  }
+
+  pub fn dpk(&self) -> &DoublePublicKey {
+    &self.dpk
+  }
+
+  pub fn chain(&self) -> Chain {
+    self.chain
+  }
+
+  pub fn encoding(&self) -> AddressEncoding {
+    self.encoding
+  }
+
+  // detects the chain from the HRP of `addr_str`. fails with AmbiguousChain
+  // if the HRP is shared by more than one chain, which parse_for_chain resolves.
+  pub fn parse(addr_str: &str) -> Result<Self, Error> {
+    let chains = chains_of_hrp(addr_str)?;
+    if chains.len() > 1 {
+      return Err(Error::AmbiguousChain(chains));
+    }
+    parse_on_chain(addr_str, chains[0])
+  }
+
+  // fails with ChainMismatch if `addr_str` does not belong to `expected`
+  pub fn parse_for_chain(addr_str: &str, expected: Chain) -> Result<Self, Error> {
+    let chains = chains_of_hrp(addr_str)?;
+    if !chains.contains(&expected) {
+      return Err(Error::ChainMismatch {
+        expected,
+        actual: chains[0],
+      });
     }
+    parse_on_chain(addr_str, expected)
   }
 
-  pub fn decode(addr_str: &str) -> Result<DoublePublicKey, Error<'_>> {
-    let c_addr_str = CString::new(addr_str).map_err(Error::FailedToConstructCString)?;
+  pub fn encode(addr_dpk: &DoublePublicKey, encoding: AddressEncoding) -> Result<String, Error> {
+    with_current_chain(|| encode_dpk(addr_dpk, encoding))
+  }
+
+  // decodes `addr_str` on the chain of the calling thread
+  pub fn decode(addr_str: &str) -> Result<DoublePublicKey, Error> {
+    let chain = Chain::get().map_err(Error::ChainError)?;
+    let candidates = chains_of_hrp(addr_str)?;
+    if !candidates.contains(&chain) {
+      return Err(Error::ChainMismatch {
+        expected: chain,
+        actual: candidates[0],
+      });
+    }
+    with_chain(chain, || decode_dpk(addr_str))
+  }
+}
+
+impl fmt::Display for Address {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.addr_str)
+  }
+}
+
+impl FromStr for Address {
+  type Err = Error;
 
-    let rv = unsafe { decode_address(c_addr_str.as_ptr()) };
-    if rv.is_null() {
-      unsafe { free_obj(rv as *mut c_void) };
-      Err(Error::FailedToAllocateMemory("BlsctRetVal"))
-    } else if unsafe { (*rv).result != 0 } {
-      unsafe { free_obj(rv as *mut c_void) };
-      Err(Error::FailedToDecodeAddress(addr_str))
-    } else {
-      let addr_dpk = BlsctObj::from_retval(rv).map_err(Error::FailedToConstructBlsctRetVal)?;
-      Ok(addr_dpk.into())
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Address::parse(s)
+  }
+}
+
+fn encode_dpk(addr_dpk: &DoublePublicKey, encoding: AddressEncoding) -> Result<String, Error> {
+  let rv = unsafe { encode_address(addr_dpk.value(), encoding) };
+  if rv.is_null() {
+    unsafe { free_obj(rv as *mut c_void) };
+    Err(Error::FailedToAllocateMemory("BlsctRetVal"))
+  } else if unsafe { (*rv).result != 0 } {
+    unsafe { free_obj(rv as *mut c_void) };
+    Err(Error::FailedToEncodeAddress(addr_dpk.clone()))
+  } else {
+    let addr_c_str = unsafe { CStr::from_ptr((*rv).value as *const c_char) };
+    let addr = addr_c_str
+      .to_str()
+      .map(str::to_string)
+      .map_err(Error::FailedToConvertCStrToStr);
+    unsafe {
+      free_obj((*rv).value as *mut c_void);
+      free_obj(rv as *mut c_void);
     }
+    addr
   }
 }
 
+fn decode_dpk(addr_str: &str) -> Result<DoublePublicKey, Error> {
+  let c_addr_str = CString::new(addr_str).map_err(Error::FailedToConstructCString)?;
+
+  let rv = unsafe { decode_address(c_addr_str.as_ptr()) };
+  if rv.is_null() {
+    unsafe { free_obj(rv as *mut c_void) };
+    Err(Error::FailedToAllocateMemory("BlsctRetVal"))
+  } else if unsafe { (*rv).result != 0 } {
+    unsafe { free_obj(rv as *mut c_void) };
+    Err(Error::FailedToDecodeAddress(addr_str.to_string()))
+  } else {
+    let addr_dpk = BlsctObj::from_retval(rv).map_err(Error::FailedToConstructBlsctRetVal)?;
+    Ok(addr_dpk.into())
+  }
+}
+
+// the decoder does not report the encoding, so the address is re-encoded to find it out
+fn parse_on_chain(addr_str: &str, chain: Chain) -> Result<Address, Error> {
+  let dpk = with_chain(chain, || decode_dpk(addr_str))?;
+
+  let bech32m_str = with_chain(chain, || encode_dpk(&dpk, AddressEncoding::Bech32M))
+    .map_err(|_| Error::FailedToDecodeAddress(addr_str.to_string()))?;
+  if bech32m_str.eq_ignore_ascii_case(addr_str) {
+    return Ok(Address {
+      dpk,
+      chain,
+      encoding: AddressEncoding::Bech32M,
+      addr_str: bech32m_str,
+    });
+  }
+  Address::new(dpk, chain, AddressEncoding::Bech32)
+}
+
+const CHAINS: [Chain; 4] = [
+  Chain::Mainnet,
+  Chain::Testnet,
+  Chain::Signet,
+  Chain::Regtest,
+];
+
+// the HRP of each chain is taken from libblsct by encoding a throwaway key
+// so that it never goes out of sync with the chain parameters
+fn hrps() -> Result<&'static [(Chain, String)], Error> {
+  static HRPS: OnceLock<Vec<(Chain, String)>> = OnceLock::new();
+  if let Some(hrps) = HRPS.get() {
+    return Ok(hrps);
+  }
+  let dpk = DoublePublicKey::random().map_err(Error::FailedToConstructBlsctRetVal)?;
+  let hrps = CHAINS
+    .iter()
+    .map(|chain| {
+      let addr_str = with_chain(*chain, || encode_dpk(&dpk, AddressEncoding::Bech32M))
+        .map_err(|_| Error::FailedToAllocateMemory("HRP"))?;
+      let hrp = split_hrp(&addr_str).ok_or(Error::UnknownHrp(addr_str.clone()))?;
+      Ok((*chain, hrp.to_string()))
+    })
+    .collect::<Result<Vec<_>, Error>>()?;
+  Ok(HRPS.get_or_init(|| hrps))
+}
+
+fn split_hrp(addr_str: &str) -> Option<&str> {
+  addr_str.rsplit_once('1').map(|(hrp, _)| hrp)
+}
+
+// testnet and signet share the same HRP, so more than one chain may be returned
+fn chains_of_hrp(addr_str: &str) -> Result<Vec<Chain>, Error> {
+  let hrp = split_hrp(addr_str).ok_or(Error::UnknownHrp(addr_str.to_string()))?;
+  let chains = hrps()?
+    .iter()
+    .filter(|(_, x)| x.eq_ignore_ascii_case(hrp))
+    .map(|(chain, _)| *chain)
+    .collect::<Vec<_>>();
+  if chains.is_empty() {
+    return Err(Error::UnknownHrp(hrp.to_string()));
+  }
+  Ok(chains)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, keys::double_public_key::DoublePublicKey};
+  use crate::keys::double_public_key::DoublePublicKey;
 
   #[test]
   fn test_encode_decode() {
-    init();
     for encoding in [AddressEncoding::Bech32, AddressEncoding::Bech32M] {
       let addr_dpk = DoublePublicKey::random().unwrap();
       let addr_str = Address::encode(&addr_dpk, encoding).unwrap();
@@ -80,4 +248,111 @@ mod tests {
       assert_eq!(addr_dpk, decoded_dpk);
     }
   }
+
+  #[test]
+  fn test_parse_display() {
+    for chain in CHAINS {
+      for encoding in [AddressEncoding::Bech32, AddressEncoding::Bech32M] {
+        let addr = Address::new(DoublePublicKey::random().unwrap(), chain, encoding).unwrap();
+        let addr_str = addr.to_string();
+        let parsed = Address::parse_for_chain(&addr_str, chain).unwrap();
+        assert_eq!(parsed, addr);
+        assert_eq!(parsed.to_string(), addr_str);
+        if chains_of_hrp(&addr_str).unwrap().len() == 1 {
+          assert_eq!(addr_str.parse::<Address>().unwrap(), addr);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_shared_hrp() {
+    let dpk = DoublePublicKey::random().unwrap();
+    let testnet_addr = Address::new(dpk.clone(), Chain::Testnet, AddressEncoding::Bech32M).unwrap();
+    let signet_addr = Address::new(dpk, Chain::Signet, AddressEncoding::Bech32M).unwrap();
+    let addr_str = testnet_addr.to_string();
+    assert_eq!(signet_addr.to_string(), addr_str);
+
+    // the chain is never guessed from the chain selected on the thread
+    for chain in CHAINS {
+      let _scope = chain.scope();
+      assert!(matches!(
+        Address::parse(&addr_str),
+        Err(Error::AmbiguousChain(chains)) if chains == [Chain::Testnet, Chain::Signet]
+      ));
+    }
+    let _scope = Chain::Mainnet.scope();
+    assert_eq!(
+      Address::parse_for_chain(&addr_str, Chain::Testnet).unwrap(),
+      testnet_addr
+    );
+    assert_eq!(
+      Address::parse_for_chain(&addr_str, Chain::Signet).unwrap(),
+      signet_addr
+    );
+  }
+
+  #[test]
+  fn test_detect_chain() {
+    let dpk = DoublePublicKey::random().unwrap();
+    let mainnet_addr = Address::new(dpk.clone(), Chain::Mainnet, AddressEncoding::Bech32M).unwrap();
+    let regtest_addr = Address::new(dpk, Chain::Regtest, AddressEncoding::Bech32M).unwrap();
+    assert_ne!(mainnet_addr.to_string(), regtest_addr.to_string());
+
+    let addr = Address::parse(&regtest_addr.to_string()).unwrap();
+    assert_eq!(addr.chain(), Chain::Regtest);
+  }
+
+  #[test]
+  fn test_chain_mismatch() {
+    let addr = Address::new(
+      DoublePublicKey::random().unwrap(),
+      Chain::Regtest,
+      AddressEncoding::Bech32M,
+    )
+    .unwrap();
+    let res = Address::parse_for_chain(&addr.to_string(), Chain::Mainnet);
+    assert!(matches!(
+      res,
+      Err(Error::ChainMismatch {
+        expected: Chain::Mainnet,
+        actual: Chain::Regtest,
+      })
+    ));
+  }
+
+  #[test]
+  fn test_parallel_chains() {
+    let handles = [Chain::Mainnet, Chain::Testnet, Chain::Regtest]
+      .into_iter()
+      .map(|chain| {
+        std::thread::spawn(move || {
+          let _scope = chain.scope();
+          for _ in 0..50 {
+            let dpk = DoublePublicKey::random().unwrap();
+            let addr_str = Address::encode(&dpk, AddressEncoding::Bech32M).unwrap();
+            assert_eq!(Address::decode(&addr_str).unwrap(), dpk);
+
+            let addr = Address::parse_for_chain(&addr_str, chain).unwrap();
+            assert_eq!(addr.chain(), chain);
+          }
+        })
+      })
+      .collect::<Vec<_>>();
+    for handle in handles {
+      handle.join().unwrap();
+    }
+  }
+
+  #[test]
+  fn test_unknown_hrp() {
+    assert!(matches!(
+      "xyz1qqqqqq".parse::<Address>(),
+      Err(Error::UnknownHrp(_))
+    ));
+    assert!(matches!(
+      "no-separator".parse::<Address>(),
+      Err(Error::UnknownHrp(_))
+    ));
+  }
 }
@@ -37,11 +37,10 @@ impl PartialEq for AmountRecoveryReq {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, token_id::TokenId};
+  use crate::token_id::TokenId;
 
   #[test]
   fn test_deser() {
-    init();
     let values = vec![123u64];
     let nonce = Point::random().unwrap();
     let token_id = TokenId::default().unwrap();
@@ -1,36 +1,51 @@
+use crate::memo::{self, Memo};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Eq)]
 pub struct AmountRecoveryRes {
   pub is_succ: bool,
   pub amount: u64,
+  // the message as text, with invalid UTF-8 replaced
   pub msg: String,
+  // the message exactly as recovered
+  pub msg_bytes: Vec<u8>,
 }
 
 impl AmountRecoveryRes {
   pub fn new(is_succ: bool, amount: u64, msg: &str) -> Self {
+    Self::from_msg_bytes(is_succ, amount, msg.as_bytes())
+  }
+
+  // the message is attacker-controlled, so invalid UTF-8 must not prevent
+  // the amount from being recovered
+  pub fn from_msg_bytes(is_succ: bool, amount: u64, msg_bytes: &[u8]) -> Self {
     AmountRecoveryRes {
       is_succ,
       amount,
-      msg: msg.to_string(),
+      msg: String::from_utf8_lossy(msg_bytes).into_owned(),
+      msg_bytes: msg_bytes.to_vec(),
     }
   }
+
+  pub fn memo(&self) -> Result<Memo, memo::Error> {
+    Memo::from_recovery_res(self)
+  }
 }
 
 impl PartialEq for AmountRecoveryRes {
   fn eq(&self, other: &Self) -> bool {
-    self.is_succ == other.is_succ && self.amount == other.amount && self.msg == other.msg
+    self.is_succ == other.is_succ
+      && self.amount == other.amount
+      && self.msg_bytes == other.msg_bytes
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
 
   #[test]
   fn test_deser() {
-    init();
     let is_succ = true;
     let amount = 12345u64;
     let msg = "navio";
@@ -39,4 +54,12 @@ mod tests {
     let b = bincode::deserialize::<AmountRecoveryRes>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_invalid_utf8_msg() {
+    let res = AmountRecoveryRes::from_msg_bytes(true, 1, b"nav\xffio");
+    assert_eq!(res.msg, "nav\u{fffd}io");
+    assert_eq!(res.msg_bytes, b"nav\xffio");
+    assert!(matches!(res.memo(), Err(memo::Error::InvalidUtf8(_))));
+  }
 }
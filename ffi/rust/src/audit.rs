@@ -0,0 +1,376 @@
+use crate::{
+  blsct_obj::BlsctObj,
+  ctx::CTx,
+  ctx_out::CTxOut,
+  ffi::{sign_message, verify_msg_sig},
+  hash_id::{HashId, SubAddrs},
+  keys::public_key::PublicKey,
+  scalar::Scalar,
+  signature::Signature,
+  sub_addr_id::SubAddrId,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ffi::CString};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("Failed to read the transaction: {0}")]
+  FailedToReadCTx(String),
+
+  #[error("The total of token {token}:{subid} overflows")]
+  TotalOverflow { token: u64, subid: u64 },
+
+  #[error("Failed to sign the report: {0}")]
+  FailedToSign(String),
+
+  #[error("The key is not the spending key of the audited wallet")]
+  WrongSpendingKey,
+
+  #[error("Invalid signature")]
+  InvalidSignature,
+
+  #[error("The report does not match the transactions")]
+  ReportMismatch,
+}
+
+// an output received by one of the audited sub-addresses
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuditEntry {
+  pub txid: String,
+  pub index: usize,
+  pub amount: u64,
+  pub memo: String,
+  pub sub_addr_id: SubAddrId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TokenReport {
+  pub token: u64,
+  pub subid: u64,
+  pub total: u64,
+  pub outputs: Vec<AuditEntry>,
+}
+
+// the statement that gets signed. outputs are sorted by txid and index so
+// that the auditor gets the same report regardless of the order of the CTxs.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuditBody {
+  pub spending_pub_key: PublicKey,
+  pub tokens: Vec<TokenReport>,
+}
+
+// signed with the spending key of the audited wallet, which shows that the
+// report comes from the owner of the outputs and not just from someone who
+// knows the view key
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuditReport {
+  body: AuditBody,
+  signature: Signature,
+}
+
+fn read_error(e: impl ToString) -> Error {
+  Error::FailedToReadCTx(e.to_string())
+}
+
+// returns None if the output is not addressed to one of the sub-addresses
+fn audit_output(
+  ctx_out: &CTxOut,
+  txid: &str,
+  index: usize,
+  view_key: &Scalar,
+  sub_addrs: &SubAddrs,
+) -> Result<Option<AuditEntry>, Error> {
+  let key_id = HashId::new(
+    &ctx_out.blsct_data_blinding_pub_key(),
+    &ctx_out.blsct_data_spending_pub_key(),
+    view_key,
+  );
+  let Some(sub_addr_id) = sub_addrs.get(&key_id) else {
+    return Ok(None);
+  };
+  let Some(res) = ctx_out.recover_amount(view_key).map_err(read_error)? else {
+    return Ok(None);
+  };
+  Ok(Some(AuditEntry {
+    txid: txid.to_string(),
+    index,
+    amount: res.amount,
+    memo: res.msg,
+    sub_addr_id: sub_addr_id.clone(),
+  }))
+}
+
+impl AuditBody {
+  // reports the outputs received by the given sub-addresses of the wallet
+  // with the spending public key. outputs to any other sub-address are left
+  // out even if the view key can read them.
+  pub fn new(
+    view_key: &Scalar,
+    spending_pub_key: &PublicKey,
+    sub_addr_ids: &[SubAddrId],
+    ctxs: &[CTx],
+  ) -> Result<Self, Error> {
+    let sub_addrs = SubAddrs::new(view_key, spending_pub_key, sub_addr_ids).map_err(read_error)?;
+
+    // keyed by (token, subid) and then by (txid, index), which also drops
+    // CTxs given more than once
+    let mut tokens: BTreeMap<(u64, u64), BTreeMap<(String, usize), AuditEntry>> = BTreeMap::new();
+
+    for ctx in ctxs {
+      let txid = ctx.get_ctx_id().map_err(read_error)?.to_hex();
+      let ctx_outs = ctx.get_ctx_outs();
+      for i in 0..ctx_outs.len() {
+        let ctx_out = ctx_outs.at(i).map_err(read_error)?;
+        if let Some(entry) = audit_output(&ctx_out, &txid, i, view_key, &sub_addrs)? {
+          let token_id = ctx_out.token_id();
+          tokens
+            .entry((token_id.token(), token_id.subid()))
+            .or_default()
+            .insert((txid.clone(), i), entry);
+        }
+      }
+    }
+
+    let tokens = tokens
+      .into_iter()
+      .map(|((token, subid), outputs)| {
+        let outputs: Vec<AuditEntry> = outputs.into_values().collect();
+        let total = outputs
+          .iter()
+          .try_fold(0u64, |acc, x| acc.checked_add(x.amount))
+          .ok_or(Error::TotalOverflow { token, subid })?;
+        Ok(TokenReport {
+          token,
+          subid,
+          total,
+          outputs,
+        })
+      })
+      .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(AuditBody {
+      spending_pub_key: spending_pub_key.clone(),
+      tokens,
+    })
+  }
+
+  // the signed message is the JSON of the body
+  fn message(&self) -> Result<CString, Error> {
+    let msg = serde_json::to_string(self).map_err(|e| Error::FailedToSign(e.to_string()))?;
+    CString::new(msg).map_err(|e| Error::FailedToSign(e.to_string()))
+  }
+
+  pub fn sign(self, spending_key: &Scalar) -> Result<AuditReport, Error> {
+    if PublicKey::from(spending_key) != self.spending_pub_key {
+      return Err(Error::WrongSpendingKey);
+    }
+    let msg = self.message()?;
+    let blsct_signature = unsafe { sign_message(spending_key.value(), msg.as_ptr()) };
+    let signature: Signature = BlsctObj::from_c_obj(blsct_signature).into();
+    Ok(AuditReport {
+      body: self,
+      signature,
+    })
+  }
+}
+
+impl AuditReport {
+  pub fn body(&self) -> &AuditBody {
+    &self.body
+  }
+
+  pub fn signature(&self) -> &Signature {
+    &self.signature
+  }
+
+  // checks that the report is signed by the spending key it is about
+  pub fn verify_signature(&self) -> Result<(), Error> {
+    let msg = self.body.message()?;
+    let spending_pub_key = &self.body.spending_pub_key;
+    if unsafe {
+      verify_msg_sig(
+        spending_pub_key.value(),
+        msg.as_ptr(),
+        self.signature.value(),
+      )
+    } {
+      Ok(())
+    } else {
+      Err(Error::InvalidSignature)
+    }
+  }
+
+  // recomputes the report from the CTxs with the disclosed view key and
+  // sub-addresses and checks that it is the one the wallet signed. the
+  // auditor has to check separately that the spending public key is the
+  // one of the wallet it audits.
+  pub fn verify(
+    &self,
+    view_key: &Scalar,
+    sub_addr_ids: &[SubAddrId],
+    ctxs: &[CTx],
+  ) -> Result<(), Error> {
+    self.verify_signature()?;
+    let body = AuditBody::new(view_key, &self.body.spending_pub_key, sub_addr_ids, ctxs)?;
+    if body != self.body {
+      return Err(Error::ReportMismatch);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    keys::child_key::ChildKey, sub_addr::SubAddr, sub_addr_id::SubAddrId, test_util::gen_ctx_actual,
+  };
+
+  struct Wallet {
+    view_key: Scalar,
+    spending_key: Scalar,
+    spending_pub_key: PublicKey,
+  }
+
+  fn gen_wallet() -> Wallet {
+    let tx_key = ChildKey::random().unwrap().to_tx_key();
+    let spending_key = tx_key.to_spending_key().expose_secret().clone();
+    Wallet {
+      view_key: tx_key.to_view_key().expose_secret().clone(),
+      spending_pub_key: PublicKey::from(&spending_key),
+      spending_key,
+    }
+  }
+
+  fn sub_addr_ids() -> Vec<SubAddrId> {
+    (0..4).map(|i| SubAddrId::new(0, i)).collect()
+  }
+
+  fn gen_ctxs(wallet: &Wallet) -> Vec<CTx> {
+    let destination = |address| {
+      SubAddr::new(
+        &wallet.view_key,
+        &wallet.spending_pub_key,
+        &SubAddrId::new(0, address),
+      )
+    };
+    vec![
+      gen_ctx_actual(1000, "first", &destination(1), &Scalar::random().unwrap()),
+      gen_ctx_actual(2000, "second", &destination(2), &Scalar::random().unwrap()),
+    ]
+  }
+
+  #[test]
+  fn test_report() {
+    let wallet = gen_wallet();
+    let ctxs = gen_ctxs(&wallet);
+    let ids = sub_addr_ids();
+    let body = AuditBody::new(&wallet.view_key, &wallet.spending_pub_key, &ids, &ctxs).unwrap();
+    assert_eq!(body.tokens.len(), 1);
+
+    let token = &body.tokens[0];
+    assert_eq!(token.total, 3000);
+    assert_eq!(token.outputs.len(), 2);
+    let first = token.outputs.iter().find(|x| x.memo == "first").unwrap();
+    assert_eq!(first.sub_addr_id, SubAddrId::new(0, 1));
+    let second = token.outputs.iter().find(|x| x.memo == "second").unwrap();
+    assert_eq!(second.sub_addr_id, SubAddrId::new(0, 2));
+
+    // the order of the CTxs and duplicates do not matter
+    let reordered = vec![
+      ctxs[1].try_clone().unwrap(),
+      ctxs[0].try_clone().unwrap(),
+      ctxs[1].try_clone().unwrap(),
+    ];
+    let body2 =
+      AuditBody::new(&wallet.view_key, &wallet.spending_pub_key, &ids, &reordered).unwrap();
+    assert_eq!(body, body2);
+  }
+
+  #[test]
+  fn test_other_sub_addrs_are_left_out() {
+    let wallet = gen_wallet();
+    let ctxs = gen_ctxs(&wallet);
+    let ids = vec![SubAddrId::new(0, 2)];
+    let body = AuditBody::new(&wallet.view_key, &wallet.spending_pub_key, &ids, &ctxs).unwrap();
+    assert_eq!(body.tokens[0].total, 2000);
+    assert_eq!(body.tokens[0].outputs.len(), 1);
+
+    // the view key reads the outputs, but they belong to another wallet
+    let other = PublicKey::random().unwrap();
+    let body = AuditBody::new(&wallet.view_key, &other, &sub_addr_ids(), &ctxs).unwrap();
+    assert!(body.tokens.is_empty());
+  }
+
+  #[test]
+  fn test_sign_and_verify() {
+    let wallet = gen_wallet();
+    let ctxs = gen_ctxs(&wallet);
+    let ids = sub_addr_ids();
+    let body = AuditBody::new(&wallet.view_key, &wallet.spending_pub_key, &ids, &ctxs).unwrap();
+
+    // only the spending key of the audited wallet can sign the report
+    assert!(matches!(
+      body.clone().sign(&Scalar::random().unwrap()),
+      Err(Error::WrongSpendingKey)
+    ));
+    let report = body.sign(&wallet.spending_key).unwrap();
+
+    let json = serde_json::to_string(&report).unwrap();
+    let report: AuditReport = serde_json::from_str(&json).unwrap();
+    report.verify(&wallet.view_key, &ids, &ctxs).unwrap();
+
+    assert!(matches!(
+      report.verify(&wallet.view_key, &ids, &ctxs[..1]),
+      Err(Error::ReportMismatch)
+    ));
+    assert!(matches!(
+      report.verify(&wallet.view_key, &ids[..2], &ctxs),
+      Err(Error::ReportMismatch)
+    ));
+  }
+
+  #[test]
+  fn test_signature_by_other_key_is_rejected() {
+    let wallet = gen_wallet();
+    let ctxs = gen_ctxs(&wallet);
+    let ids = sub_addr_ids();
+    let body = AuditBody::new(&wallet.view_key, &wallet.spending_pub_key, &ids, &ctxs).unwrap();
+
+    // a report signed by an unrelated key that claims the wallet's
+    // spending public key
+    let other_key = Scalar::random().unwrap();
+    let msg = body.message().unwrap();
+    let blsct_signature = unsafe { sign_message(other_key.value(), msg.as_ptr()) };
+    let report = AuditReport {
+      body,
+      signature: BlsctObj::from_c_obj(blsct_signature).into(),
+    };
+    assert!(matches!(
+      report.verify(&wallet.view_key, &ids, &ctxs),
+      Err(Error::InvalidSignature)
+    ));
+  }
+
+  #[test]
+  fn test_tampered_report_is_rejected() {
+    let wallet = gen_wallet();
+    let ctxs = gen_ctxs(&wallet);
+    let mut report = AuditBody::new(
+      &wallet.view_key,
+      &wallet.spending_pub_key,
+      &sub_addr_ids(),
+      &ctxs,
+    )
+    .unwrap()
+    .sign(&wallet.spending_key)
+    .unwrap();
+
+    report.body.tokens[0].total += 1;
+    assert!(matches!(
+      report.verify_signature(),
+      Err(Error::InvalidSignature)
+    ));
+  }
+}
@@ -0,0 +1,263 @@
+use crate::chain::{self, Chain};
+use std::{
+  future::Future,
+  panic::{self, AssertUnwindSafe},
+  pin::Pin,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex, OnceLock,
+  },
+  task::{Context, Poll, Waker},
+  thread,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+  #[error("The task was cancelled")]
+  Cancelled,
+
+  #[error("The task panicked")]
+  Panicked,
+
+  #[error("Failed to start the blocking pool")]
+  Unavailable,
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// a fixed set of threads running the blocking libblsct calls of the async
+// API, so that at most that many run at once and the threads of the async
+// executor stay free
+pub struct BlockingPool {
+  sender: mpsc::Sender<Job>,
+  num_threads: usize,
+}
+
+static GLOBAL_POOL: OnceLock<BlockingPool> = OnceLock::new();
+
+impl BlockingPool {
+  pub fn new(num_threads: usize) -> std::io::Result<Self> {
+    let num_threads = num_threads.max(1);
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    for i in 0..num_threads {
+      let receiver = Arc::clone(&receiver);
+      thread::Builder::new()
+        .name(format!("blsct-blocking-{i}"))
+        .spawn(move || loop {
+          // the pool is dropped once the sender is gone
+          let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+          match job {
+            Ok(job) => job(),
+            Err(_) => break,
+          }
+        })?;
+    }
+    Ok(BlockingPool {
+      sender,
+      num_threads,
+    })
+  }
+
+  // the pool the async API runs on. it has a thread per available core.
+  pub fn global() -> Result<&'static BlockingPool, Error> {
+    if let Some(pool) = GLOBAL_POOL.get() {
+      return Ok(pool);
+    }
+    let num_threads = thread::available_parallelism().map_or(4, |n| n.get());
+    let pool = BlockingPool::new(num_threads).map_err(|_| Error::Unavailable)?;
+    // a pool started by a racing thread is dropped and its threads exit
+    Ok(GLOBAL_POOL.get_or_init(|| pool))
+  }
+
+  pub fn num_threads(&self) -> usize {
+    self.num_threads
+  }
+
+  // runs `f` on the pool in the chain scope of the caller. dropping the
+  // returned task cancels `f` if it has not started yet. a running `f`
+  // cannot be interrupted and its result is discarded.
+  pub fn spawn<F, T>(&self, f: F) -> Task<T>
+  where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+  {
+    let shared = Arc::new(Shared {
+      slot: Mutex::new(Slot {
+        result: None,
+        waker: None,
+      }),
+      cancelled: AtomicBool::new(false),
+    });
+
+    let job_shared = Arc::clone(&shared);
+    let chain = chain::scoped_chain();
+    let job: Job = Box::new(move || {
+      let result = if job_shared.cancelled.load(Ordering::Acquire) {
+        Err(Error::Cancelled)
+      } else {
+        let _scope = chain.map(Chain::scope);
+        panic::catch_unwind(AssertUnwindSafe(f)).map_err(|_| Error::Panicked)
+      };
+      job_shared.complete(result);
+    });
+    if self.sender.send(job).is_err() {
+      shared.complete(Err(Error::Cancelled));
+    }
+    Task { shared }
+  }
+}
+
+struct Slot<T> {
+  result: Option<Result<T, Error>>,
+  waker: Option<Waker>,
+}
+
+struct Shared<T> {
+  slot: Mutex<Slot<T>>,
+  cancelled: AtomicBool,
+}
+
+impl<T> Shared<T> {
+  fn lock(&self) -> std::sync::MutexGuard<'_, Slot<T>> {
+    self.slot.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  fn complete(&self, result: Result<T, Error>) {
+    let waker = {
+      let mut slot = self.lock();
+      slot.result = Some(result);
+      slot.waker.take()
+    };
+    if let Some(waker) = waker {
+      waker.wake();
+    }
+  }
+}
+
+// the result of a function run on a BlockingPool
+pub struct Task<T> {
+  shared: Arc<Shared<T>>,
+}
+
+impl<T> Task<T> {
+  pub fn cancel(&self) {
+    self.shared.cancelled.store(true, Ordering::Release);
+  }
+}
+
+impl<T> Future for Task<T> {
+  type Output = Result<T, Error>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let mut slot = self.shared.lock();
+    if let Some(result) = slot.result.take() {
+      return Poll::Ready(result);
+    }
+    if self.shared.cancelled.load(Ordering::Acquire) {
+      return Poll::Ready(Err(Error::Cancelled));
+    }
+    slot.waker = Some(cx.waker().clone());
+    Poll::Pending
+  }
+}
+
+impl<T> Drop for Task<T> {
+  fn drop(&mut self) {
+    self.cancel();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::block_on;
+  use std::{
+    sync::atomic::AtomicUsize,
+    time::{Duration, Instant},
+  };
+
+  fn assert_send<T: Send>(_: &T) {}
+
+  #[test]
+  fn test_spawn() {
+    let pool = BlockingPool::new(2).unwrap();
+    let task = pool.spawn(|| 1 + 2);
+    assert_send(&task);
+    assert_eq!(block_on(task), Ok(3));
+  }
+
+  #[test]
+  fn test_bounded_concurrency() {
+    let pool = BlockingPool::new(2).unwrap();
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+    let tasks: Vec<_> = (0..8)
+      .map(|_| {
+        let running = Arc::clone(&running);
+        let max_running = Arc::clone(&max_running);
+        pool.spawn(move || {
+          let n = running.fetch_add(1, Ordering::SeqCst) + 1;
+          max_running.fetch_max(n, Ordering::SeqCst);
+          thread::sleep(Duration::from_millis(20));
+          running.fetch_sub(1, Ordering::SeqCst);
+        })
+      })
+      .collect();
+    for task in tasks {
+      block_on(task).unwrap();
+    }
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+  }
+
+  #[test]
+  fn test_cancel() {
+    let pool = BlockingPool::new(1).unwrap();
+    let (release, wait) = mpsc::channel::<()>();
+    let blocker = pool.spawn(move || wait.recv().unwrap());
+
+    // queued behind the blocker and cancelled before it starts
+    let ran = Arc::new(AtomicBool::new(false));
+    let ran_clone = Arc::clone(&ran);
+    let task = pool.spawn(move || ran_clone.store(true, Ordering::SeqCst));
+    task.cancel();
+    assert_eq!(block_on(task), Err(Error::Cancelled));
+
+    let ran_clone = Arc::clone(&ran);
+    drop(pool.spawn(move || ran_clone.store(true, Ordering::SeqCst)));
+
+    release.send(()).unwrap();
+    block_on(blocker).unwrap();
+    block_on(pool.spawn(|| ())).unwrap();
+    assert!(!ran.load(Ordering::SeqCst));
+  }
+
+  #[test]
+  fn test_chain_scope() {
+    let pool = BlockingPool::new(1).unwrap();
+    let _scope = Chain::Signet.scope();
+    let task = pool.spawn(chain::scoped_chain);
+    assert_eq!(block_on(task), Ok(Some(Chain::Signet)));
+  }
+
+  #[test]
+  fn test_panic() {
+    let pool = BlockingPool::new(1).unwrap();
+    assert_eq!(
+      block_on(pool.spawn(|| panic!("boom"))),
+      Err::<(), _>(Error::Panicked)
+    );
+    // the thread survives the panic
+    assert_eq!(block_on(pool.spawn(|| 7)), Ok(7));
+  }
+
+  #[test]
+  fn test_does_not_block_the_caller() {
+    let pool = BlockingPool::new(1).unwrap();
+    let start = Instant::now();
+    let task = pool.spawn(|| thread::sleep(Duration::from_millis(200)));
+    assert!(start.elapsed() < Duration::from_millis(200));
+    block_on(task).unwrap();
+  }
+}
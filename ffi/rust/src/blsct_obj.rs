@@ -1,38 +1,39 @@
 use crate::{
   blsct_serde::BlsctSerde,
+  error::ResultCode,
   ffi::{free_obj, malloc, BlsctRetVal},
 };
 use std::slice;
 
 use serde::{
-  de::Error as DeError, ser::Error as SerError, Deserialize, Deserializer, Serialize, Serializer,
+  de::{Error as DeError, SeqAccess, Visitor},
+  ser::Error as SerError,
+  Deserialize, Deserializer, Serialize, Serializer,
 };
 use std::{
+  alloc::{handle_alloc_error, Layout},
   any::type_name,
-  ffi::{c_void, CStr, CString},
+  ffi::{c_char, c_void, CStr, CString},
   fmt,
   ptr::NonNull,
 };
+use subtle::{Choice, ConstantTimeEq};
+use thiserror::Error;
+use zeroize::Zeroize;
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum Error<'a> {
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error("Failed to allocate memory for {0}")]
   FailedToAllocateMemory(&'static str),
-  FailedToGenerateObject(&'a str),
-}
 
-impl<'a> std::error::Error for Error<'a> {}
+  #[error("Failed to generate object for {0}")]
+  FailedToGenerateObject(&'static str),
 
-impl<'a> fmt::Display for Error<'a> {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    match self {
-      Error::FailedToAllocateMemory(target) => {
-        write!(f, "Failed to allocate memory for {target}")
-      }
-      Error::FailedToGenerateObject(target) => {
-        write!(f, "Failed to generate object for {target}")
-      }
-    }
-  }
+  #[error("Failed to generate object for {0}")]
+  FailedWithResultCode(&'static str, #[source] ResultCode),
+
+  #[error("Invalid bytes for {0}")]
+  InvalidBytes(&'static str),
 }
 
 #[derive(Eq, Debug)]
@@ -44,6 +45,15 @@ pub struct BlsctObj<T: BlsctSerde, U> {
   _u: std::marker::PhantomData<fn() -> U>,
 }
 
+// a BlsctObj exclusively owns the C object it points to. libblsct keeps no
+// references to the objects it returns and does not mutate them through the
+// const pointers handed out by `as_ptr`, so they can be moved and shared
+// across threads. chain-dependent calls are coordinated by chain::with_chain.
+// objects that borrow from another object (e.g. CTxIns) hold raw pointers
+// instead and stay !Send and !Sync.
+unsafe impl<T: BlsctSerde, U> Send for BlsctObj<T, U> {}
+unsafe impl<T: BlsctSerde, U> Sync for BlsctObj<T, U> {}
+
 // assumes that `ptr` points to readble memory of `size` bytes
 impl<T: BlsctSerde, U> PartialEq for BlsctObj<T, U> {
   fn eq(&self, other: &Self) -> bool {
@@ -58,11 +68,25 @@ impl<T: BlsctSerde, U> PartialEq for BlsctObj<T, U> {
   }
 }
 
+// compares the bytes without short-circuiting. only the sizes, which are
+// public for every object type, may leak through timing.
+impl<T: BlsctSerde, U> ConstantTimeEq for BlsctObj<T, U> {
+  fn ct_eq(&self, other: &Self) -> Choice {
+    if self.size != other.size {
+      return Choice::from(0);
+    }
+    self.as_slice().ct_eq(other.as_slice())
+  }
+}
+
+// objects owning C++ resources are serialized by libblsct, which can fail.
+// displaying them must not, so the failure is shown instead.
 impl<T: BlsctSerde, U> fmt::Display for BlsctObj<T, U> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    let bytes = bincode::serialize(self).map_err(|_| fmt::Error)?;
-    let hex = hex::encode(bytes);
-    write!(f, "{}", hex)
+    match bincode::serialize(self) {
+      Ok(bytes) => write!(f, "{}", hex::encode(bytes)),
+      Err(_) => write!(f, "<unserializable>"),
+    }
   }
 }
 
@@ -91,7 +115,7 @@ impl<T: BlsctSerde, U> BlsctObj<T, U> {
     }
   }
 
-  pub fn from_retval<'a>(rv: *mut BlsctRetVal) -> Result<Self, Error<'a>> {
+  pub fn from_retval(rv: *mut BlsctRetVal) -> Result<Self, Error> {
     // check if allocating memory for BlsctRetVal is failed
     if rv.is_null() {
       return Err(Error::FailedToAllocateMemory("BlsctRetVal"));
@@ -105,61 +129,59 @@ impl<T: BlsctSerde, U> BlsctObj<T, U> {
 
     // check if generating object is failed
     if result != 0 {
-      return Err(Error::FailedToGenerateObject(type_name::<Self>()));
+      return Err(Error::FailedWithResultCode(
+        type_name::<Self>(),
+        crate::error::result_code(result),
+      ));
     }
-    assert!(
-      !value.is_null(),
-      "the value is null altough result is 0. check code."
-    );
-
-    let ptr = NonNull::new(value as *mut u8).unwrap();
+    let Some(ptr) = NonNull::new(value as *mut u8) else {
+      return Err(Error::FailedToGenerateObject(type_name::<Self>()));
+    };
 
     Ok(Self {
       ptr,
       size: value_size,
       _t: std::marker::PhantomData,
       _u: std::marker::PhantomData,
-      deallocator: None,
+      deallocator: T::deallocator(),
     })
   }
 
+  // the getters of libblsct return null only if they fail to allocate
+  // the returned object, which is handled the same way as in std
   pub fn from_c_obj(c_obj: *mut U) -> Self {
-    let ptr = NonNull::new(c_obj as *mut u8).unwrap();
     let size = std::mem::size_of::<U>();
-    Self {
-      ptr,
-      size,
-      _t: std::marker::PhantomData,
-      _u: std::marker::PhantomData,
-      deallocator: None,
-    }
+    Self::new(non_null_or_oom(c_obj as *mut u8, size), size)
   }
 
   pub fn copy_from_c_obj(c_obj: *const U) -> Self {
     let size = std::mem::size_of::<U>();
-    let new_ptr = unsafe {
-      let buf = malloc(size) as *mut u8;
-      std::ptr::copy_nonoverlapping(c_obj as *const u8, buf, size);
-      NonNull::new(buf).unwrap()
-    };
-    Self {
-      ptr: new_ptr,
-      size,
-      _t: std::marker::PhantomData,
-      _u: std::marker::PhantomData,
-      deallocator: None,
+    match copy_to_malloced_buf(c_obj as *const u8, size) {
+      Ok(ptr) => Self::new(ptr, size),
+      Err(_) => handle_alloc_error(buf_layout(size)),
     }
   }
 
   pub fn from_c_obj_and_size(c_obj: *mut c_void, size: usize) -> Self {
-    let ptr = NonNull::new(c_obj as *mut u8).unwrap();
-    Self {
-      ptr,
-      size,
-      _t: std::marker::PhantomData,
-      _u: std::marker::PhantomData,
-      deallocator: None,
+    Self::new(non_null_or_oom(c_obj as *mut u8, size), size)
+  }
+
+  // plain buffers are copied as is. objects that own C++ resources are
+  // copied by a serialization round trip.
+  pub fn try_clone(&self) -> Result<Self, Error> {
+    if self.deallocator.is_none() {
+      let ptr = copy_to_malloced_buf(self.ptr.as_ptr(), self.size)?;
+      return Ok(Self::new(ptr, self.size));
+    }
+    let c_hex = unsafe { T::serialize(self.as_ptr() as *const u8, self.size) };
+    if c_hex.is_null() {
+      return Err(Error::FailedToAllocateMemory("serialized hex"));
+    }
+    let rv = unsafe { T::deserialize(c_hex) };
+    unsafe {
+      free_obj(c_hex as *mut c_void);
     }
+    Self::from_retval(rv)
   }
 
   #[inline]
@@ -171,48 +193,152 @@ impl<T: BlsctSerde, U> BlsctObj<T, U> {
   pub fn as_ptr(&self) -> *const U {
     self.ptr.as_ptr() as *const U
   }
+
+  // plain buffers are copied as is. objects that own C++ resources
+  // are serialized by libblsct.
+  pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+    if self.deallocator.is_none() {
+      return Ok(self.as_slice().to_vec());
+    }
+    let c_hex = unsafe { T::serialize(self.as_ptr() as *const u8, self.size) };
+    if c_hex.is_null() {
+      return Err(Error::FailedToAllocateMemory("serialized hex"));
+    }
+    let bytes = hex::decode(unsafe { CStr::from_ptr(c_hex) }.to_bytes());
+    unsafe {
+      wipe_c_str(c_hex as *mut c_char);
+      free_obj(c_hex as *mut c_void);
+    }
+    bytes.map_err(|_| Error::InvalidBytes(type_name::<T>()))
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+    // the deserializers of libblsct reject empty input only for some types
+    let is_valid_size = if T::VARIABLE_SIZE || T::deallocator().is_some() {
+      !bytes.is_empty()
+    } else {
+      bytes.len() == std::mem::size_of::<U>()
+    };
+    if !is_valid_size || !T::is_valid_bytes(bytes) {
+      return Err(Error::InvalidBytes(type_name::<T>()));
+    }
+    if T::PLAIN_BYTES && T::deallocator().is_none() {
+      let ptr = copy_to_malloced_buf(bytes.as_ptr(), bytes.len())?;
+      return Ok(Self::new(ptr, bytes.len()));
+    }
+    let mut hex_c_str =
+      CString::new(hex::encode(bytes)).map_err(|_| Error::InvalidBytes(type_name::<T>()))?;
+    let rv = unsafe { T::deserialize(hex_c_str.as_ptr()) };
+    hex_c_str.zeroize();
+    Self::from_retval(rv)
+  }
+
+  // assumes that `ptr` points to readble memory of `size` bytes
+  #[inline]
+  pub fn as_slice(&self) -> &[u8] {
+    unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.size) }
+  }
 }
 
-// expects T::serialize to take a pointer to a BLSCT object
-// and return a pointer to a c-string (null-terminated byte sequence)
+// human-readable formats get the bytes as a hex string
 impl<T: BlsctSerde, U> Serialize for BlsctObj<T, U> {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: Serializer,
   {
-    let c_hex = unsafe { T::serialize(self.as_ptr() as *const u8, self.size()) };
-    if c_hex.is_null() {
-      return Err(SerError::custom("Serialization failed. c_hex is null"));
-    }
-    let hex = unsafe { CStr::from_ptr(c_hex) }
-      .to_str()
-      .map_err(|e| SerError::custom(format!("Converting C-Str to String failed: {:?}", e)))?
-      .to_owned();
+    // the bytes may hold secret key material, so no copy is left behind
+    let mut bytes = self
+      .to_bytes()
+      .map_err(|e| SerError::custom(format!("Serialization failed: {e}")))?;
+    let res = if serializer.is_human_readable() {
+      let mut hex = hex::encode(&bytes);
+      let res = serializer.serialize_str(&hex);
+      hex.zeroize();
+      res
+    } else {
+      serializer.serialize_bytes(&bytes)
+    };
+    bytes.zeroize();
+    res
+  }
+}
 
-    unsafe {
-      free_obj(c_hex as *mut c_void);
-    }
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+  type Value = Vec<u8>;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "a byte array")
+  }
+
+  fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Self::Value, E> {
+    Ok(v.to_vec())
+  }
 
-    serializer.serialize_str(&hex)
+  fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+    Ok(v)
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+    while let Some(b) = seq.next_element()? {
+      bytes.push(b);
+    }
+    Ok(bytes)
   }
 }
 
-// expects T::deserialize to take a pointer to a c-string
-// and return a pointer to a BlsctRetVal
 impl<'de, T: BlsctSerde, U> Deserialize<'de> for BlsctObj<T, U> {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
     D: Deserializer<'de>,
   {
-    let hex: String = Deserialize::deserialize(deserializer)?;
-    let hex_c_str =
-      CString::new(hex).map_err(|_| DeError::custom("string contains interior NUL"))?;
+    let mut bytes = if deserializer.is_human_readable() {
+      let mut hex: String = Deserialize::deserialize(deserializer)?;
+      let bytes = hex::decode(&hex);
+      hex.zeroize();
+      bytes.map_err(|e| DeError::custom(format!("Invalid hex: {e}")))?
+    } else {
+      deserializer.deserialize_byte_buf(BytesVisitor)?
+    };
+    let res = BlsctObj::<T, U>::from_bytes(&bytes);
+    bytes.zeroize();
+    res.map_err(|e| DeError::custom(format!("Deserialization failed: {e}")))
+  }
+}
 
-    let rv = unsafe { T::deserialize(hex_c_str.as_ptr()) };
+// overwrites the buffer with zeros. objects with a dedicated deallocator
+// own C++ resources behind the pointer and are left untouched.
+impl<T: BlsctSerde, U> Zeroize for BlsctObj<T, U> {
+  fn zeroize(&mut self) {
+    if self.deallocator.is_none() {
+      let buf = unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.size) };
+      buf.zeroize();
+    }
+  }
+}
 
-    BlsctObj::<T, U>::from_retval(rv)
-      .map_err(|e| DeError::custom(format!("Deserialization failed: {:?}", e)))
+unsafe fn wipe_c_str(c_str: *mut c_char) {
+  let len = CStr::from_ptr(c_str).to_bytes().len();
+  slice::from_raw_parts_mut(c_str as *mut u8, len).zeroize();
+}
+
+fn buf_layout(size: usize) -> Layout {
+  Layout::array::<u8>(size).unwrap_or(Layout::new::<u8>())
+}
+
+fn non_null_or_oom(ptr: *mut u8, size: usize) -> NonNull<u8> {
+  NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(buf_layout(size)))
+}
+
+fn copy_to_malloced_buf(src: *const u8, size: usize) -> Result<NonNull<u8>, Error> {
+  let buf = unsafe { malloc(size) as *mut u8 };
+  let buf = NonNull::new(buf).ok_or(Error::FailedToAllocateMemory("BlsctObj"))?;
+  unsafe {
+    std::ptr::copy_nonoverlapping(src, buf.as_ptr(), size);
   }
+  Ok(buf)
 }
 
 impl<T: BlsctSerde, U> Drop for BlsctObj<T, U> {
@@ -225,3 +351,266 @@ impl<T: BlsctSerde, U> Drop for BlsctObj<T, U> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::Error;
+  use crate::{
+    ctx::CTx,
+    ctx_id::CTxId,
+    error::ResultCode,
+    ffi::TxOutputType,
+    hash_id::HashId,
+    keys::{double_public_key::DoublePublicKey, public_key::PublicKey},
+    out_point::OutPoint,
+    point::Point,
+    range_proof::RangeProof,
+    scalar::Scalar,
+    script::Script,
+    signature::Signature,
+    sub_addr::SubAddr,
+    sub_addr_id::SubAddrId,
+    token_id::TokenId,
+    tx_in::TxIn,
+    tx_out::TxOut,
+    unsigned_input::UnsignedInput,
+    unsigned_output::UnsignedOutput,
+    unsigned_transaction::UnsignedTransaction,
+    vector_predicate::VectorPredicate,
+  };
+  use rand::Rng;
+  use serde::de::DeserializeOwned;
+
+  fn garbage_hex_strs() -> Vec<String> {
+    let mut rng = rand::rng();
+    let random_bytes = (0..64).map(|_| rng.random()).collect::<Vec<u8>>();
+    vec![
+      String::new(),
+      "0".to_string(),
+      "abc".to_string(),
+      "zz".to_string(),
+      "not a hex string".to_string(),
+      "é".repeat(64),
+      "00".to_string(),
+      "ff".repeat(7),
+      "ff".repeat(1000),
+      hex::encode(random_bytes),
+    ]
+  }
+
+  fn garbage_bincode() -> Vec<Vec<u8>> {
+    let mut rng = rand::rng();
+    vec![
+      vec![],
+      vec![0xff; 3],
+      // a length prefix far larger than the data that follows
+      vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x30],
+      (0..256).map(|_| rng.random()).collect(),
+    ]
+  }
+
+  // any input must be rejected with an error or accepted, but never panic
+  fn feed_garbage<T: DeserializeOwned>() {
+    for hex in garbage_hex_strs() {
+      let bytes = bincode::serialize(&hex).unwrap();
+      let _ = bincode::deserialize::<T>(&bytes);
+    }
+    for bytes in garbage_bincode() {
+      let _ = bincode::deserialize::<T>(&bytes);
+    }
+  }
+
+  macro_rules! test_garbage {
+    ($($name:ident: $t:ty,)*) => {
+      $(
+        #[test]
+        fn $name() {
+          feed_garbage::<$t>();
+        }
+      )*
+    };
+  }
+
+  test_garbage! {
+    test_garbage_scalar: Scalar,
+    test_garbage_point: Point,
+    test_garbage_public_key: PublicKey,
+    test_garbage_double_public_key: DoublePublicKey,
+    test_garbage_token_id: TokenId,
+    test_garbage_tx_in: TxIn,
+    test_garbage_tx_out: TxOut,
+    test_garbage_ctx: CTx,
+    test_garbage_ctx_id: CTxId,
+    test_garbage_range_proof: RangeProof,
+    test_garbage_signature: Signature,
+    test_garbage_sub_addr: SubAddr,
+    test_garbage_sub_addr_id: SubAddrId,
+    test_garbage_out_point: OutPoint,
+    test_garbage_hash_id: HashId,
+    test_garbage_script: Script,
+    test_garbage_unsigned_input: UnsignedInput,
+    test_garbage_unsigned_output: UnsignedOutput,
+    test_garbage_unsigned_transaction: UnsignedTransaction,
+    test_garbage_vector_predicate: VectorPredicate,
+  }
+
+  fn garbage_bytes(valid: &[u8]) -> Vec<Vec<u8>> {
+    let mut rng = rand::rng();
+    let mut garbage = [1, 32, 48, 96, 256]
+      .into_iter()
+      .map(|n| (0..n).map(|_| rng.random()).collect())
+      .collect::<Vec<Vec<u8>>>();
+    garbage.push(valid[..valid.len() - 1].to_vec());
+    let mut flipped = valid.to_vec();
+    flipped[valid.len() / 2] ^= 0xff;
+    garbage.push(flipped);
+    garbage
+  }
+
+  // an object accepted from garbage must be safe to read
+  #[test]
+  fn test_garbage_getters() {
+    let nonce = Point::random().unwrap();
+    let token_id = TokenId::default().unwrap();
+    let rp = RangeProof::new(&vec![1], &nonce, "navio", &token_id).unwrap();
+    for bytes in garbage_bytes(&rp.to_bytes()) {
+      if let Ok(rp) = RangeProof::from_bytes(&bytes) {
+        let _ = rp.get_A();
+        let _ = rp.get_tau_x();
+        let _ = RangeProof::verify_proofs(&vec![rp]);
+      }
+    }
+
+    for bytes in garbage_bytes(&[1u8; 5]) {
+      if let Ok(vp) = VectorPredicate::from_bytes(&bytes) {
+        assert_eq!(vp, vp.clone());
+      }
+    }
+
+    let tx_in = TxIn::new(
+      1,
+      &Scalar::random().unwrap(),
+      &Scalar::random().unwrap(),
+      &token_id,
+      &OutPoint::new(&CTxId::random()).unwrap(),
+      false,
+      false,
+    )
+    .unwrap();
+    for bytes in garbage_bytes(&tx_in.to_bytes()) {
+      if let Ok(tx_in) = TxIn::from_bytes(&bytes) {
+        let _ = (tx_in.amount(), tx_in.is_staked_commitment(), tx_in.is_rbf());
+      }
+    }
+
+    let tx_out = TxOut::new(
+      &SubAddr::try_from(DoublePublicKey::random().unwrap()).unwrap(),
+      1,
+      "navio",
+      &token_id,
+      TxOutputType::Normal,
+      0,
+      false,
+      None,
+    )
+    .unwrap();
+    for bytes in garbage_bytes(&tx_out.to_bytes()) {
+      if let Ok(tx_out) = TxOut::from_bytes(&bytes) {
+        let _ = tx_out.memo();
+        let _ = (tx_out.output_type(), tx_out.subtract_fee_from_amount());
+      }
+    }
+
+    let sig = Signature::random();
+    for bytes in garbage_bytes(&sig.to_bytes()) {
+      if let Ok(scalar) = Scalar::from_bytes(&bytes) {
+        let _ = u64::from(scalar);
+      }
+      if let Ok(sig) = Signature::from_bytes(&bytes) {
+        assert_eq!(sig, sig.clone());
+      }
+    }
+  }
+
+  #[test]
+  fn test_wrong_size_tx_in_is_rejected() {
+    for hex in ["", "00", "ff".repeat(7).as_str()] {
+      let bytes = bincode::serialize(hex).unwrap();
+      assert!(bincode::deserialize::<TxIn>(&bytes).is_err());
+      assert!(bincode::deserialize::<TxOut>(&bytes).is_err());
+    }
+  }
+
+  #[test]
+  fn test_try_clone() {
+    let scalar = Scalar::random().unwrap();
+    assert_eq!(scalar.try_clone().unwrap(), scalar);
+
+    let point = Point::random().unwrap();
+    assert_eq!(point.try_clone().unwrap(), point);
+
+    // objects owning C++ resources are copied through libblsct
+    let ctx = crate::test_util::gen_ctx();
+    assert_eq!(ctx.try_clone().unwrap(), ctx);
+  }
+
+  #[test]
+  fn test_bytes_round_trip() {
+    let scalar = Scalar::random().unwrap();
+    let bytes = scalar.to_bytes();
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(Scalar::from_bytes(&bytes).unwrap(), scalar);
+
+    let point = Point::random().unwrap();
+    assert_eq!(Point::from_bytes(&point.to_bytes()).unwrap(), point);
+
+    let dpk = DoublePublicKey::random().unwrap();
+    assert_eq!(DoublePublicKey::from_bytes(&dpk.to_bytes()).unwrap(), dpk);
+  }
+
+  #[test]
+  fn test_wrong_size_bytes_are_rejected() {
+    for bytes in [vec![], vec![0u8; 31], vec![0u8; 33]] {
+      assert!(matches!(
+        Scalar::from_bytes(&bytes),
+        Err(Error::InvalidBytes(_))
+      ));
+    }
+    assert!(RangeProof::from_bytes(&[]).is_err());
+  }
+
+  #[test]
+  fn test_result_code_is_kept() {
+    let rv = unsafe { crate::ffi::err_bool(crate::ffi::BLSCT_BAD_SIZE) };
+    assert!(matches!(
+      super::BlsctObj::<Scalar, Scalar>::from_retval(rv),
+      Err(Error::FailedWithResultCode(_, ResultCode::BadSize))
+    ));
+  }
+
+  #[test]
+  fn test_invalid_point_bytes_are_rejected() {
+    let bytes = vec![0xff; 48];
+    assert!(Point::from_bytes(&bytes).is_err());
+    assert!(PublicKey::from_bytes(&bytes).is_err());
+  }
+
+  #[test]
+  fn test_serde_formats() {
+    let scalar = Scalar::random().unwrap();
+
+    // a length prefix followed by the raw bytes
+    let bin = bincode::serialize(&scalar).unwrap();
+    assert_eq!(bin.len(), 8 + 32);
+    assert_eq!(&bin[8..], scalar.to_bytes().as_slice());
+    assert_eq!(bincode::deserialize::<Scalar>(&bin).unwrap(), scalar);
+
+    let json = serde_json::to_string(&scalar).unwrap();
+    assert_eq!(json, format!("\"{}\"", hex::encode(scalar.to_bytes())));
+    assert_eq!(serde_json::from_str::<Scalar>(&json).unwrap(), scalar);
+
+    // binary formats hold the raw bytes only, never the hex string
+    let hex_bin = bincode::serialize(&hex::encode(scalar.to_bytes())).unwrap();
+    assert!(bincode::deserialize::<Scalar>(&hex_bin).is_err());
+  }
+}
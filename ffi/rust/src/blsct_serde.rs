@@ -1,7 +1,24 @@
 use crate::ffi::BlsctRetVal;
-use std::os::raw::c_char;
+use std::{ffi::c_void, os::raw::c_char};
 
 pub trait BlsctSerde {
   unsafe fn serialize(ptr: *const u8, size: usize) -> *const c_char;
   unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal;
+
+  // objects allocated with `new` on the C++ side need a dedicated deallocator
+  fn deallocator() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    None
+  }
+
+  // objects such as range proofs have no fixed size
+  const VARIABLE_SIZE: bool = false;
+
+  // checks the raw bytes an object is about to be built from
+  fn is_valid_bytes(_bytes: &[u8]) -> bool {
+    true
+  }
+
+  // true if any bytes of the right size that pass is_valid_bytes make a
+  // valid object. the bytes of other objects are checked by deserialize.
+  const PLAIN_BYTES: bool = false;
 }
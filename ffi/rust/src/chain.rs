@@ -1,15 +1,24 @@
 use crate::ffi::{get_blsct_chain, set_blsct_chain};
-use std::os::raw::c_int;
+use serde::{Deserialize, Serialize};
+use std::{
+  cell::Cell,
+  marker::PhantomData,
+  os::raw::c_int,
+  sync::{Condvar, Mutex, MutexGuard},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+pub const COIN: u64 = 100000000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
 pub enum Error {
   #[error("Invalid chain ID: {0}")]
-  InvalidChainId(u8),
+  InvalidChainId(c_int),
 }
 
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Chain {
   Mainnet = 0,
   Testnet = 1,
@@ -17,19 +26,216 @@ pub enum Chain {
   Regtest = 3,
 }
 
+thread_local! {
+  static SCOPED_CHAIN: Cell<Option<Chain>> = const { Cell::new(None) };
+}
+
 impl Chain {
-  pub fn get() -> Chain {
-    match unsafe { get_blsct_chain() } {
-      0 => Chain::Mainnet,
-      1 => Chain::Testnet,
-      2 => Chain::Signet,
-      3 => Chain::Regtest,
-      n => panic!("Unexpected chain ID {n}. Check code."),
+  // returns the chain of the innermost scope on this thread,
+  // falling back to the process-wide chain
+  pub fn get() -> Result<Chain, Error> {
+    match SCOPED_CHAIN.get() {
+      Some(chain) => Ok(chain),
+      None => {
+        // while calls are running another chain may be selected on the C side
+        let state = CHAIN_LOCK.state();
+        if state.users > 0 {
+          chain_of_id(state.global_id)
+        } else {
+          global_chain()
+        }
+      }
     }
   }
 
+  // sets the process-wide chain used by threads without a scope.
+  // waits for the running chain-dependent calls to return.
   pub fn set(chain: Chain) {
-    unsafe { set_blsct_chain(chain as u8 as c_int) }
+    let _state = CHAIN_LOCK.exclusive();
+    set_global_chain(chain);
+  }
+
+  // makes `self` the chain of this thread until the returned guard is dropped
+  pub fn scope(self) -> ChainGuard {
+    let prev_chain = SCOPED_CHAIN.replace(Some(self));
+    ChainGuard {
+      prev_chain,
+      _not_send: PhantomData,
+    }
+  }
+
+  pub fn min_stake(&self) -> u64 {
+    match self {
+      Chain::Mainnet | Chain::Testnet | Chain::Signet => 10000 * COIN,
+      Chain::Regtest => 100 * COIN,
+    }
+  }
+}
+
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct ChainGuard {
+  prev_chain: Option<Chain>,
+  // the scope is bound to the thread that created it
+  _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ChainGuard {
+  fn drop(&mut self) {
+    SCOPED_CHAIN.set(self.prev_chain);
+  }
+}
+
+// the chain selected on the C side. the caller holds CHAIN_LOCK.
+fn global_chain() -> Result<Chain, Error> {
+  chain_of_id(unsafe { get_blsct_chain() })
+}
+
+fn chain_of_id(id: c_int) -> Result<Chain, Error> {
+  u8::try_from(id)
+    .ok()
+    .and_then(|n| Chain::try_from(n).ok())
+    .ok_or(Error::InvalidChainId(id))
+}
+
+fn chain_id(chain: Chain) -> c_int {
+  chain as u8 as c_int
+}
+
+fn set_global_chain(chain: Chain) {
+  unsafe { set_blsct_chain(chain_id(chain)) }
+}
+
+// libblsct reads the chain from a C global. every chain-dependent call
+// goes through with_chain so that it sees the chain of the calling thread.
+// calls on the chain that is selected run side by side, and another chain
+// is selected only once all of them have returned.
+static CHAIN_LOCK: ChainLock = ChainLock {
+  state: Mutex::new(LockState {
+    users: 0,
+    waiting: 0,
+    selected_id: 0,
+    global_id: 0,
+  }),
+  released: Condvar::new(),
+};
+
+struct ChainLock {
+  state: Mutex<LockState>,
+  released: Condvar,
+}
+
+struct LockState {
+  // the number of calls running on the selected chain
+  users: usize,
+  // the number of threads waiting for the calls to return or for their chain
+  // to be selected. calls that have not waited do not join while any are
+  // waiting so that those are not starved.
+  waiting: usize,
+  // raw IDs of the chain selected for the running calls and of the chain that
+  // was selected before, which is restored as is so that an unknown chain is
+  // never an error here. both are only valid while users > 0.
+  selected_id: c_int,
+  global_id: c_int,
+}
+
+impl ChainLock {
+  fn state(&self) -> MutexGuard<'_, LockState> {
+    self.state.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  // waits until no call is running. the chain on the C side can be changed
+  // while the returned guard is held.
+  fn exclusive(&self) -> MutexGuard<'_, LockState> {
+    self.wait_for_release(self.state())
+  }
+
+  fn wait_for_release<'a>(
+    &'a self,
+    mut state: MutexGuard<'a, LockState>,
+  ) -> MutexGuard<'a, LockState> {
+    if state.users > 0 {
+      state.waiting += 1;
+      while state.users > 0 {
+        state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+      }
+      state.waiting -= 1;
+    }
+    state
+  }
+
+  // `chain` is None for the process-wide chain
+  fn acquire(&self, chain: Option<Chain>) -> ChainLockGuard<'_> {
+    let mut state = self.state();
+    let mut waited = false;
+    loop {
+      if state.users == 0 {
+        let global_id = unsafe { get_blsct_chain() };
+        let selected_id = chain.map_or(global_id, chain_id);
+        if selected_id != global_id {
+          unsafe { set_blsct_chain(selected_id) };
+        }
+        state.selected_id = selected_id;
+        state.global_id = global_id;
+        // the waiting calls on the same chain can join now
+        self.released.notify_all();
+        break;
+      }
+      let joins = chain.map_or(state.global_id, chain_id) == state.selected_id;
+      if joins && (waited || state.waiting == 0) {
+        break;
+      }
+      if !waited {
+        state.waiting += 1;
+        waited = true;
+      }
+      state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+    }
+    if waited {
+      state.waiting -= 1;
+    }
+    state.users += 1;
+    ChainLockGuard { lock: self }
+  }
+}
+
+struct ChainLockGuard<'a> {
+  lock: &'a ChainLock,
+}
+
+impl Drop for ChainLockGuard<'_> {
+  fn drop(&mut self) {
+    let mut state = self.lock.state();
+    state.users -= 1;
+    if state.users == 0 {
+      if state.selected_id != state.global_id {
+        unsafe { set_blsct_chain(state.global_id) };
+      }
+      self.lock.released.notify_all();
+    }
+  }
+}
+
+// runs `f` with `chain` selected on the C side and restores the previous chain afterwards.
+// `f` must not call with_chain again.
+pub(crate) fn with_chain<T>(chain: Chain, f: impl FnOnce() -> T) -> T {
+  let _guard = CHAIN_LOCK.acquire(Some(chain));
+  f()
+}
+
+// the chain of the innermost scope on this thread, if any
+#[cfg(feature = "async")]
+pub(crate) fn scoped_chain() -> Option<Chain> {
+  SCOPED_CHAIN.get()
+}
+
+// without a scope on this thread `f` runs on the process-wide chain
+pub(crate) fn with_current_chain<T>(f: impl FnOnce() -> T) -> T {
+  match SCOPED_CHAIN.get() {
+    Some(chain) => with_chain(chain, f),
+    None => {
+      let _guard = CHAIN_LOCK.acquire(None);
+      f()
+    }
   }
 }
 
@@ -42,7 +248,7 @@ impl TryFrom<u8> for Chain {
       1 => Ok(Chain::Testnet),
       2 => Ok(Chain::Signet),
       3 => Ok(Chain::Regtest),
-      n => Err(Error::InvalidChainId(n)),
+      n => Err(Error::InvalidChainId(n.into())),
     }
   }
 }
@@ -50,6 +256,14 @@ impl TryFrom<u8> for Chain {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use std::{
+    sync::{
+      atomic::{AtomicBool, Ordering},
+      mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+  };
 
   #[test]
   fn test_get_set_chain() {
@@ -60,9 +274,109 @@ mod tests {
       Chain::Regtest,
     ] {
       Chain::set(exp_chain);
-      assert_eq!(exp_chain, Chain::get());
+      assert_eq!(exp_chain, Chain::get().unwrap());
     }
     // reset to mainnet
     Chain::set(Chain::Mainnet);
   }
+
+  #[test]
+  fn test_with_chain() {
+    let chain = with_chain(Chain::Regtest, global_chain);
+    assert_eq!(chain, Ok(Chain::Regtest));
+  }
+
+  #[test]
+  fn test_scope() {
+    let global = Chain::get();
+    {
+      let _testnet = Chain::Testnet.scope();
+      assert_eq!(Chain::get(), Ok(Chain::Testnet));
+      {
+        let _regtest = Chain::Regtest.scope();
+        assert_eq!(Chain::get(), Ok(Chain::Regtest));
+        assert_eq!(with_current_chain(global_chain), Ok(Chain::Regtest));
+      }
+      assert_eq!(Chain::get(), Ok(Chain::Testnet));
+    }
+    assert_eq!(Chain::get(), global);
+  }
+
+  #[test]
+  fn test_other_chain_waits() {
+    let (tx, rx) = mpsc::channel();
+    let done = Arc::new(AtomicBool::new(false));
+    let handle = {
+      let done = done.clone();
+      thread::spawn(move || {
+        with_chain(Chain::Regtest, || {
+          tx.send(()).unwrap();
+          thread::sleep(Duration::from_millis(100));
+          done.store(true, Ordering::SeqCst);
+        })
+      })
+    };
+    rx.recv().unwrap();
+    // the chain of the other thread is never seen
+    assert_eq!(with_chain(Chain::Testnet, global_chain), Ok(Chain::Testnet));
+    assert!(done.load(Ordering::SeqCst));
+    handle.join().unwrap();
+  }
+
+  #[test]
+  fn test_same_chain_is_shared() {
+    let (entered_tx, entered_rx) = mpsc::channel();
+    let (tx, rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+      with_chain(Chain::Signet, || {
+        entered_tx.send(()).unwrap();
+        rx.recv_timeout(Duration::from_secs(5))
+      })
+    });
+    entered_rx.recv().unwrap();
+    // runs while the other thread is still inside with_chain
+    with_chain(Chain::Signet, || {
+      assert_eq!(global_chain(), Ok(Chain::Signet));
+      tx.send(()).unwrap();
+    });
+    assert_eq!(handle.join().unwrap(), Ok(()));
+  }
+
+  #[test]
+  fn test_chain_is_released_after_panic() {
+    let res = std::panic::catch_unwind(|| with_chain(Chain::Regtest, || panic!()));
+    assert!(res.is_err());
+    // another chain can still be selected
+    assert_eq!(with_chain(Chain::Testnet, global_chain), Ok(Chain::Testnet));
+  }
+
+  #[test]
+  fn test_scope_is_per_thread() {
+    let _regtest = Chain::Regtest.scope();
+    let other = thread::spawn(|| SCOPED_CHAIN.get()).join().unwrap();
+    assert_eq!(other, None);
+    assert_eq!(Chain::get(), Ok(Chain::Regtest));
+  }
+
+  #[test]
+  fn test_unknown_chain_id() {
+    let _state = CHAIN_LOCK.exclusive();
+    let prev_chain_id = unsafe { get_blsct_chain() };
+    unsafe { set_blsct_chain(42) };
+    let res = global_chain();
+    unsafe { set_blsct_chain(prev_chain_id) };
+    assert_eq!(res, Err(Error::InvalidChainId(42)));
+
+    unsafe { set_blsct_chain(-1) };
+    let res = global_chain();
+    unsafe { set_blsct_chain(prev_chain_id) };
+    assert_eq!(res, Err(Error::InvalidChainId(-1)));
+  }
+
+  #[test]
+  fn test_min_stake() {
+    assert_eq!(Chain::Mainnet.min_stake(), 10000 * COIN);
+    assert_eq!(Chain::Testnet.min_stake(), 10000 * COIN);
+    assert_eq!(Chain::Regtest.min_stake(), 100 * COIN);
+  }
 }
@@ -0,0 +1,325 @@
+use crate::{
+  ctx::CTx,
+  out_point::OutPoint,
+  rpc::{self, RpcClient, RpcErrorCode},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("No block at height {0}")]
+  BlockNotFound(u32),
+
+  #[error("The chain has no blocks")]
+  NoBlocks,
+
+  #[error("Invalid block: {0}")]
+  InvalidBlock(String),
+
+  #[error("Failed to read the block: {0}")]
+  Io(#[from] std::io::Error),
+
+  #[error("RPC failed: {0}")]
+  Rpc(#[from] rpc::Error),
+}
+
+// a block in the shape of the result of getblock with verbosity 2, keeping
+// only what scanning needs
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Block {
+  pub hash: String,
+  pub height: u32,
+  pub tx: Vec<BlockTx>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BlockTx {
+  pub txid: String,
+  pub hex: String,
+  #[serde(default)]
+  pub vout: Vec<BlockTxOut>,
+}
+
+// navio-core reports the hash of each output, which is the out point
+// spending it refers to. it is missing from the outputs of nodes that do
+// not report it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BlockTxOut {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub hash: Option<String>,
+}
+
+fn invalid_block(e: impl ToString) -> Error {
+  Error::InvalidBlock(e.to_string())
+}
+
+// hashes are shown in reverse byte order like txids
+fn hash_to_hex(bytes: &[u8]) -> String {
+  hex::encode(bytes.iter().rev().copied().collect::<Vec<u8>>())
+}
+
+fn hash_from_hex(s: &str) -> Result<Vec<u8>, Error> {
+  let mut bytes = hex::decode(s).map_err(invalid_block)?;
+  bytes.reverse();
+  Ok(bytes)
+}
+
+impl BlockTx {
+  pub fn new(ctx: &CTx, out_points: &[OutPoint]) -> Result<Self, Error> {
+    Ok(BlockTx {
+      txid: ctx.get_ctx_id().map_err(invalid_block)?.to_hex(),
+      hex: hex::encode(ctx.to_bytes().map_err(invalid_block)?),
+      vout: out_points
+        .iter()
+        .map(|x| BlockTxOut {
+          hash: Some(hash_to_hex(&x.to_bytes())),
+        })
+        .collect(),
+    })
+  }
+
+  pub fn to_ctx(&self) -> Result<CTx, Error> {
+    let bytes = hex::decode(&self.hex).map_err(invalid_block)?;
+    CTx::from_bytes(&bytes).map_err(invalid_block)
+  }
+
+  // the out points the node reported, or the ones computed from the CTx
+  // if it reported none
+  pub fn out_points(&self) -> Result<Vec<OutPoint>, Error> {
+    let hashes: Option<Vec<&String>> = self.vout.iter().map(|x| x.hash.as_ref()).collect();
+    match hashes {
+      Some(hashes) if !hashes.is_empty() => hashes
+        .into_iter()
+        .map(|x| OutPoint::from_bytes(&hash_from_hex(x)?).map_err(invalid_block))
+        .collect(),
+      _ => self.to_ctx()?.out_points().map_err(invalid_block),
+    }
+  }
+}
+
+pub trait ChainSource {
+  fn tip_height(&self) -> Result<u32, Error>;
+
+  fn block_at(&self, height: u32) -> Result<Block, Error>;
+
+  fn transactions_at(&self, height: u32) -> Result<Vec<CTx>, Error> {
+    self
+      .block_at(height)?
+      .tx
+      .iter()
+      .map(BlockTx::to_ctx)
+      .collect()
+  }
+}
+
+// reads blocks from a directory holding a <height>.json file per block
+#[derive(Debug, Clone)]
+pub struct DirSource {
+  dir: PathBuf,
+}
+
+impl DirSource {
+  pub fn new(dir: impl AsRef<Path>) -> Self {
+    DirSource {
+      dir: dir.as_ref().to_path_buf(),
+    }
+  }
+
+  fn block_path(&self, height: u32) -> PathBuf {
+    self.dir.join(format!("{height}.json"))
+  }
+
+  pub fn write_block(&self, block: &Block) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(block).map_err(invalid_block)?;
+    fs::write(self.block_path(block.height), json)?;
+    Ok(())
+  }
+}
+
+impl ChainSource for DirSource {
+  fn tip_height(&self) -> Result<u32, Error> {
+    let mut tip = None;
+    for entry in fs::read_dir(&self.dir)? {
+      let path = entry?.path();
+      if path.extension().and_then(|x| x.to_str()) != Some("json") {
+        continue;
+      }
+      let height = path
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .and_then(|x| x.parse::<u32>().ok());
+      tip = tip.max(height);
+    }
+    tip.ok_or(Error::NoBlocks)
+  }
+
+  fn block_at(&self, height: u32) -> Result<Block, Error> {
+    let json = match fs::read_to_string(self.block_path(height)) {
+      Ok(json) => json,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Err(Error::BlockNotFound(height)),
+      Err(e) => return Err(e.into()),
+    };
+    let block: Block = serde_json::from_str(&json).map_err(invalid_block)?;
+    if block.height != height {
+      return Err(Error::InvalidBlock(format!(
+        "{}.json holds the block at height {}",
+        height, block.height
+      )));
+    }
+    Ok(block)
+  }
+}
+
+// fetches blocks from a navio-core node
+#[derive(Debug, Clone)]
+pub struct RpcSource {
+  client: RpcClient,
+}
+
+impl RpcSource {
+  pub fn new(client: RpcClient) -> Self {
+    RpcSource { client }
+  }
+}
+
+impl ChainSource for RpcSource {
+  fn tip_height(&self) -> Result<u32, Error> {
+    Ok(self.client.call("getblockcount", json!([]))?)
+  }
+
+  fn block_at(&self, height: u32) -> Result<Block, Error> {
+    let hash: String = match self.client.call("getblockhash", json!([height])) {
+      Ok(hash) => hash,
+      // returned for a height above the tip
+      Err(rpc::Error::Rpc {
+        code: RpcErrorCode::InvalidParameter,
+        ..
+      }) => return Err(Error::BlockNotFound(height)),
+      Err(e) => return Err(e.into()),
+    };
+    Ok(self.client.call("getblock", json!([hash, 2]))?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ctx_id::CTxId,
+    test_util::{gen_ctx, serve_blocks},
+  };
+
+  fn gen_blocks() -> Vec<Block> {
+    (0..3)
+      .map(|height| {
+        let ctx = gen_ctx();
+        Block {
+          hash: hex::encode(CTxId::random().to_bytes()),
+          height,
+          tx: vec![BlockTx::new(&ctx, &ctx.out_points().unwrap()).unwrap()],
+        }
+      })
+      .collect()
+  }
+
+  fn gen_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blocks-{}", rand::random::<u64>()));
+    fs::create_dir(&dir).unwrap();
+    dir
+  }
+
+  fn assert_source(source: &dyn ChainSource, blocks: &[Block]) {
+    assert_eq!(source.tip_height().unwrap(), 2);
+    for block in blocks {
+      assert_eq!(&source.block_at(block.height).unwrap(), block);
+      let ctxs = source.transactions_at(block.height).unwrap();
+      assert_eq!(ctxs, vec![block.tx[0].to_ctx().unwrap()]);
+    }
+    assert!(matches!(source.block_at(3), Err(Error::BlockNotFound(3))));
+  }
+
+  #[test]
+  fn test_block_tx() {
+    let block = &gen_blocks()[0];
+    let ctx = block.tx[0].to_ctx().unwrap();
+    let out_points = block.tx[0].out_points().unwrap();
+    assert_eq!(BlockTx::new(&ctx, &out_points).unwrap(), block.tx[0]);
+  }
+
+  // a getblock response with verbosity 2 in the shape navio-core returns.
+  // its transaction is the one of testdata/ctx_out_points.json.
+  const GETBLOCK: &str = include_str!("../testdata/getblock.json");
+  const CTX_OUT_POINTS: &str = include_str!("../testdata/ctx_out_points.json");
+
+  #[test]
+  fn test_getblock_response() {
+    let block: Block = serde_json::from_str(GETBLOCK).unwrap();
+    assert_eq!(block.height, 1042);
+    assert_eq!(block.tx.len(), 1);
+
+    let vector: serde_json::Value = serde_json::from_str(CTX_OUT_POINTS).unwrap();
+    assert_eq!(block.tx[0].hex, vector["hex"]);
+    let out_points = block.tx[0].out_points().unwrap();
+    let hashes: Vec<String> = out_points
+      .iter()
+      .map(|x| hash_to_hex(&x.to_bytes()))
+      .collect();
+    let expected: Vec<&str> = vector["vout"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|x| x["hash"].as_str().unwrap())
+      .collect();
+    assert_eq!(hashes, expected);
+  }
+
+  #[test]
+  fn test_out_points_without_vout() {
+    let ctx = gen_ctx();
+    let out_points = ctx.out_points().unwrap();
+    let mut block_tx = BlockTx::new(&ctx, &out_points).unwrap();
+
+    // computed from the CTx when the node reports no hashes
+    block_tx.vout.clear();
+    let json = serde_json::to_string(&block_tx).unwrap();
+    let mut block_tx: BlockTx = serde_json::from_str(&json.replace(",\"vout\":[]", "")).unwrap();
+    assert!(block_tx.vout.is_empty());
+    assert_eq!(block_tx.out_points().unwrap(), out_points);
+
+    block_tx.vout = vec![BlockTxOut { hash: None }; out_points.len()];
+    assert_eq!(block_tx.out_points().unwrap(), out_points);
+  }
+
+  #[test]
+  fn test_dir_source() {
+    let dir = gen_dir();
+    let source = DirSource::new(&dir);
+    assert!(matches!(source.tip_height(), Err(Error::NoBlocks)));
+
+    let blocks = gen_blocks();
+    for block in &blocks {
+      source.write_block(block).unwrap();
+    }
+    assert_source(&source, &blocks);
+
+    fs::rename(dir.join("2.json"), dir.join("1.json")).unwrap();
+    let res = source.block_at(1);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(matches!(res, Err(Error::InvalidBlock(_))));
+  }
+
+  #[test]
+  fn test_rpc_source() {
+    let blocks = gen_blocks();
+    let server = serve_blocks(blocks.clone());
+    let source = RpcSource::new(RpcClient::new(&server.url()).unwrap());
+    assert_source(&source, &blocks);
+  }
+}
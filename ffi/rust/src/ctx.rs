@@ -1,16 +1,22 @@
+#[cfg(feature = "async")]
+use crate::blocking_pool::BlockingPool;
 use crate::{
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
+  chain::with_current_chain,
   ctx_id::CTxId,
   ctx_ins::CTxIns,
   ctx_outs::CTxOuts,
+  ctx_ser,
+  ctx_view::CTxView,
   ffi::{
     add_to_tx_in_vec, add_to_tx_out_vec, build_ctx, create_tx_in_vec, create_tx_out_vec,
     delete_ctx, delete_tx_in_vec, delete_tx_out_vec, deserialize_ctx, deserialize_ctx_id, free_obj,
     get_ctx_id, get_ctx_ins, get_ctx_outs, serialize_ctx, BlsctCTx, BlsctCTxId, BlsctRetVal,
     BLSCT_IN_AMOUNT_ERROR, BLSCT_OUT_AMOUNT_ERROR,
   },
-  macros::{impl_clone, impl_display},
+  macros::{impl_display, impl_try_clone},
+  out_point::OutPoint,
   tx_in::TxIn,
   tx_out::TxOut,
 };
@@ -47,8 +53,8 @@ pub struct CTx {
   obj: BlsctObj<CTx, BlsctCTx>,
 }
 
+impl_try_clone!(CTx);
 impl_display!(CTx);
-impl_clone!(CTx);
 
 impl CTx {
   pub fn new(tx_ins: &Vec<TxIn>, tx_outs: &Vec<TxOut>) -> Result<Self, Error> {
@@ -62,7 +68,7 @@ impl CTx {
       for tx_out in tx_outs {
         add_to_tx_out_vec(vp_tx_outs, tx_out.value());
       }
-      let rv = build_ctx(vp_tx_ins, vp_tx_outs);
+      let rv = with_current_chain(|| build_ctx(vp_tx_ins, vp_tx_outs));
       if rv.is_null() {
         delete_tx_in_vec(vp_tx_ins);
         delete_tx_out_vec(vp_tx_outs);
@@ -76,7 +82,10 @@ impl CTx {
       };
 
       if (*rv).result == 0 {
-        let vp_ctx = NonNull::<u8>::new((*rv).ctx as *mut u8).unwrap();
+        let Some(vp_ctx) = NonNull::<u8>::new((*rv).ctx as *mut u8) else {
+          clean_up();
+          return Err(Error::FailedToAllocateMemory);
+        };
         let obj = BlsctObj::<CTx, BlsctCTx>::new_with_deallocator(vp_ctx, 0, Some(delete_ctx)); // size will not be used
 
         clean_up();
@@ -101,7 +110,7 @@ impl CTx {
     }
   }
 
-  pub fn get_ctx_id<'a>(&self) -> Result<CTxId, blsct_obj::Error<'a>> {
+  pub fn get_ctx_id(&self) -> Result<CTxId, blsct_obj::Error> {
     let rv = unsafe {
       let c_str_hex = get_ctx_id(self.value());
       deserialize_ctx_id(c_str_hex)
@@ -110,14 +119,42 @@ impl CTx {
     Ok(obj.into())
   }
 
+  // serialized by libblsct since a CTx is a C++ object
+  pub fn to_bytes(&self) -> Result<Vec<u8>, blsct_obj::Error> {
+    self.obj.to_bytes()
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, blsct_obj::Error> {
+    Ok(BlsctObj::from_bytes(bytes)?.into())
+  }
+
+  pub fn to_view(&self) -> Result<CTxView, crate::error::Error> {
+    CTxView::new(self)
+  }
+
+  // pretty-printed like decoderawtransaction
+  pub fn to_json(&self) -> Result<String, crate::error::Error> {
+    serde_json::to_string_pretty(&self.to_view()?)
+      .map_err(|_| blsct_obj::Error::FailedToGenerateObject("CTx JSON").into())
+  }
+
   pub fn get_ctx_ins(&self) -> CTxIns {
     let obj = unsafe { get_ctx_ins(self.value()) };
     obj.into()
   }
 
-  pub fn get_ctx_outs(&self) -> CTxOuts {
+  pub fn get_ctx_outs(&self) -> CTxOuts<'_> {
     let obj = unsafe { get_ctx_outs(self.value()) };
-    obj.into()
+    CTxOuts::new(obj, self)
+  }
+
+  // the out points of the outputs in order
+  pub fn out_points(&self) -> Result<Vec<OutPoint>, blsct_obj::Error> {
+    let ctx_bytes = self.to_bytes()?;
+    let outs = ctx_ser::split_outs(&ctx_bytes)
+      .filter(|x| x.len() == self.get_ctx_outs().len())
+      .ok_or(blsct_obj::Error::InvalidBytes("CTx"))?;
+    outs.into_iter().map(ctx_ser::out_point).collect()
   }
 
   // not using impl_void_ptr_value!() to return *mut c_void
@@ -127,6 +164,20 @@ impl CTx {
   }
 }
 
+#[cfg(feature = "async")]
+impl CTx {
+  // builds the transaction on the blocking pool in the chain scope of the caller
+  pub async fn new_async(
+    tx_ins: Vec<TxIn>,
+    tx_outs: Vec<TxOut>,
+  ) -> Result<Self, crate::error::Error> {
+    let pool = BlockingPool::global()?;
+    pool
+      .spawn(move || Ok(Self::new(&tx_ins, &tx_outs)?))
+      .await?
+  }
+}
+
 impl BlsctSerde for CTx {
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_ctx(ptr as *mut c_void)
@@ -135,6 +186,10 @@ impl BlsctSerde for CTx {
   unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
     deserialize_ctx(hex)
   }
+
+  fn deallocator() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    Some(delete_ctx)
+  }
 }
 
 impl From<BlsctObj<CTx, BlsctCTx>> for CTx {
@@ -155,24 +210,30 @@ mod tests {
   use crate::{
     amount_recovery_req::AmountRecoveryReq,
     ffi::{get_ctx_ins_size, get_ctx_outs_size},
-    initializer::init,
     keys::{double_public_key::DoublePublicKey, public_key::PublicKey},
     range_proof::RangeProof,
     scalar::Scalar,
     sub_addr::SubAddr,
-    test_util::{gen_ctx, gen_ctx_actual},
+    test_util::{gen_ctx, gen_ctx_actual, gen_ctx_spending},
   };
+  use std::{sync::Arc, thread};
 
   #[test]
   fn test_get_ctx_id() {
-    init();
     let ctx = gen_ctx();
     let _ = ctx.get_ctx_id();
   }
 
+  #[test]
+  fn test_display() {
+    // the hex serialization, JSON is left to to_json
+    let ctx = gen_ctx();
+    let hex = hex::encode(bincode::serialize(&ctx).unwrap());
+    assert_eq!(ctx.to_string(), format!("CTx({hex})"));
+  }
+
   #[test]
   fn test_get_ctx_ins() {
-    init();
     let ctx = gen_ctx();
     let ctx_ins = ctx.get_ctx_ins();
     let ctx_ins_size = unsafe { get_ctx_ins_size(ctx_ins.value()) };
@@ -181,20 +242,42 @@ mod tests {
 
   #[test]
   fn test_get_ctx_outs() {
-    init();
     let ctx = gen_ctx();
     let ctx_outs = ctx.get_ctx_outs();
     let ctx_outs_size = unsafe { get_ctx_outs_size(ctx_outs.value()) };
     assert_eq!(ctx_outs_size, 3);
   }
 
+  #[test]
+  fn test_out_points() {
+    let ctx = gen_ctx();
+    let out_points = ctx.out_points().unwrap();
+    let ctx_outs = ctx.get_ctx_outs();
+    assert_eq!(out_points.len(), ctx_outs.len());
+    for (i, out_point) in out_points.iter().enumerate() {
+      assert_eq!(&ctx_outs.at(i).unwrap().out_point().unwrap(), out_point);
+    }
+    assert_ne!(out_points[0], out_points[1]);
+
+    let b = CTx::from_bytes(&ctx.to_bytes().unwrap()).unwrap();
+    assert_eq!(b.out_points().unwrap(), out_points);
+
+    let spending = gen_ctx_spending(&out_points[1]);
+    assert_eq!(
+      spending
+        .get_ctx_ins()
+        .spent_out_points(&out_points)
+        .unwrap(),
+      vec![Some(&out_points[1])]
+    );
+  }
+
   #[test]
   fn test_amount_recovery() {
-    init();
     let pk_view_key = PublicKey::random().unwrap();
     let pk_spend_key = PublicKey::random().unwrap();
     let dpk = DoublePublicKey::from_view_and_spend_keys(&pk_view_key, &pk_spend_key).unwrap();
-    let destination: SubAddr = dpk.into();
+    let destination: SubAddr = dpk.try_into().unwrap();
     let blinding_key = Scalar::random().unwrap();
     let out_amount = 12345;
     let msg = "space_x";
@@ -215,12 +298,65 @@ mod tests {
     assert_eq!(amounts[0].msg, msg);
   }
 
+  fn assert_send_sync<T: Send + Sync>() {}
+
+  #[test]
+  fn test_send_sync() {
+    assert_send_sync::<CTx>();
+    assert_send_sync::<TxIn>();
+    assert_send_sync::<TxOut>();
+    assert_send_sync::<Scalar>();
+    assert_send_sync::<PublicKey>();
+    assert_send_sync::<RangeProof>();
+    assert_send_sync::<DoublePublicKey>();
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn test_new_async_is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+    assert_send(&CTx::new_async(vec![], vec![]));
+  }
+
+  #[test]
+  fn test_parallel_build() {
+    let ctxs = (0..8)
+      .map(|_| thread::spawn(gen_ctx))
+      .collect::<Vec<_>>()
+      .into_iter()
+      .map(|handle| handle.join().unwrap())
+      .collect::<Vec<_>>();
+
+    let ctxs = Arc::new(ctxs);
+    let handles = (0..4)
+      .map(|_| {
+        let ctxs = Arc::clone(&ctxs);
+        thread::spawn(move || {
+          for ctx in ctxs.iter() {
+            assert_eq!(ctx.get_ctx_ins().len(), 1);
+            let hex = bincode::serialize(ctx).unwrap();
+            assert_eq!(&bincode::deserialize::<CTx>(&hex).unwrap(), ctx);
+          }
+        })
+      })
+      .collect::<Vec<_>>();
+    for handle in handles {
+      handle.join().unwrap();
+    }
+  }
+
   #[test]
   fn test_deser() {
-    init();
     let a = gen_ctx();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<CTx>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_bytes() {
+    let a = gen_ctx();
+    let b = CTx::from_bytes(&a.to_bytes().unwrap()).unwrap();
+    assert_eq!(a, b);
+  }
 }
@@ -1,8 +1,8 @@
 use crate::{
-  blsct_obj::BlsctObj,
+  blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
   ffi::{deserialize_ctx_id, serialize_ctx_id, BlsctCTxId, BlsctRetVal, CTX_ID_SIZE},
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   util::gen_random_malloced_buf,
 };
 use serde::{Deserialize, Serialize};
@@ -15,6 +15,7 @@ pub struct CTxId {
 
 impl_from_retval!(CTxId);
 impl_display!(CTxId);
+impl_bytes!(CTxId);
 impl_clone!(CTxId);
 
 impl CTxId {
@@ -24,10 +25,25 @@ impl CTxId {
     obj.into()
   }
 
+  // in reverse byte order as navio-core displays txids
+  pub fn to_hex(&self) -> String {
+    let mut bytes = self.to_bytes();
+    bytes.reverse();
+    hex::encode(bytes)
+  }
+
+  pub fn from_hex(s: &str) -> Result<Self, blsct_obj::Error> {
+    let mut bytes = hex::decode(s).map_err(|_| blsct_obj::Error::InvalidBytes("CTxId"))?;
+    bytes.reverse();
+    Self::from_bytes(&bytes)
+  }
+
   impl_value!(BlsctCTxId);
 }
 
 impl BlsctSerde for CTxId {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_ctx_id(ptr as *const BlsctCTxId)
   }
@@ -52,14 +68,20 @@ impl PartialEq for CTxId {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
 
   #[test]
   fn test_deser() {
-    init();
     let a = CTxId::random();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<CTxId>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_hex() {
+    let a = CTxId::random();
+    let b = CTxId::from_hex(&a.to_hex()).unwrap();
+    assert_eq!(a, b);
+    assert!(CTxId::from_hex("zz").is_err());
+  }
 }
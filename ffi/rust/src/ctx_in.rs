@@ -6,6 +6,7 @@ use crate::{
     get_ctx_in_sequence, BlsctCTxId, BlsctScript,
   },
   macros::impl_value_raw_const_obj,
+  out_point::OutPoint,
   script::Script,
 };
 use std::ffi::c_void;
@@ -21,6 +22,12 @@ impl CTxIn {
     BlsctObj::<CTxId, BlsctCTxId>::from_c_obj(c_obj as *mut BlsctCTxId).into()
   }
 
+  // an out point is the hash of the output it refers to, which is what
+  // prev_out_hash holds
+  pub fn spends(&self, out_point: &OutPoint) -> bool {
+    self.prev_out_hash().to_bytes() == out_point.to_bytes()
+  }
+
   pub fn script_sig(&self) -> Script {
     let c_obj = unsafe { get_ctx_in_script_sig(self.value()) };
     BlsctObj::<Script, BlsctScript>::from_c_obj(c_obj as *mut BlsctScript).into()
@@ -55,7 +62,7 @@ impl Eq for CTxIn {}
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, test_util::gen_ctx};
+  use crate::test_util::{gen_ctx, gen_ctx_spending};
 
   fn get_ctx_in() -> CTxIn {
     let ctx = gen_ctx();
@@ -66,29 +73,47 @@ mod tests {
 
   #[test]
   fn test_prev_out_hash() {
-    init();
     let ctx_in = get_ctx_in();
     let _ = ctx_in.prev_out_hash();
   }
 
   #[test]
   fn test_script_sig() {
-    init();
     let ctx_in = get_ctx_in();
     let _ = ctx_in.script_sig();
   }
 
   #[test]
   fn test_sequence() {
-    init();
     let ctx_in = get_ctx_in();
     let _ = ctx_in.sequence();
   }
 
   #[test]
   fn test_script_witness() {
-    init();
     let ctx_in = get_ctx_in();
     let _ = ctx_in.script_witness();
   }
+
+  #[test]
+  fn test_spends() {
+    let mut out_points = gen_ctx().out_points().unwrap();
+    let out_point = out_points.remove(0);
+    let ctx = gen_ctx_spending(&out_point);
+    let ctx_ins = ctx.get_ctx_ins();
+    assert!(ctx_ins.get_ctx_in_at(0).unwrap().spends(&out_point));
+
+    let other = out_points.remove(0);
+    assert!(!ctx_ins.get_ctx_in_at(0).unwrap().spends(&other));
+
+    let out_points = vec![other, out_point.clone()];
+    assert_eq!(
+      ctx_ins.spent_out_points(&out_points).unwrap(),
+      vec![Some(&out_point)]
+    );
+    assert_eq!(
+      ctx_ins.spent_out_points(&out_points[..1]).unwrap(),
+      vec![None]
+    );
+  }
 }
@@ -2,6 +2,7 @@ use crate::{
   ctx_in::CTxIn,
   ffi::{are_ctx_ins_equal, get_ctx_in_at, get_ctx_ins_size},
   macros::impl_value_raw_const_obj,
+  out_point::OutPoint,
 };
 use std::{ffi::c_void, fmt};
 
@@ -38,13 +39,26 @@ impl CTxIns {
     if i >= self.len() {
       return Err(Error::IndexOutOfRange {
         index: i,
-        max_index: self.len() - 1,
+        max_index: self.len().saturating_sub(1),
       });
     }
     let obj = unsafe { get_ctx_in_at(self.value(), i) };
     Ok(obj.into())
   }
 
+  // for each CTxIn, the one of the given out points it spends, if any
+  pub fn spent_out_points<'b>(
+    &self,
+    out_points: &'b [OutPoint],
+  ) -> Result<Vec<Option<&'b OutPoint>>, Error> {
+    (0..self.len())
+      .map(|i| {
+        let ctx_in = self.get_ctx_in_at(i)?;
+        Ok(out_points.iter().find(|x| ctx_in.spends(x)))
+      })
+      .collect()
+  }
+
   pub fn len(&self) -> usize {
     unsafe { get_ctx_ins_size(self.value()) }
   }
@@ -1,5 +1,9 @@
 use crate::{
+  amount_recovery_req::AmountRecoveryReq,
+  amount_recovery_res::AmountRecoveryRes,
   blsct_obj::{self, BlsctObj},
+  ctx::CTx,
+  ctx_ser,
   ffi::{
     are_ctx_out_equal, get_ctx_out_blinding_key, get_ctx_out_ephemeral_key,
     get_ctx_out_range_proof, get_ctx_out_script_pub_key, get_ctx_out_spending_key,
@@ -7,26 +11,48 @@ use crate::{
     BlsctPoint, BlsctRangeProof, BlsctRetVal, BlsctScalar, BlsctScript, BlsctTokenId,
     BlsctVectorPredicate,
   },
+  keys::public_key::PublicKey,
   macros::impl_value_raw_const_obj,
+  out_point::OutPoint,
   point::Point,
-  range_proof::RangeProof,
+  range_proof::{self, RangeProof},
   scalar::Scalar,
   script::Script,
   token_id::TokenId,
   vector_predicate::VectorPredicate,
+  view_tag::ViewTag,
 };
 use std::ffi::c_void;
 
+// borrowed from its CTx, which owns the C++ object `obj` points to
 #[derive(Debug)]
-pub struct CTxOut {
+pub struct CTxOut<'a> {
   obj: *const c_void,
+  ctx: &'a CTx,
+  index: usize,
 }
 
-impl CTxOut {
+impl<'a> CTxOut<'a> {
+  // `ctx` is the CTx the output is the `index`-th output of
+  pub(crate) fn new(obj: *const c_void, ctx: &'a CTx, index: usize) -> Self {
+    CTxOut { obj, ctx, index }
+  }
+
   pub fn out_value(&self) -> u64 {
     unsafe { get_ctx_out_value(self.value()) }
   }
 
+  // libblsct does not serialize a single output, so the output is read
+  // from the serialization of its CTx
+  pub fn out_point(&self) -> Result<OutPoint, blsct_obj::Error> {
+    let ctx_bytes = self.ctx.to_bytes()?;
+    let outs = ctx_ser::split_outs(&ctx_bytes).ok_or(blsct_obj::Error::InvalidBytes("CTx"))?;
+    let out_bytes = outs
+      .get(self.index)
+      .ok_or(blsct_obj::Error::InvalidBytes("CTx"))?;
+    ctx_ser::out_point(out_bytes)
+  }
+
   pub fn script_pub_key(&self) -> Script {
     let c_obj = unsafe { get_ctx_out_script_pub_key(self.value()) };
     BlsctObj::<Script, BlsctScript>::from_c_obj(c_obj as *mut BlsctScript).into()
@@ -37,7 +63,7 @@ impl CTxOut {
     BlsctObj::<TokenId, BlsctTokenId>::from_c_obj(c_obj as *mut BlsctTokenId).into()
   }
 
-  pub fn vector_predicate(&self) -> Result<VectorPredicate, blsct_obj::Error<'_>> {
+  pub fn vector_predicate(&self) -> Result<VectorPredicate, blsct_obj::Error> {
     let rv = unsafe { get_ctx_out_vector_predicate(self.value()) };
     let obj = BlsctObj::<VectorPredicate, BlsctVectorPredicate>::from_retval(rv)?;
     Ok(obj.into())
@@ -58,7 +84,7 @@ impl CTxOut {
     BlsctObj::<Scalar, BlsctScalar>::from_c_obj(c_obj as *mut BlsctScalar).into()
   }
 
-  pub fn blsct_data_range_proof(&self) -> Result<RangeProof, blsct_obj::Error<'_>> {
+  pub fn blsct_data_range_proof(&self) -> Result<RangeProof, blsct_obj::Error> {
     let rv = unsafe { get_ctx_out_range_proof(self.value()) } as *mut BlsctRetVal;
     let obj = BlsctObj::<RangeProof, BlsctRangeProof>::from_retval(rv)?;
     Ok(obj.into())
@@ -68,104 +94,178 @@ impl CTxOut {
     unsafe { get_ctx_out_view_tag(self.value()) }
   }
 
-  impl_value_raw_const_obj!();
-}
+  pub fn blsct_data_blinding_pub_key(&self) -> PublicKey {
+    let c_obj = unsafe { get_ctx_out_blinding_key(self.value()) };
+    let point: Point = BlsctObj::<Point, BlsctPoint>::from_c_obj(c_obj as *mut BlsctPoint).into();
+    (&point).into()
+  }
+
+  pub fn blsct_data_spending_pub_key(&self) -> PublicKey {
+    let c_obj = unsafe { get_ctx_out_spending_key(self.value()) };
+    let point: Point = BlsctObj::<Point, BlsctPoint>::from_c_obj(c_obj as *mut BlsctPoint).into();
+    (&point).into()
+  }
 
-impl From<*const c_void> for CTxOut {
-  fn from(obj: *const c_void) -> CTxOut {
-    CTxOut { obj }
+  pub fn is_staked_commitment(&self) -> bool {
+    self.script_pub_key().is_staked_commitment()
   }
+
+  // the fee of a CTx is paid to an unspendable output carrying it in the clear
+  pub fn is_fee(&self) -> bool {
+    self.script_pub_key().is_op_return() && self.out_value() > 0
+  }
+
+  // returns None if the output is not addressed to the owner of the view key
+  pub fn recover_amount(
+    &self,
+    view_key: &Scalar,
+  ) -> Result<Option<AmountRecoveryRes>, range_proof::Error> {
+    let blinding_pub_key = self.blsct_data_blinding_pub_key();
+    let view_tag = ViewTag::new(&blinding_pub_key, view_key);
+    if view_tag.value() != self.blsct_data_view_tag() as u64 {
+      return Ok(None);
+    }
+    let range_proof = self
+      .blsct_data_range_proof()
+      .map_err(range_proof::Error::BlsctObjError)?;
+    let nonce: Point = (&blinding_pub_key.generate_nonce(view_key)).into();
+    let req = AmountRecoveryReq::new_with_token_id(&range_proof, &nonce, &self.token_id());
+
+    let res = RangeProof::recover_amounts(vec![req])?;
+    Ok(res.into_iter().find(|x| x.is_succ))
+  }
+
+  impl_value_raw_const_obj!();
 }
 
-impl PartialEq for CTxOut {
+impl PartialEq for CTxOut<'_> {
   fn eq(&self, other: &Self) -> bool {
     unsafe { are_ctx_out_equal(self.value(), other.value()) }
   }
 }
 
-impl Eq for CTxOut {}
+impl Eq for CTxOut<'_> {}
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, test_util::gen_ctx};
+  use crate::{
+    fee::{calc_fee, DEFAULT_FEE_RATE},
+    sub_addr::SubAddr,
+    sub_addr_id::SubAddrId,
+    test_util::{gen_ctx, gen_ctx_actual},
+    util::gen_random_view_key,
+  };
 
-  fn get_ctx_out() -> CTxOut {
-    let ctx = gen_ctx();
-    let ctx_outs = ctx.get_ctx_outs();
-    let ctx_out = ctx_outs.at(0).unwrap();
-    ctx_out
+  fn get_ctx_out(ctx: &CTx) -> CTxOut<'_> {
+    ctx.get_ctx_outs().at(0).unwrap()
   }
 
   #[test]
   fn test_out_value() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let out_value = ctx_out.out_value();
     println!("OutValue: {out_value}");
   }
 
+  #[test]
+  fn test_is_fee() {
+    let ctx = gen_ctx();
+    let ctx_outs = ctx.get_ctx_outs();
+    let fees: Vec<u64> = (0..ctx_outs.len())
+      .map(|i| ctx_outs.at(i).unwrap())
+      .filter(|x| x.is_fee())
+      .map(|x| x.out_value())
+      .collect();
+    assert_eq!(fees, vec![calc_fee(1, 1, DEFAULT_FEE_RATE)]);
+  }
+
   #[test]
   fn test_script_pub_key() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let script_pub_key = ctx_out.script_pub_key();
     println!("ScriptPubKey: {script_pub_key}");
   }
 
   #[test]
   fn test_token_id() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let token_id = ctx_out.token_id();
     println!("TokenId: {}, {}", token_id.token(), token_id.subid());
   }
 
   #[test]
   fn test_vector_predicate() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let vector_predicate = ctx_out.vector_predicate();
     println!("VectorPredicate: {vector_predicate:?}");
   }
 
   #[test]
   fn test_spending_key() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let spending_key = ctx_out.blsct_data_spending_key();
     println!("BlsctData.SpendingKey: {spending_key:?}");
   }
 
   #[test]
   fn test_ephemeral_key() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let ephemeral_key = ctx_out.blsct_data_ephemeral_key();
     println!("BlsctData.EphemeralKey: {ephemeral_key:?}");
   }
 
   #[test]
   fn test_blinding_key() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let blinding_key = ctx_out.blsct_data_blinding_key();
     println!("BlsctData.BlindingKey: {blinding_key:?}");
   }
 
   #[test]
   fn test_range_proof() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let range_proof = ctx_out.blsct_data_range_proof();
     println!("BlsctData.RangeProof: {range_proof:?}");
   }
 
   #[test]
   fn test_view_tag() {
-    init();
-    let ctx_out = get_ctx_out();
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
     let view_tag = ctx_out.blsct_data_view_tag();
     println!("BlsctData.ViewTag: {view_tag}");
   }
+
+  #[test]
+  fn test_is_staked_commitment() {
+    let ctx = gen_ctx();
+    let ctx_out = get_ctx_out(&ctx);
+    assert!(!ctx_out.is_staked_commitment());
+  }
+
+  #[test]
+  fn test_recover_amount() {
+    let view_key = gen_random_view_key().unwrap();
+    let spending_pub_key = PublicKey::random().unwrap();
+    let destination = SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(0, 0));
+    let blinding_key = Scalar::random().unwrap();
+    let ctx = gen_ctx_actual(12345, "navio", &destination, &blinding_key);
+    let ctx_out = ctx.get_ctx_outs().at(0).unwrap();
+
+    let res = ctx_out.recover_amount(&view_key).unwrap().unwrap();
+    assert_eq!(res.amount, 12345);
+    assert_eq!(res.msg, "navio");
+
+    let other_view_key = gen_random_view_key().unwrap();
+    assert!(ctx_out.recover_amount(&other_view_key).unwrap().is_none());
+  }
 }
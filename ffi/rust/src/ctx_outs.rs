@@ -1,4 +1,5 @@
 use crate::{
+  ctx::CTx,
   ctx_out::CTxOut,
   ffi::{are_ctx_outs_equal, get_ctx_out_at, get_ctx_outs_size},
   macros::impl_value_raw_const_obj,
@@ -29,20 +30,26 @@ impl fmt::Display for Error {
    };
 */
 #[derive(Debug)]
-pub struct CTxOuts {
+pub struct CTxOuts<'a> {
   obj: *const c_void,
+  ctx: &'a CTx,
 }
 
-impl CTxOuts {
-  pub fn at(&self, i: usize) -> Result<CTxOut, Error> {
+impl<'a> CTxOuts<'a> {
+  // `ctx` is the CTx the outputs belong to
+  pub(crate) fn new(obj: *const c_void, ctx: &'a CTx) -> Self {
+    CTxOuts { obj, ctx }
+  }
+
+  pub fn at(&self, i: usize) -> Result<CTxOut<'a>, Error> {
     if i >= self.len() {
       return Err(Error::IndexOutOfRange {
         index: i,
-        max_index: self.len() - 1,
+        max_index: self.len().saturating_sub(1),
       });
     }
     let obj = unsafe { get_ctx_out_at(self.value(), i) };
-    Ok(obj.into())
+    Ok(CTxOut::new(obj, self.ctx, i))
   }
 
   pub fn len(&self) -> usize {
@@ -52,13 +59,7 @@ impl CTxOuts {
   impl_value_raw_const_obj!();
 }
 
-impl From<*const c_void> for CTxOuts {
-  fn from(obj: *const c_void) -> CTxOuts {
-    CTxOuts { obj }
-  }
-}
-
-impl PartialEq for CTxOuts {
+impl PartialEq for CTxOuts<'_> {
   fn eq(&self, other: &Self) -> bool {
     unsafe { are_ctx_outs_equal(self.value(), other.value()) }
   }
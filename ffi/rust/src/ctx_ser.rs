@@ -0,0 +1,197 @@
+use crate::{
+  blsct_obj,
+  ffi::{POINT_SIZE, TOKEN_ID_SIZE},
+  out_point::OutPoint,
+};
+use sha2::{Digest, Sha256};
+
+// reads the serialization of a CTx written by navio-core
+// (SerializeTransaction and CTxOut::Serialize in primitives/transaction.h)
+// just far enough to find where each output starts and ends
+
+const VERSION_SIZE: usize = 4;
+const PREV_OUT_SIZE: usize = 32;
+const SEQUENCE_SIZE: usize = 4;
+const AMOUNT_SIZE: usize = 8;
+const SCALAR_SIZE: usize = 32;
+const VIEW_TAG_SIZE: usize = 2;
+
+// nValue of an output that is followed by the flags below
+const FLAGS_MARKER: u64 = i64::MAX as u64;
+
+const BLSCT_MARKER: u64 = 1 << 0;
+const TOKEN_MARKER: u64 = 1 << 1;
+const PREDICATE_MARKER: u64 = 1 << 2;
+const TRANSPARENT_VALUE_MARKER: u64 = 1 << 3;
+
+struct Reader<'b> {
+  bytes: &'b [u8],
+  pos: usize,
+}
+
+impl<'b> Reader<'b> {
+  fn skip(&mut self, n: usize) -> Option<&'b [u8]> {
+    let end = self.pos.checked_add(n)?;
+    let bytes = self.bytes.get(self.pos..end)?;
+    self.pos = end;
+    Some(bytes)
+  }
+
+  fn u8(&mut self) -> Option<u8> {
+    Some(self.skip(1)?[0])
+  }
+
+  fn u64(&mut self) -> Option<u64> {
+    Some(u64::from_le_bytes(self.skip(8)?.try_into().ok()?))
+  }
+
+  fn compact_size(&mut self) -> Option<usize> {
+    let n = match self.u8()? {
+      0xfd => u16::from_le_bytes(self.skip(2)?.try_into().ok()?) as u64,
+      0xfe => u32::from_le_bytes(self.skip(4)?.try_into().ok()?) as u64,
+      0xff => self.u64()?,
+      n => n as u64,
+    };
+    n.try_into().ok()
+  }
+
+  // a vector of elements of a fixed size. returns the number of elements.
+  fn skip_vec(&mut self, elem_size: usize) -> Option<usize> {
+    let n = self.compact_size()?;
+    self.skip(n.checked_mul(elem_size)?)?;
+    Some(n)
+  }
+
+  fn skip_in(&mut self) -> Option<()> {
+    self.skip(PREV_OUT_SIZE)?;
+    self.skip_vec(1)?; // scriptSig
+    self.skip(SEQUENCE_SIZE)?;
+    Some(())
+  }
+
+  // bulletproofs_plus::RangeProof
+  fn skip_range_proof(&mut self) -> Option<()> {
+    let num_vs = self.skip_vec(POINT_SIZE)?;
+    if num_vs > 0 {
+      self.skip_vec(POINT_SIZE)?; // Ls
+      self.skip_vec(POINT_SIZE)?; // Rs
+                                  // A, A_wip, B, r', s', delta', alpha_hat, tau_x
+      self.skip(3 * POINT_SIZE + 5 * SCALAR_SIZE)?;
+    }
+    Some(())
+  }
+
+  fn skip_out(&mut self) -> Option<()> {
+    let flags = if self.u64()? == FLAGS_MARKER {
+      self.u64()?
+    } else {
+      0
+    };
+    if flags & TRANSPARENT_VALUE_MARKER != 0 {
+      self.skip(AMOUNT_SIZE)?;
+    }
+    self.skip_vec(1)?; // scriptPubKey
+    if flags & BLSCT_MARKER != 0 {
+      self.skip_range_proof()?;
+      // spending, blinding and ephemeral keys and the view tag
+      self.skip(3 * POINT_SIZE + VIEW_TAG_SIZE)?;
+    }
+    if flags & TOKEN_MARKER != 0 {
+      self.skip(TOKEN_ID_SIZE)?;
+    }
+    if flags & PREDICATE_MARKER != 0 {
+      self.skip_vec(1)?;
+    }
+    Some(())
+  }
+}
+
+// the serialized outputs of a serialized CTx, or None if it is malformed
+pub fn split_outs(ctx_bytes: &[u8]) -> Option<Vec<&[u8]>> {
+  let mut r = Reader {
+    bytes: ctx_bytes,
+    pos: 0,
+  };
+  r.skip(VERSION_SIZE)?;
+  let mut num_ins = r.compact_size()?;
+  if num_ins == 0 {
+    // an empty vin followed by non-zero flags marks the extended format
+    if r.u8()? == 0 {
+      return Some(vec![]);
+    }
+    num_ins = r.compact_size()?;
+  }
+  for _ in 0..num_ins {
+    r.skip_in()?;
+  }
+  let num_outs = r.compact_size()?;
+  (0..num_outs)
+    .map(|_| {
+      let start = r.pos;
+      r.skip_out()?;
+      Some(&ctx_bytes[start..r.pos])
+    })
+    .collect()
+}
+
+// the out point of an output is CTxOut::GetHash, the double SHA-256 of
+// the serialized output
+pub fn out_point(out_bytes: &[u8]) -> Result<OutPoint, blsct_obj::Error> {
+  let hash: [u8; 32] = Sha256::digest(Sha256::digest(out_bytes)).into();
+  OutPoint::from_bytes(&hash)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::gen_ctx;
+
+  #[test]
+  fn test_split_outs() {
+    let ctx = gen_ctx();
+    let ctx_bytes = ctx.to_bytes().unwrap();
+    let outs = split_outs(&ctx_bytes).unwrap();
+    let ctx_outs = ctx.get_ctx_outs();
+    assert_eq!(outs.len(), ctx_outs.len());
+
+    // each output holds its own range proof
+    for (i, out) in outs.iter().enumerate() {
+      let Ok(rp) = ctx_outs.at(i).unwrap().blsct_data_range_proof() else {
+        continue;
+      };
+      let rp_bytes = rp.to_bytes();
+      assert!(out.windows(rp_bytes.len()).any(|x| x == rp_bytes));
+    }
+  }
+
+  // a CTx laid out as navio-core serializes it, with BLSCT, token,
+  // predicate, transparent value and plain fee outputs, and the hashes of
+  // the outputs in the byte order the node reports them. both were
+  // computed independently of this module.
+  const CTX_OUT_POINTS: &str = include_str!("../testdata/ctx_out_points.json");
+
+  #[test]
+  fn test_known_out_points() {
+    let vector: serde_json::Value = serde_json::from_str(CTX_OUT_POINTS).unwrap();
+    let ctx_bytes = hex::decode(vector["hex"].as_str().unwrap()).unwrap();
+    let vout = vector["vout"].as_array().unwrap();
+
+    let outs = split_outs(&ctx_bytes).unwrap();
+    assert_eq!(outs.len(), vout.len());
+    for (out, expected) in outs.iter().zip(vout) {
+      let mut hash = out_point(out).unwrap().to_bytes();
+      hash.reverse();
+      assert_eq!(hex::encode(hash), expected["hash"].as_str().unwrap());
+    }
+  }
+
+  #[test]
+  fn test_split_outs_of_garbage() {
+    let ctx_bytes = gen_ctx().to_bytes().unwrap();
+    for len in 0..ctx_bytes.len() / 2 {
+      let _ = split_outs(&ctx_bytes[..len]);
+    }
+    assert_eq!(split_outs(&[]), None);
+    assert_eq!(split_outs(&[0xff; 16]), None);
+  }
+}
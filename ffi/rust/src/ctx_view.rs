@@ -0,0 +1,182 @@
+use crate::{
+  blsct_obj, ctx::CTx, ctx_in::CTxIn, ctx_out::CTxOut, error::Error, range_proof::RangeProof,
+  script::Script,
+};
+use serde::{Deserialize, Serialize};
+
+// a JSON view of a CTx laid out like the result of decoderawtransaction
+// in navio-core. keys, scripts and proofs are hex encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CTxView {
+  pub txid: String,
+  pub vin: Vec<CTxInView>,
+  pub vout: Vec<CTxOutView>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CTxInView {
+  pub prev_out_hash: String,
+  pub sequence: u32,
+  pub script_sig: String,
+  pub script_witness: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CTxOutView {
+  pub n: usize,
+  pub value: u64,
+  pub script_pub_key: String,
+  pub token_id: TokenIdView,
+  pub spending_key: String,
+  pub ephemeral_key: String,
+  pub blinding_key: String,
+  pub view_tag: u16,
+  // outputs without a predicate or a range proof have none
+  pub predicate: Option<String>,
+  pub range_proof: Option<RangeProofSummary>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TokenIdView {
+  pub token: u64,
+  pub subid: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RangeProofSummary {
+  pub size: usize,
+  pub a: String,
+  pub a_wip: String,
+  pub b: String,
+  pub tau_x: String,
+}
+
+// libblsct returns an empty predicate or proof as a null value without an
+// error code. any other error is a failure to read the output.
+fn non_empty<T>(res: Result<T, blsct_obj::Error>) -> Result<Option<T>, Error> {
+  match res {
+    Ok(x) => Ok(Some(x)),
+    Err(blsct_obj::Error::FailedToGenerateObject(_)) => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+fn script_hex(script: &Script) -> String {
+  hex::encode(script.to_bytes())
+}
+
+impl From<&CTxIn> for CTxInView {
+  fn from(ctx_in: &CTxIn) -> Self {
+    CTxInView {
+      prev_out_hash: ctx_in.prev_out_hash().to_hex(),
+      sequence: ctx_in.sequence(),
+      script_sig: script_hex(&ctx_in.script_sig()),
+      script_witness: script_hex(&ctx_in.script_witness()),
+    }
+  }
+}
+
+impl From<&RangeProof> for RangeProofSummary {
+  fn from(range_proof: &RangeProof) -> Self {
+    RangeProofSummary {
+      size: range_proof.size(),
+      a: hex::encode(range_proof.get_A().to_bytes()),
+      a_wip: hex::encode(range_proof.get_A_wip().to_bytes()),
+      b: hex::encode(range_proof.get_B().to_bytes()),
+      tau_x: hex::encode(range_proof.get_tau_x().to_bytes()),
+    }
+  }
+}
+
+impl CTxOutView {
+  pub fn new(n: usize, ctx_out: &CTxOut) -> Result<Self, Error> {
+    let token_id = ctx_out.token_id();
+    let predicate = non_empty(ctx_out.vector_predicate())?
+      .filter(|x| x.size() > 0)
+      .map(|x| hex::encode(x.to_bytes()));
+    let range_proof = non_empty(ctx_out.blsct_data_range_proof())?
+      .filter(|x| x.size() > 0)
+      .map(|x| (&x).into());
+
+    Ok(CTxOutView {
+      n,
+      value: ctx_out.out_value(),
+      script_pub_key: script_hex(&ctx_out.script_pub_key()),
+      token_id: TokenIdView {
+        token: token_id.token(),
+        subid: token_id.subid(),
+      },
+      spending_key: hex::encode(ctx_out.blsct_data_spending_key().to_bytes()),
+      ephemeral_key: hex::encode(ctx_out.blsct_data_ephemeral_key().to_bytes()),
+      blinding_key: hex::encode(ctx_out.blsct_data_blinding_pub_key().to_bytes()),
+      view_tag: ctx_out.blsct_data_view_tag(),
+      predicate,
+      range_proof,
+    })
+  }
+}
+
+impl CTxView {
+  pub fn new(ctx: &CTx) -> Result<Self, Error> {
+    let ctx_ins = ctx.get_ctx_ins();
+    let vin = (0..ctx_ins.len())
+      .map(|i| Ok((&ctx_ins.get_ctx_in_at(i)?).into()))
+      .collect::<Result<_, Error>>()?;
+
+    let ctx_outs = ctx.get_ctx_outs();
+    let vout = (0..ctx_outs.len())
+      .map(|i| CTxOutView::new(i, &ctx_outs.at(i)?))
+      .collect::<Result<_, Error>>()?;
+
+    Ok(CTxView {
+      txid: ctx.get_ctx_id()?.to_hex(),
+      vin,
+      vout,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{error::ResultCode, test_util::gen_ctx};
+
+  #[test]
+  fn test_view() {
+    let ctx = gen_ctx();
+    let view = CTxView::new(&ctx).unwrap();
+    assert_eq!(view.txid, ctx.get_ctx_id().unwrap().to_hex());
+    assert_eq!(view.vin.len(), 1);
+    assert!(!view.vout.is_empty());
+    for (i, out) in view.vout.iter().enumerate() {
+      assert_eq!(out.n, i);
+    }
+    assert!(view.vout.iter().any(|x| x.range_proof.is_some()));
+  }
+
+  #[test]
+  fn test_non_empty() {
+    let absent: Result<(), _> = Err(blsct_obj::Error::FailedToGenerateObject("RangeProof"));
+    assert_eq!(non_empty(absent).unwrap(), None);
+    assert_eq!(non_empty(Ok(1)).unwrap(), Some(1));
+
+    let failed: Result<(), _> = Err(blsct_obj::Error::FailedWithResultCode(
+      "RangeProof",
+      ResultCode::Failure,
+    ));
+    assert!(non_empty(failed).is_err());
+  }
+
+  #[test]
+  fn test_json() {
+    let ctx = gen_ctx();
+    let json = ctx.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["txid"].is_string());
+    assert!(value["vin"][0]["prev_out_hash"].is_string());
+    assert!(value["vout"][0]["token_id"]["token"].is_u64());
+
+    let view: CTxView = serde_json::from_str(&json).unwrap();
+    assert_eq!(view, CTxView::new(&ctx).unwrap());
+  }
+}
@@ -0,0 +1,309 @@
+use crate::{
+  address, audit, blsct_obj, chain, chain_source, ctx, ctx_ins, ctx_outs,
+  ffi::{
+    BLSCT_BAD_OUT_TYPE, BLSCT_BAD_SIZE, BLSCT_DID_NOT_RUN_TO_COMPLETION, BLSCT_EXCEPTION,
+    BLSCT_FAILURE, BLSCT_IN_AMOUNT_ERROR, BLSCT_MEMO_TOO_LONG, BLSCT_MEM_ALLOC_FAILED,
+    BLSCT_OUT_AMOUNT_ERROR, BLSCT_SUCCESS, BLSCT_UNKNOWN_ENCODING, BLSCT_VALUE_OUTSIDE_THE_RANGE,
+  },
+  history, keystore, memo, payment_proof, payment_request, pst, range_proof, rbf, rpc, signature,
+  staking, tx_out, unsigned_transaction, wallet,
+};
+use std::{ffi::NulError, str::Utf8Error};
+use thiserror::Error;
+
+// non-success result codes returned by libblsct
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultCode {
+  #[error("Operation failed")]
+  Failure,
+
+  #[error("Exception thrown in libblsct")]
+  Exception,
+
+  #[error("Bad size")]
+  BadSize,
+
+  #[error("Unknown encoding")]
+  UnknownEncoding,
+
+  #[error("Value outside the range")]
+  ValueOutsideTheRange,
+
+  #[error("Did not run to completion")]
+  DidNotRunToCompletion,
+
+  #[error("Invalid in-amount")]
+  InAmountError,
+
+  #[error("Invalid out-amount")]
+  OutAmountError,
+
+  #[error("Bad output type")]
+  BadOutType,
+
+  #[error("Memo too long")]
+  MemoTooLong,
+
+  #[error("Memory allocation failed")]
+  MemAllocFailed,
+
+  #[error("Unknown result code {0}")]
+  Unknown(u8),
+}
+
+impl ResultCode {
+  // returns None for BLSCT_SUCCESS
+  pub fn from_code(code: u8) -> Option<Self> {
+    let result_code = match code {
+      BLSCT_SUCCESS => return None,
+      BLSCT_FAILURE => ResultCode::Failure,
+      BLSCT_EXCEPTION => ResultCode::Exception,
+      BLSCT_BAD_SIZE => ResultCode::BadSize,
+      BLSCT_UNKNOWN_ENCODING => ResultCode::UnknownEncoding,
+      BLSCT_VALUE_OUTSIDE_THE_RANGE => ResultCode::ValueOutsideTheRange,
+      BLSCT_DID_NOT_RUN_TO_COMPLETION => ResultCode::DidNotRunToCompletion,
+      BLSCT_IN_AMOUNT_ERROR => ResultCode::InAmountError,
+      BLSCT_OUT_AMOUNT_ERROR => ResultCode::OutAmountError,
+      BLSCT_BAD_OUT_TYPE => ResultCode::BadOutType,
+      BLSCT_MEMO_TOO_LONG => ResultCode::MemoTooLong,
+      BLSCT_MEM_ALLOC_FAILED => ResultCode::MemAllocFailed,
+      n => ResultCode::Unknown(n),
+    };
+    Some(result_code)
+  }
+
+  pub fn code(&self) -> u8 {
+    match self {
+      ResultCode::Failure => BLSCT_FAILURE,
+      ResultCode::Exception => BLSCT_EXCEPTION,
+      ResultCode::BadSize => BLSCT_BAD_SIZE,
+      ResultCode::UnknownEncoding => BLSCT_UNKNOWN_ENCODING,
+      ResultCode::ValueOutsideTheRange => BLSCT_VALUE_OUTSIDE_THE_RANGE,
+      ResultCode::DidNotRunToCompletion => BLSCT_DID_NOT_RUN_TO_COMPLETION,
+      ResultCode::InAmountError => BLSCT_IN_AMOUNT_ERROR,
+      ResultCode::OutAmountError => BLSCT_OUT_AMOUNT_ERROR,
+      ResultCode::BadOutType => BLSCT_BAD_OUT_TYPE,
+      ResultCode::MemoTooLong => BLSCT_MEMO_TOO_LONG,
+      ResultCode::MemAllocFailed => BLSCT_MEM_ALLOC_FAILED,
+      ResultCode::Unknown(n) => *n,
+    }
+  }
+}
+
+// owned error type that the module errors convert into.
+// it is Send + Sync + 'static so that it can be boxed and sent across threads.
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("libblsct returned an error")]
+  Blsct(#[from] ResultCode),
+
+  #[error("BLSCT object error")]
+  BlsctObj(#[from] blsct_obj::Error),
+
+  #[error("Failed to create CString")]
+  FailedToCreateCString(#[from] NulError),
+
+  #[error("Failed to convert CStr to &str")]
+  FailedToConvertCStrToStr(#[from] Utf8Error),
+
+  #[error("Invalid address")]
+  Address(#[from] address::Error),
+
+  #[error("Invalid chain")]
+  Chain(#[from] chain::Error),
+
+  #[error("Chain source error")]
+  ChainSource(#[from] chain_source::Error),
+
+  #[error("Failed to build CTx")]
+  CTx(#[from] ctx::Error),
+
+  #[error("Invalid CTxIn index")]
+  CTxIns(#[from] ctx_ins::Error),
+
+  #[error("Invalid CTxOut index")]
+  CTxOuts(#[from] ctx_outs::Error),
+
+  #[error("TxOut error")]
+  TxOut(#[from] tx_out::Error),
+
+  #[error("Range proof error")]
+  RangeProof(#[from] range_proof::Error),
+
+  #[error("Unsigned transaction error")]
+  UnsignedTransaction(#[from] unsigned_transaction::Error),
+
+  #[error("Failed to replace transaction")]
+  Rbf(#[from] rbf::Error),
+
+  #[error("Staking error")]
+  Staking(#[from] staking::Error),
+
+  #[error("Invalid memo")]
+  Memo(#[from] memo::Error),
+
+  #[error("Signature error")]
+  Signature(#[from] signature::Error),
+
+  #[error("Invalid payment request")]
+  PaymentRequest(#[from] payment_request::Error),
+
+  #[error("Audit report error")]
+  Audit(#[from] audit::Error),
+
+  #[error("History error")]
+  History(#[from] history::Error),
+
+  #[error("Keystore error")]
+  Keystore(#[from] keystore::Error),
+
+  #[error("Invalid payment proof")]
+  PaymentProof(#[from] payment_proof::Error),
+
+  #[error("RPC error")]
+  Rpc(#[from] rpc::Error),
+
+  #[error("PST error")]
+  Pst(#[from] pst::Error),
+
+  #[error("Wallet error")]
+  Wallet(#[from] wallet::Error),
+
+  #[cfg(feature = "async")]
+  #[error("Blocking task failed")]
+  Task(#[from] crate::blocking_pool::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+  // returns the libblsct result code at the bottom of the chain if any
+  pub fn result_code(&self) -> Option<ResultCode> {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(self);
+    while let Some(e) = source {
+      if let Some(code) = e.downcast_ref::<ResultCode>() {
+        return Some(*code);
+      }
+      // ctx::Error keeps the raw code and the index of the failing amount
+      match e.downcast_ref::<ctx::Error>() {
+        Some(ctx::Error::FailedToBuildCTx(code)) => return ResultCode::from_code(*code),
+        Some(ctx::Error::InAmountError(_)) => return Some(ResultCode::InAmountError),
+        Some(ctx::Error::OutAmountError(_)) => return Some(ResultCode::OutAmountError),
+        _ => {}
+      }
+      source = e.source();
+    }
+    None
+  }
+}
+
+// for the error variants that hold the code of a failed libblsct call
+pub(crate) fn result_code(code: u8) -> ResultCode {
+  ResultCode::from_code(code).unwrap_or(ResultCode::Unknown(code))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::keys::double_public_key::DoublePublicKey;
+  use std::error::Error as StdError;
+
+  fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+
+  #[test]
+  fn test_send_sync_static() {
+    assert_send_sync_static::<Error>();
+    let _: Box<dyn StdError + Send + Sync> = Box::new(Error::from(ResultCode::Failure));
+  }
+
+  #[test]
+  fn test_result_code() {
+    for code in 1..=u8::MAX {
+      let result_code = ResultCode::from_code(code).unwrap();
+      assert_eq!(result_code.code(), code);
+    }
+    assert_eq!(ResultCode::from_code(BLSCT_SUCCESS), None);
+    assert_eq!(
+      ResultCode::from_code(BLSCT_MEMO_TOO_LONG),
+      Some(ResultCode::MemoTooLong)
+    );
+    assert_eq!(ResultCode::from_code(200), Some(ResultCode::Unknown(200)));
+  }
+
+  #[test]
+  fn test_source_chain() {
+    let e: Error = rbf::Error::UnsignedTransactionError(unsigned_transaction::Error::FailedToSign(
+      ResultCode::Exception,
+    ))
+    .into();
+    assert_eq!(e.result_code(), Some(ResultCode::Exception));
+
+    let mut messages = vec![e.to_string()];
+    let mut source = e.source();
+    while let Some(e) = source {
+      messages.push(e.to_string());
+      source = e.source();
+    }
+    assert_eq!(
+      messages,
+      vec![
+        "Failed to replace transaction",
+        "Failed to sign unsigned transaction",
+        "Exception thrown in libblsct"
+      ]
+    );
+
+    let e: Error = rbf::Error::NoChangeOutput.into();
+    assert!(matches!(
+      e.source().and_then(|e| e.downcast_ref::<rbf::Error>()),
+      Some(rbf::Error::NoChangeOutput)
+    ));
+  }
+
+  #[test]
+  fn test_module_errors_are_owned() {
+    let dpk = DoublePublicKey::random().unwrap();
+    let e: Error = address::Error::FailedToEncodeAddress(dpk.clone()).into();
+    drop(dpk);
+    assert!(matches!(
+      e,
+      Error::Address(address::Error::FailedToEncodeAddress(_))
+    ));
+
+    let e: Error = blsct_obj::Error::FailedToGenerateObject("Scalar").into();
+    assert!(matches!(
+      e,
+      Error::BlsctObj(blsct_obj::Error::FailedToGenerateObject("Scalar"))
+    ));
+    assert_eq!(e.result_code(), None);
+
+    let e: Error = payment_request::Error::InvalidScheme("xx".to_string()).into();
+    let _: Box<dyn StdError + Send + Sync> = Box::new(e);
+  }
+
+  #[test]
+  fn test_result_code_of_object() {
+    let e: Error = blsct_obj::Error::FailedWithResultCode("Scalar", ResultCode::BadSize).into();
+    assert_eq!(e.result_code(), Some(ResultCode::BadSize));
+
+    let e: Error = range_proof::Error::FailedToRecoverAmount(ResultCode::Failure).into();
+    assert_eq!(e.result_code(), Some(ResultCode::Failure));
+
+    // wrapped errors are transparent to the lookup
+    let e: Error = staking::Error::RangeProofError(range_proof::Error::BlsctObjError(
+      blsct_obj::Error::FailedWithResultCode("RangeProof", ResultCode::MemAllocFailed),
+    ))
+    .into();
+    assert_eq!(e.result_code(), Some(ResultCode::MemAllocFailed));
+  }
+
+  #[test]
+  fn test_from_module_errors() {
+    let e: Error = ctx::Error::FailedToBuildCTx(BLSCT_BAD_OUT_TYPE).into();
+    assert_eq!(e.result_code(), Some(ResultCode::BadOutType));
+    assert!(e.source().is_some());
+
+    let e: Error = memo::Error::TooLong(200).into();
+    assert!(matches!(e, Error::Memo(memo::Error::TooLong(200))));
+  }
+}
@@ -0,0 +1,18 @@
+// fee rule used by build_ctx: every input and output is charged the fee rate
+pub const DEFAULT_FEE_RATE: u64 = 200000;
+
+pub fn calc_fee(num_tx_ins: usize, num_tx_outs: usize, fee_rate: u64) -> u64 {
+  (num_tx_ins + num_tx_outs) as u64 * fee_rate
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_calc_fee() {
+    assert_eq!(calc_fee(1, 1, DEFAULT_FEE_RATE), 400000);
+    assert_eq!(calc_fee(2, 3, 10), 50);
+    assert_eq!(calc_fee(0, 0, DEFAULT_FEE_RATE), 0);
+  }
+}
@@ -69,7 +69,7 @@ pub struct BlsctTxOut {
 }
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressEncoding {
   Bech32,
   Bech32M,
@@ -82,6 +82,59 @@ pub enum TxOutputType {
   StakedCommitment,
 }
 
+// the structs of bls384_256 and mcl, which libblsct is built on. libblsct
+// builds them with 6 limbs per field element and in the ETH mode, so that a
+// public key is a G1 point and a signature a G2 point.
+const MCL_FP_LIMBS: usize = 6;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MclBnG1 {
+  d: [u64; MCL_FP_LIMBS * 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MclBnG2 {
+  d: [u64; MCL_FP_LIMBS * 2 * 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MclBnGT {
+  d: [u64; MCL_FP_LIMBS * 12],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BlsPublicKey {
+  pub v: MclBnG1,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BlsSignature {
+  pub v: MclBnG2,
+}
+
+impl MclBnG1 {
+  pub const ZERO: MclBnG1 = MclBnG1 {
+    d: [0; MCL_FP_LIMBS * 3],
+  };
+}
+
+impl MclBnG2 {
+  pub const ZERO: MclBnG2 = MclBnG2 {
+    d: [0; MCL_FP_LIMBS * 2 * 3],
+  };
+}
+
+impl MclBnGT {
+  pub const ZERO: MclBnGT = MclBnGT {
+    d: [0; MCL_FP_LIMBS * 12],
+  };
+}
+
 // constants
 pub const CTX_ID_SIZE: usize = 32;
 pub const KEY_ID_SIZE: usize = 20;
@@ -98,10 +151,29 @@ pub const TOKEN_ID_SIZE: usize = 40;
 pub const MAX_MEMO_LEN: usize = 100;
 pub const MEMO_BUF_SIZE: usize = MAX_MEMO_LEN + 1;
 
+// offsets of the fields of BlsctTxIn and BlsctTxOut that not every byte
+// pattern is valid for
+pub const TX_IN_BOOL_OFFSETS: [usize; 2] = [
+  std::mem::offset_of!(BlsctTxIn, staked_commitment),
+  std::mem::offset_of!(BlsctTxIn, rbf),
+];
+pub const TX_OUT_MEMO_OFFSET: usize = std::mem::offset_of!(BlsctTxOut, memo_c_str);
+pub const TX_OUT_OUTPUT_TYPE_OFFSET: usize = std::mem::offset_of!(BlsctTxOut, output_type);
+pub const TX_OUT_BOOL_OFFSET: usize = std::mem::offset_of!(BlsctTxOut, subtract_fee_from_amount);
+
 // error codes
+pub const BLSCT_SUCCESS: u8 = 0;
 pub const BLSCT_FAILURE: u8 = 1;
+pub const BLSCT_EXCEPTION: u8 = 2;
+pub const BLSCT_BAD_SIZE: u8 = 10;
+pub const BLSCT_UNKNOWN_ENCODING: u8 = 11;
+pub const BLSCT_VALUE_OUTSIDE_THE_RANGE: u8 = 12;
+pub const BLSCT_DID_NOT_RUN_TO_COMPLETION: u8 = 13;
 pub const BLSCT_IN_AMOUNT_ERROR: u8 = 14;
 pub const BLSCT_OUT_AMOUNT_ERROR: u8 = 15;
+pub const BLSCT_BAD_OUT_TYPE: u8 = 16;
+pub const BLSCT_MEMO_TOO_LONG: u8 = 17;
+pub const BLSCT_MEM_ALLOC_FAILED: u8 = 18;
 
 // serialized types
 pub type BlsctCTxId = [u8; CTX_ID_SIZE];
@@ -119,9 +191,36 @@ pub type BlsctTokenId = [u8; TOKEN_ID_SIZE];
 
 pub type BlsctRangeProof = u8;
 pub type BlsctCTx = u8;
+pub type BlsctUnsignedInput = u8;
+pub type BlsctUnsignedOutput = u8;
+pub type BlsctUnsignedTransaction = u8;
 pub type BlsctVectorPredicate = u8;
 
-extern "C" {
+// declares the libblsct functions in `raw` and wraps each of them so that
+// the library is initialized before anything calls into it. the wrappers
+// keep the C ABI since some of them are used as deallocators.
+macro_rules! blsct_fns {
+  ($(pub fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+    mod raw {
+      use super::*;
+
+      extern "C" {
+        $(pub fn $name($($arg: $arg_ty),*) $(-> $ret)?;)*
+      }
+    }
+
+    $(
+      #[inline]
+      #[allow(non_snake_case)]
+      pub unsafe extern "C" fn $name($($arg: $arg_ty),*) $(-> $ret)? {
+        crate::initializer::init();
+        raw::$name($($arg),*)
+      }
+    )*
+  };
+}
+
+blsct_fns! {
 
   pub fn malloc(size: usize) -> *mut core::ffi::c_void;
   pub fn free_obj(x: *mut c_void);
@@ -328,7 +427,6 @@ extern "C" {
   pub fn deserialize_scalar(hex: *const c_char) -> *mut BlsctRetVal;
   pub fn gen_scalar(n: u64) -> *mut BlsctRetVal;
   pub fn gen_random_scalar() -> *mut BlsctRetVal;
-  pub fn are_scalar_equal(a: *const BlsctScalar, b: *const BlsctScalar) -> c_int;
   pub fn scalar_to_uint64(blsct_scalar: *const BlsctScalar) -> u64;
   pub fn serialize_scalar(blsct_scalar: *const BlsctScalar) -> *const c_char;
 
@@ -336,7 +434,52 @@ extern "C" {
   pub fn serialize_script(blsct_script: *const BlsctScript) -> *const c_char;
   pub fn deserialize_script(hex: *const c_char) -> *mut BlsctRetVal;
 
+  // bls384_256 and mcl. they are called through the wrappers as well
+  // since libblsct sets up the curve when it is initialized.
+  pub fn blsPublicKeyDeserialize(
+    pub_key: *mut BlsPublicKey,
+    buf: *const c_void,
+    buf_size: usize,
+  ) -> usize;
+  pub fn blsPublicKeySerialize(
+    buf: *mut c_void,
+    max_buf_size: usize,
+    pub_key: *const BlsPublicKey,
+  ) -> usize;
+  pub fn blsPublicKeyAdd(pub_key: *mut BlsPublicKey, rhs: *const BlsPublicKey);
+  pub fn blsGetGeneratorOfPublicKey(pub_key: *mut BlsPublicKey);
+  pub fn blsSignatureDeserialize(
+    sig: *mut BlsSignature,
+    buf: *const c_void,
+    buf_size: usize,
+  ) -> usize;
+  pub fn blsSignatureSerialize(
+    buf: *mut c_void,
+    max_buf_size: usize,
+    sig: *const BlsSignature,
+  ) -> usize;
+  pub fn blsAggregateSignature(agg_sig: *mut BlsSignature, sig_vec: *const BlsSignature, n: usize);
+  pub fn mclBnG1_neg(y: *mut MclBnG1, x: *const MclBnG1);
+  pub fn mclBnG2_hashAndMapTo(x: *mut MclBnG2, buf: *const c_void, buf_size: usize) -> c_int;
+  pub fn mclBn_millerLoopVec(
+    z: *mut MclBnGT,
+    x_vec: *const MclBnG1,
+    y_vec: *const MclBnG2,
+    n: usize,
+  );
+  pub fn mclBn_finalExp(y: *mut MclBnGT, x: *const MclBnGT);
+  pub fn mclBnGT_isOne(x: *const MclBnGT) -> c_int;
+
   // Signature
+  pub fn sign_message(
+    blsct_priv_key: *const BlsctScalar,
+    blsct_msg: *const c_char,
+  ) -> *mut BlsctSignature;
+  pub fn verify_msg_sig(
+    blsct_pub_key: *const BlsctPubKey,
+    blsct_msg: *const c_char,
+    blsct_signature: *const BlsctSignature,
+  ) -> bool;
   pub fn serialize_signature(blsct_signature: *const BlsctSignature) -> *const c_char;
   pub fn deserialize_signature(hex: *const c_char) -> *mut BlsctRetVal;
 
@@ -410,6 +553,37 @@ extern "C" {
   pub fn from_tx_key_to_view_key(tx_key: *const BlsctScalar) -> *mut BlsctScalar;
   pub fn from_tx_key_to_spending_key(tx_key: *const BlsctScalar) -> *mut BlsctScalar;
 
+  // UnsignedInput
+  pub fn build_unsigned_input(tx_in: *const BlsctTxIn) -> *mut BlsctRetVal;
+  pub fn delete_unsigned_input(vp_unsigned_input: *mut c_void);
+  pub fn serialize_unsigned_input(vp_unsigned_input: *const c_void) -> *const c_char;
+  pub fn deserialize_unsigned_input(hex: *const c_char) -> *mut BlsctRetVal;
+
+  // UnsignedOutput
+  pub fn build_unsigned_output(tx_out: *const BlsctTxOut) -> *mut BlsctRetVal;
+  pub fn delete_unsigned_output(vp_unsigned_output: *mut c_void);
+  pub fn serialize_unsigned_output(vp_unsigned_output: *const c_void) -> *const c_char;
+  pub fn deserialize_unsigned_output(hex: *const c_char) -> *mut BlsctRetVal;
+
+  // UnsignedTransaction
+  pub fn create_unsigned_transaction() -> *mut c_void;
+  pub fn add_unsigned_transaction_input(
+    vp_unsigned_transaction: *mut c_void,
+    vp_unsigned_input: *const c_void,
+  );
+  pub fn add_unsigned_transaction_output(
+    vp_unsigned_transaction: *mut c_void,
+    vp_unsigned_output: *const c_void,
+  );
+  pub fn set_unsigned_transaction_fee(vp_unsigned_transaction: *mut c_void, fee: u64);
+  pub fn get_unsigned_transaction_fee(vp_unsigned_transaction: *const c_void) -> u64;
+  pub fn get_unsigned_transaction_inputs_size(vp_unsigned_transaction: *const c_void) -> usize;
+  pub fn get_unsigned_transaction_outputs_size(vp_unsigned_transaction: *const c_void) -> usize;
+  pub fn delete_unsigned_transaction(vp_unsigned_transaction: *mut c_void);
+  pub fn serialize_unsigned_transaction(vp_unsigned_transaction: *const c_void) -> *const c_char;
+  pub fn deserialize_unsigned_transaction(hex: *const c_char) -> *mut BlsctRetVal;
+  pub fn sign_unsigned_transaction(vp_unsigned_transaction: *const c_void) -> *mut BlsctRetVal;
+
   // VectorPredicate
   pub fn are_vector_predicate_equal(
     a: *const BlsctVectorPredicate,
@@ -473,7 +647,7 @@ extern "C" {
     idx: usize,
   ) -> *const c_char;
 
-} // extern "C"
+} // blsct_fns!
 
 #[cfg(test)]
 mod tests {
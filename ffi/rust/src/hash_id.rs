@@ -2,12 +2,15 @@ use crate::{
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
   ffi::{calc_key_id, deserialize_key_id, serialize_key_id, BlsctKeyId, BlsctRetVal},
-  keys::{child_key::ChildKey, public_key::PublicKey},
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  keys::{child_key::ChildKey, double_public_key::DoublePublicKey, public_key::PublicKey},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   scalar::Scalar,
+  sub_addr_id::SubAddrId,
 };
+use ripemd::Ripemd160;
 use serde::{Deserialize, Serialize};
-use std::ffi::c_char;
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, ffi::c_char};
 
 #[derive(Debug, Deserialize, Serialize, Eq)]
 pub struct HashId {
@@ -16,6 +19,7 @@ pub struct HashId {
 
 impl_from_retval!(HashId);
 impl_display!(HashId);
+impl_bytes!(HashId);
 impl_clone!(HashId);
 
 impl HashId {
@@ -34,7 +38,24 @@ impl HashId {
     BlsctObj::from_c_obj(blsct_key_id).into()
   }
 
-  pub fn random<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  // the id of a sub-address is the Hash160 of its spending key, which is
+  // what `new` recovers from an output sent to the sub-address
+  pub fn from_sub_addr_id(
+    view_key: &Scalar,
+    spending_pub_key: &PublicKey,
+    sub_addr_id: &SubAddrId,
+  ) -> Result<Self, blsct_obj::Error> {
+    let dpk = DoublePublicKey::from_keys_acct_addr(
+      view_key,
+      spending_pub_key,
+      sub_addr_id.clone().account(),
+      sub_addr_id.clone().address(),
+    );
+    let hash = Ripemd160::digest(Sha256::digest(dpk.spending_pub_key()?.to_bytes()));
+    Self::from_bytes(&hash)
+  }
+
+  pub fn random() -> Result<Self, blsct_obj::Error> {
     let blinding_pub_key = PublicKey::random()?;
     let spending_pub_key = PublicKey::random()?;
     let view_key = {
@@ -48,6 +69,8 @@ impl HashId {
 }
 
 impl BlsctSerde for HashId {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_key_id(ptr as *const BlsctKeyId)
   }
@@ -69,14 +92,36 @@ impl From<BlsctObj<HashId, BlsctKeyId>> for HashId {
   }
 }
 
+// the sub-addresses of a wallet keyed by the bytes of their ids. an output
+// belongs to the wallet only if the id recovered from it is one of them.
+pub(crate) struct SubAddrs(BTreeMap<Vec<u8>, SubAddrId>);
+
+impl SubAddrs {
+  pub(crate) fn new(
+    view_key: &Scalar,
+    spending_pub_key: &PublicKey,
+    sub_addr_ids: &[SubAddrId],
+  ) -> Result<Self, blsct_obj::Error> {
+    let mut sub_addrs = BTreeMap::new();
+    for sub_addr_id in sub_addr_ids {
+      let key_id = HashId::from_sub_addr_id(view_key, spending_pub_key, sub_addr_id)?;
+      sub_addrs.insert(key_id.to_bytes(), sub_addr_id.clone());
+    }
+    Ok(SubAddrs(sub_addrs))
+  }
+
+  pub(crate) fn get(&self, key_id: &HashId) -> Option<&SubAddrId> {
+    self.0.get(&key_id.to_bytes())
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
+  use crate::{sub_addr::SubAddr, test_util::gen_ctx_actual};
 
   #[test]
   fn test_eq() {
-    init();
     let (a, b) = {
       loop {
         let a = HashId::random().unwrap();
@@ -92,9 +137,35 @@ mod tests {
     assert!(b == b);
   }
 
+  #[test]
+  fn test_from_sub_addr_id() {
+    let tx_key = ChildKey::random().unwrap().to_tx_key();
+    let view_key = tx_key.to_view_key();
+    let spending_pub_key = PublicKey::from(tx_key.to_spending_key().expose_secret());
+    let sub_addr_id = SubAddrId::new(0, 1);
+    let destination = SubAddr::new(&view_key, &spending_pub_key, &sub_addr_id);
+    let ctx = gen_ctx_actual(1000, "navio", &destination, &Scalar::random().unwrap());
+    let ctx_outs = ctx.get_ctx_outs();
+    let key_ids = (0..ctx_outs.len())
+      .map(|i| {
+        let ctx_out = ctx_outs.at(i).unwrap();
+        HashId::new(
+          &ctx_out.blsct_data_blinding_pub_key(),
+          &ctx_out.blsct_data_spending_pub_key(),
+          &view_key,
+        )
+      })
+      .collect::<Vec<_>>();
+
+    let key_id = HashId::from_sub_addr_id(&view_key, &spending_pub_key, &sub_addr_id).unwrap();
+    assert!(key_ids.contains(&key_id));
+    let other_id = SubAddrId::new(0, 2);
+    let key_id = HashId::from_sub_addr_id(&view_key, &spending_pub_key, &other_id).unwrap();
+    assert!(!key_ids.contains(&key_id));
+  }
+
   #[test]
   fn test_deser() {
-    init();
     let a = HashId::random().unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<HashId>(&hex).unwrap();
@@ -0,0 +1,611 @@
+use crate::{ctx::CTx, out_point::OutPoint, sub_addr::SubAddr, wallet::Wallet};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("Unknown transaction {0}")]
+  UnknownTx(String),
+
+  #[error("Transaction {txid} cannot become {to} while {from}")]
+  InvalidTransition {
+    txid: String,
+    from: String,
+    to: String,
+  },
+
+  #[error("Transaction {txid} conflicts with confirmed transaction {confirmed}")]
+  ConflictsWithConfirmed { txid: String, confirmed: String },
+
+  #[error("Failed to read the transaction: {0}")]
+  FailedToReadCTx(String),
+
+  #[error("Failed to export the history: {0}")]
+  FailedToExport(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+  In,
+  Out,
+  // spends the wallet's outputs only to pay the wallet itself
+  #[serde(rename = "self")]
+  SelfTransfer,
+}
+
+impl fmt::Display for Direction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match self {
+      Direction::In => "in",
+      Direction::Out => "out",
+      Direction::SelfTransfer => "self",
+    };
+    write!(f, "{s}")
+  }
+}
+
+// pending -> confirmed, conflicted or abandoned. a confirmed transaction
+// goes back to pending when its block is disconnected, which also releases
+// the transactions it conflicted. an abandoned transaction can still confirm.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum State {
+  Pending,
+  Confirmed { height: u32 },
+  Conflicted { by: String },
+  Abandoned,
+}
+
+impl State {
+  fn name(&self) -> &'static str {
+    match self {
+      State::Pending => "pending",
+      State::Confirmed { .. } => "confirmed",
+      State::Conflicted { .. } => "conflicted",
+      State::Abandoned => "abandoned",
+    }
+  }
+}
+
+impl fmt::Display for State {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+// the change of the wallet's balance of a token. negative if the wallet
+// spent more of the token than it got back.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NetAmount {
+  pub token: u64,
+  pub subid: u64,
+  pub amount: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HistoryEntry {
+  pub txid: String,
+  pub direction: Direction,
+  pub amounts: Vec<NetAmount>,
+  // the fee paid by the wallet. zero for incoming transactions.
+  pub fee: u64,
+  pub memo: String,
+  // the chain does not reveal the other side of a transaction, so it is set
+  // by the caller
+  pub counterparty: Option<SubAddr>,
+  pub state: State,
+  // the out points spent by all the inputs of the transaction
+  pub spends: Vec<OutPoint>,
+}
+
+impl HistoryEntry {
+  fn conflicts_with(&self, other: &HistoryEntry) -> bool {
+    self.txid != other.txid && self.spends.iter().any(|x| other.spends.contains(x))
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct History {
+  entries: Vec<HistoryEntry>,
+}
+
+fn read_error(e: impl ToString) -> Error {
+  Error::FailedToReadCTx(e.to_string())
+}
+
+fn csv_field(s: &str) -> String {
+  if s.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", s.replace('"', "\"\""))
+  } else {
+    s.to_string()
+  }
+}
+
+impl History {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn entries(&self) -> &[HistoryEntry] {
+    &self.entries
+  }
+
+  pub fn get(&self, txid: &str) -> Option<&HistoryEntry> {
+    self.entries.iter().find(|x| x.txid == txid)
+  }
+
+  fn get_mut(&mut self, txid: &str) -> Result<&mut HistoryEntry, Error> {
+    self
+      .entries
+      .iter_mut()
+      .find(|x| x.txid == txid)
+      .ok_or_else(|| Error::UnknownTx(txid.to_string()))
+  }
+
+  // records a CTx the wallet has ingested as pending, or refreshes its
+  // amounts if it is already recorded
+  pub fn record(&mut self, wallet: &Wallet, ctx: &CTx) -> Result<&HistoryEntry, Error> {
+    let txid = ctx.get_ctx_id().map_err(read_error)?.to_hex();
+
+    let ctx_ins = ctx.get_ctx_ins();
+    let spends = (0..ctx_ins.len())
+      .map(|i| {
+        let ctx_in = ctx_ins.get_ctx_in_at(i).map_err(read_error)?;
+        OutPoint::from_bytes(&ctx_in.prev_out_hash().to_bytes()).map_err(read_error)
+      })
+      .collect::<Result<Vec<_>, Error>>()?;
+
+    // the wallet marks an output spent by the first transaction spending
+    // it only, so what a conflicting transaction spends is read from its
+    // inputs
+    let mut amounts: BTreeMap<(u64, u64), i128> = BTreeMap::new();
+    let mut received = 0;
+    let mut spent = 0;
+    let mut memo = String::new();
+    for output in wallet.outputs() {
+      let key = (output.token_id.token(), output.token_id.subid());
+      if output.txid == txid {
+        *amounts.entry(key).or_default() += output.amount as i128;
+        received += 1;
+        if memo.is_empty() {
+          memo = output.memo.clone();
+        }
+      }
+      if spends.contains(&output.out_point) {
+        *amounts.entry(key).or_default() -= output.amount as i128;
+        spent += 1;
+      }
+    }
+
+    let ctx_outs = ctx.get_ctx_outs();
+    let mut fee = 0;
+    let mut num_payments = 0;
+    for i in 0..ctx_outs.len() {
+      let ctx_out = ctx_outs.at(i).map_err(read_error)?;
+      if ctx_out.is_fee() {
+        fee += ctx_out.out_value();
+      } else {
+        num_payments += 1;
+      }
+    }
+    let direction = if spent == 0 {
+      Direction::In
+    } else if received == num_payments {
+      Direction::SelfTransfer
+    } else {
+      Direction::Out
+    };
+    if direction == Direction::In {
+      fee = 0;
+    }
+
+    let amounts = amounts
+      .into_iter()
+      .map(|((token, subid), amount)| NetAmount {
+        token,
+        subid,
+        amount,
+      })
+      .collect();
+
+    let index = match self.entries.iter().position(|x| x.txid == txid) {
+      Some(index) => {
+        let entry = &mut self.entries[index];
+        entry.direction = direction;
+        entry.amounts = amounts;
+        entry.fee = fee;
+        entry.memo = memo;
+        entry.spends = spends;
+        index
+      }
+      None => {
+        let mut entry = HistoryEntry {
+          txid,
+          direction,
+          amounts,
+          fee,
+          memo,
+          counterparty: None,
+          state: State::Pending,
+          spends,
+        };
+        // a transaction that shows up after a conflicting one confirmed
+        if let Some(confirmed) = self
+          .entries
+          .iter()
+          .find(|x| matches!(x.state, State::Confirmed { .. }) && x.conflicts_with(&entry))
+        {
+          entry.state = State::Conflicted {
+            by: confirmed.txid.clone(),
+          };
+        }
+        self.entries.push(entry);
+        self.entries.len() - 1
+      }
+    };
+    Ok(&self.entries[index])
+  }
+
+  pub fn set_counterparty(&mut self, txid: &str, counterparty: &SubAddr) -> Result<(), Error> {
+    self.get_mut(txid)?.counterparty = Some(counterparty.clone());
+    Ok(())
+  }
+
+  // the txids of the other transactions spending any of the same out points
+  pub fn conflicts(&self, txid: &str) -> Result<Vec<&str>, Error> {
+    let entry = self
+      .get(txid)
+      .ok_or_else(|| Error::UnknownTx(txid.to_string()))?;
+    Ok(
+      self
+        .entries
+        .iter()
+        .filter(|x| x.conflicts_with(entry))
+        .map(|x| x.txid.as_str())
+        .collect(),
+    )
+  }
+
+  fn invalid_transition(entry: &HistoryEntry, to: &str) -> Error {
+    Error::InvalidTransition {
+      txid: entry.txid.clone(),
+      from: entry.state.name().to_string(),
+      to: to.to_string(),
+    }
+  }
+
+  // points the wallet's spent mark of each output spent by a recorded
+  // transaction at the confirmed one, or else at the first pending one.
+  // abandoned and conflicted transactions spend nothing.
+  fn sync_spends(&self, wallet: &mut Wallet) {
+    let out_points: Vec<OutPoint> = wallet
+      .outputs()
+      .iter()
+      .map(|x| x.out_point.clone())
+      .collect();
+    for out_point in out_points {
+      let spenders: Vec<&HistoryEntry> = self
+        .entries
+        .iter()
+        .filter(|x| x.spends.contains(&out_point))
+        .collect();
+      if spenders.is_empty() {
+        continue;
+      }
+      let spender = spenders
+        .iter()
+        .find(|x| matches!(x.state, State::Confirmed { .. }))
+        .or_else(|| spenders.iter().find(|x| x.state == State::Pending));
+      wallet.set_spent_by(&out_point, spender.map(|x| x.txid.clone()));
+    }
+  }
+
+  // confirms the transaction and marks the unconfirmed transactions
+  // conflicting with it as conflicted
+  pub fn confirm(&mut self, wallet: &mut Wallet, txid: &str, height: u32) -> Result<(), Error> {
+    let entry = self
+      .get(txid)
+      .ok_or_else(|| Error::UnknownTx(txid.to_string()))?;
+    match entry.state {
+      State::Pending | State::Abandoned | State::Confirmed { .. } => (),
+      State::Conflicted { .. } => return Err(Self::invalid_transition(entry, "confirmed")),
+    }
+    if let Some(confirmed) = self
+      .entries
+      .iter()
+      .find(|x| matches!(x.state, State::Confirmed { .. }) && x.conflicts_with(entry))
+    {
+      return Err(Error::ConflictsWithConfirmed {
+        txid: txid.to_string(),
+        confirmed: confirmed.txid.clone(),
+      });
+    }
+
+    let spends = entry.spends.clone();
+    for x in self.entries.iter_mut() {
+      if x.txid == txid {
+        x.state = State::Confirmed { height };
+      } else if x.spends.iter().any(|y| spends.contains(y)) {
+        x.state = State::Conflicted {
+          by: txid.to_string(),
+        };
+      }
+    }
+    self.sync_spends(wallet);
+    Ok(())
+  }
+
+  // undoes confirm when the block holding the transaction is disconnected
+  pub fn unconfirm(&mut self, wallet: &mut Wallet, txid: &str) -> Result<(), Error> {
+    let entry = self.get_mut(txid)?;
+    if !matches!(entry.state, State::Confirmed { .. }) {
+      return Err(Self::invalid_transition(entry, "pending"));
+    }
+    entry.state = State::Pending;
+    for x in self.entries.iter_mut() {
+      if matches!(&x.state, State::Conflicted { by } if by == txid) {
+        x.state = State::Pending;
+      }
+    }
+    self.sync_spends(wallet);
+    Ok(())
+  }
+
+  // the outputs the transaction spent are released in the wallet unless
+  // another pending transaction spends them
+  pub fn abandon(&mut self, wallet: &mut Wallet, txid: &str) -> Result<(), Error> {
+    let entry = self.get_mut(txid)?;
+    match entry.state {
+      State::Pending | State::Conflicted { .. } => entry.state = State::Abandoned,
+      _ => return Err(Self::invalid_transition(entry, "abandoned")),
+    }
+    self.sync_spends(wallet);
+    Ok(())
+  }
+
+  pub fn to_json(&self) -> Result<String, Error> {
+    serde_json::to_string_pretty(&self.entries).map_err(|e| Error::FailedToExport(e.to_string()))
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, Error> {
+    let entries = serde_json::from_str(json).map_err(|e| Error::FailedToExport(e.to_string()))?;
+    Ok(History { entries })
+  }
+
+  // one row per token of each transaction
+  pub fn to_csv(&self) -> String {
+    let mut csv =
+      String::from("txid,state,height,direction,token,subid,amount,fee,memo,counterparty\n");
+    for entry in &self.entries {
+      let height = match entry.state {
+        State::Confirmed { height } => height.to_string(),
+        _ => String::new(),
+      };
+      let counterparty = entry
+        .counterparty
+        .as_ref()
+        .map(|x| hex::encode(x.to_bytes()))
+        .unwrap_or_default();
+      let amounts: Vec<[String; 3]> = if entry.amounts.is_empty() {
+        vec![Default::default()]
+      } else {
+        entry
+          .amounts
+          .iter()
+          .map(|x| {
+            [
+              x.token.to_string(),
+              x.subid.to_string(),
+              x.amount.to_string(),
+            ]
+          })
+          .collect()
+      };
+      for [token, subid, amount] in amounts {
+        csv.push_str(&format!(
+          "{},{},{},{},{},{},{},{},{},{}\n",
+          entry.txid,
+          entry.state,
+          height,
+          entry.direction,
+          token,
+          subid,
+          amount,
+          entry.fee,
+          csv_field(&entry.memo),
+          counterparty,
+        ));
+      }
+    }
+    csv
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    test_util::{gen_ctx_paying, gen_out_point, owned_out_point, TestKeys},
+    token_id::TokenId,
+  };
+
+  // a CTx spending `prev_out` of `in_amount` and paying `payments`
+  fn gen_ctx(prev_out: &OutPoint, in_amount: u64, payments: &[(&SubAddr, u64, &str)]) -> CTx {
+    gen_ctx_paying(prev_out, in_amount, payments, None)
+  }
+
+  fn ingest(wallet: &mut Wallet, history: &mut History, ctx: &CTx) -> String {
+    wallet.ingest(ctx, &ctx.out_points().unwrap()).unwrap();
+    history.record(wallet, ctx).unwrap().txid.clone()
+  }
+
+  #[test]
+  fn test_directions_and_amounts() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let mut history = History::new();
+    let own = keys.sub_addr(1);
+    let foreign = TestKeys::random().sub_addr(1);
+
+    let ctx = gen_ctx(&gen_out_point(), 10400000, &[(&own, 10000000, "salary")]);
+    let txid1 = ingest(&mut wallet, &mut history, &ctx);
+    let entry = history.get(&txid1).unwrap();
+    assert_eq!(entry.direction, Direction::In);
+    assert_eq!(entry.amounts[0].amount, 10000000);
+    assert_eq!(entry.fee, 0);
+    assert_eq!(entry.memo, "salary");
+    assert_eq!(entry.state, State::Pending);
+
+    let prev_out = owned_out_point(&wallet, &txid1);
+    let ctx = gen_ctx(
+      &prev_out,
+      10000000,
+      &[(&foreign, 3000000, "rent"), (&own, 6400000, "change")],
+    );
+    let txid2 = ingest(&mut wallet, &mut history, &ctx);
+    history.set_counterparty(&txid2, &foreign).unwrap();
+    let entry = history.get(&txid2).unwrap();
+    assert_eq!(entry.direction, Direction::Out);
+    assert_eq!(entry.fee, 600000);
+    assert_eq!(entry.amounts[0].amount, -3600000);
+    assert_eq!(entry.counterparty, Some(foreign));
+
+    let prev_out = owned_out_point(&wallet, &txid2);
+    let ctx = gen_ctx(&prev_out, 6400000, &[(&own, 6000000, "")]);
+    let txid3 = ingest(&mut wallet, &mut history, &ctx);
+    assert_eq!(
+      history.get(&txid3).unwrap().direction,
+      Direction::SelfTransfer
+    );
+  }
+
+  #[test]
+  fn test_state_machine() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let mut history = History::new();
+    let own = keys.sub_addr(1);
+
+    let prev_out = gen_out_point();
+    let ctx_a = gen_ctx(&prev_out, 1400000, &[(&own, 1000000, "a")]);
+    let ctx_b = gen_ctx(&prev_out, 1400000, &[(&own, 1000000, "b")]);
+    let a = ingest(&mut wallet, &mut history, &ctx_a);
+    let b = ingest(&mut wallet, &mut history, &ctx_b);
+    assert_eq!(history.conflicts(&a).unwrap(), vec![b.as_str()]);
+
+    history.confirm(&mut wallet, &a, 100).unwrap();
+    assert_eq!(
+      history.get(&a).unwrap().state,
+      State::Confirmed { height: 100 }
+    );
+    assert_eq!(
+      history.get(&b).unwrap().state,
+      State::Conflicted { by: a.clone() }
+    );
+    assert!(matches!(
+      history.confirm(&mut wallet, &b, 100),
+      Err(Error::InvalidTransition { .. })
+    ));
+
+    // a reorg releases the conflicted transaction
+    history.unconfirm(&mut wallet, &a).unwrap();
+    assert_eq!(history.get(&b).unwrap().state, State::Pending);
+
+    history.abandon(&mut wallet, &b).unwrap();
+    assert_eq!(history.get(&b).unwrap().state, State::Abandoned);
+    history.confirm(&mut wallet, &b, 101).unwrap();
+    assert_eq!(
+      history.get(&a).unwrap().state,
+      State::Conflicted { by: b.clone() }
+    );
+    assert!(matches!(
+      history.abandon(&mut wallet, &b),
+      Err(Error::InvalidTransition { .. })
+    ));
+    assert!(matches!(
+      history.unconfirm(&mut wallet, "unknown"),
+      Err(Error::UnknownTx(_))
+    ));
+  }
+
+  #[test]
+  fn test_conflicting_spends_of_owned_output() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let mut history = History::new();
+    let token_id = TokenId::default().unwrap();
+    let own = keys.sub_addr(1);
+    let foreign = TestKeys::random().sub_addr(1);
+
+    let ctx = gen_ctx(&gen_out_point(), 10400000, &[(&own, 10000000, "")]);
+    let txid = ingest(&mut wallet, &mut history, &ctx);
+    let prev_out = owned_out_point(&wallet, &txid);
+
+    // two transactions spending the same output of the wallet
+    let ctx_a = gen_ctx(&prev_out, 10000000, &[(&foreign, 9600000, "a")]);
+    let ctx_b = gen_ctx(&prev_out, 10000000, &[(&foreign, 9600000, "b")]);
+    let a = ingest(&mut wallet, &mut history, &ctx_a);
+    let b = ingest(&mut wallet, &mut history, &ctx_b);
+    assert_eq!(history.conflicts(&a).unwrap(), vec![b.as_str()]);
+
+    // the wallet marks the output spent by the first one only, but both
+    // spend it
+    for txid in [&a, &b] {
+      let entry = history.get(txid).unwrap();
+      assert_eq!(entry.direction, Direction::Out);
+      assert_eq!(entry.amounts[0].amount, -10000000);
+    }
+
+    let spent_by = |wallet: &Wallet| {
+      let output = wallet.outputs().iter().find(|x| x.out_point == prev_out);
+      output.unwrap().spent_by.clone()
+    };
+    assert_eq!(spent_by(&wallet), Some(a.clone()));
+
+    // the confirmed one spends the output
+    history.confirm(&mut wallet, &b, 100).unwrap();
+    assert_eq!(
+      history.get(&a).unwrap().state,
+      State::Conflicted { by: b.clone() }
+    );
+    assert_eq!(spent_by(&wallet), Some(b.clone()));
+
+    // the output is released once neither can spend it any more
+    history.unconfirm(&mut wallet, &b).unwrap();
+    history.abandon(&mut wallet, &b).unwrap();
+    assert_eq!(spent_by(&wallet), Some(a.clone()));
+    history.abandon(&mut wallet, &a).unwrap();
+    assert_eq!(spent_by(&wallet), None);
+    assert_eq!(wallet.balance(&token_id), 10000000);
+  }
+
+  #[test]
+  fn test_export() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let mut history = History::new();
+    let own = keys.sub_addr(1);
+
+    let ctx = gen_ctx(&gen_out_point(), 1400000, &[(&own, 1000000, "a, \"b\"")]);
+    let txid = ingest(&mut wallet, &mut history, &ctx);
+    history.confirm(&mut wallet, &txid, 7).unwrap();
+
+    let csv = history.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let token_id = TokenId::default().unwrap();
+    let (token, subid) = (token_id.token(), token_id.subid());
+    assert_eq!(
+      lines[1],
+      format!("{txid},confirmed,7,in,{token},{subid},1000000,0,\"a, \"\"b\"\"\",")
+    );
+
+    let json = history.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0]["state"]["status"], "confirmed");
+    assert_eq!(value[0]["direction"], "in");
+    assert_eq!(History::from_json(&json).unwrap(), history);
+  }
+}
@@ -7,7 +7,8 @@ extern "C" {
 
 }
 
-// init only once during the program lifetime
+// init only once during the program lifetime.
+// every call into libblsct runs this first, so calling it is optional.
 static INIT: Once = Once::new();
 pub fn init() {
   INIT.call_once(|| unsafe {
@@ -15,12 +16,40 @@ pub fn init() {
   });
 }
 
+// a handle to the initialized library. getting one up front moves the cost
+// of initialization out of the first call that needs it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blsct {
+  _private: (),
+}
+
+impl Blsct {
+  pub fn context() -> Blsct {
+    init();
+    Blsct { _private: () }
+  }
+
+  pub fn is_initialized() -> bool {
+    INIT.is_completed()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::scalar::Scalar;
 
   #[test]
-  fn test_init() {
-    init();
+  fn test_context() {
+    let ctx = Blsct::context();
+    assert!(Blsct::is_initialized());
+    assert_eq!(ctx, Blsct::context());
+  }
+
+  #[test]
+  fn test_implicit_init() {
+    // the constructor initializes the library itself
+    Scalar::random().unwrap();
+    assert!(Blsct::is_initialized());
   }
 }
@@ -3,10 +3,10 @@ use crate::{
   blsct_serde::BlsctSerde,
   ffi::{
     deserialize_dpk, gen_double_pub_key, gen_dpk_with_keys_acct_addr, serialize_dpk,
-    sub_addr_to_dpk, BlsctDoublePubKey, BlsctRetVal,
+    sub_addr_to_dpk, BlsctDoublePubKey, BlsctRetVal, PUBLIC_KEY_SIZE,
   },
   keys::public_key::PublicKey,
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   scalar::Scalar,
   sub_addr::SubAddr,
 };
@@ -20,6 +20,7 @@ pub struct DoublePublicKey {
 
 impl_from_retval!(DoublePublicKey);
 impl_display!(DoublePublicKey);
+impl_bytes!(DoublePublicKey);
 impl_clone!(DoublePublicKey);
 
 impl From<BlsctObj<DoublePublicKey, BlsctDoublePubKey>> for DoublePublicKey {
@@ -39,10 +40,10 @@ impl From<SubAddr> for DoublePublicKey {
 impl DoublePublicKey {
   impl_value!(BlsctDoublePubKey);
 
-  pub fn from_view_and_spend_keys<'a>(
+  pub fn from_view_and_spend_keys(
     view_key: &PublicKey,
     spend_key: &PublicKey,
-  ) -> Result<Self, blsct_obj::Error<'a>> {
+  ) -> Result<Self, blsct_obj::Error> {
     let rv = unsafe { gen_double_pub_key(view_key.value(), spend_key.value()) };
     let obj = BlsctObj::from_retval(rv)?;
     Ok(obj.into())
@@ -61,7 +62,16 @@ impl DoublePublicKey {
     obj.into()
   }
 
-  pub fn random<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  // the view key is followed by the spending key in the bytes of a dpk
+  pub fn view_pub_key(&self) -> Result<PublicKey, blsct_obj::Error> {
+    PublicKey::from_bytes(&self.obj.as_slice()[..PUBLIC_KEY_SIZE])
+  }
+
+  pub fn spending_pub_key(&self) -> Result<PublicKey, blsct_obj::Error> {
+    PublicKey::from_bytes(&self.obj.as_slice()[PUBLIC_KEY_SIZE..])
+  }
+
+  pub fn random() -> Result<Self, blsct_obj::Error> {
     let view_key = PublicKey::random()?;
     let spend_key = PublicKey::random()?;
     Self::from_view_and_spend_keys(&view_key, &spend_key)
@@ -69,6 +79,8 @@ impl DoublePublicKey {
 }
 
 impl BlsctSerde for DoublePublicKey {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_dpk(ptr as *const BlsctDoublePubKey)
   }
@@ -76,30 +88,36 @@ impl BlsctSerde for DoublePublicKey {
   unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
     deserialize_dpk(hex)
   }
+
+  // the view key followed by the spending key
+  fn is_valid_bytes(bytes: &[u8]) -> bool {
+    bytes
+      .chunks(PUBLIC_KEY_SIZE)
+      .all(<PublicKey as BlsctSerde>::is_valid_bytes)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, keys::child_key::ChildKey};
+  use crate::keys::child_key::ChildKey;
 
   #[test]
   fn test_random() {
-    init();
     let _: PublicKey = PublicKey::random().unwrap();
   }
 
   #[test]
   fn test_from_view_and_spend_keys() {
-    init();
     let a = PublicKey::random().unwrap();
     let b = PublicKey::random().unwrap();
-    let _: DoublePublicKey = DoublePublicKey::from_view_and_spend_keys(&a, &b).unwrap();
+    let dpk = DoublePublicKey::from_view_and_spend_keys(&a, &b).unwrap();
+    assert_eq!(dpk.view_pub_key().unwrap(), a);
+    assert_eq!(dpk.spending_pub_key().unwrap(), b);
   }
 
   #[test]
   fn test_from_keys_acct_addr() {
-    init();
     let child_key = ChildKey::random().unwrap();
     let tx_key = child_key.to_tx_key();
     let view_key = tx_key.to_view_key();
@@ -110,7 +128,6 @@ mod tests {
 
   #[test]
   fn test_eq() {
-    init();
     let (a, b) = {
       loop {
         let a = DoublePublicKey::random().unwrap();
@@ -128,7 +145,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = DoublePublicKey::random().unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<DoublePublicKey>(&hex).unwrap();
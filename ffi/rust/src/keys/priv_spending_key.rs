@@ -5,8 +5,8 @@ crate::macros::impl_key!(PrivSpendingKey);
 impl PrivSpendingKey {
   pub fn new(
     blinding_pub_key: PublicKey,
-    view_key: Scalar,
-    spending_key: Scalar,
+    view_key: &Scalar,
+    spending_key: &Scalar,
     account: i64,
     address: u64,
   ) -> Self {
@@ -27,11 +27,10 @@ impl PrivSpendingKey {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, keys::child_key::ChildKey};
+  use crate::keys::child_key::ChildKey;
 
   #[test]
   fn test_new() {
-    init();
     let child_key = ChildKey::random().unwrap();
     let tx_key = child_key.to_tx_key();
     let view_key = tx_key.to_view_key();
@@ -39,6 +38,6 @@ mod tests {
     let blinding_pub_key = PublicKey::random().unwrap();
 
     let _: PrivSpendingKey =
-      PrivSpendingKey::new(blinding_pub_key, view_key, spending_key, 123, 456);
+      PrivSpendingKey::new(blinding_pub_key, &view_key, &spending_key, 123, 456);
   }
 }
@@ -2,12 +2,14 @@ use crate::{
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
   ffi::{
-    calc_nonce, err_bool, gen_random_public_key, get_public_key_point, point_to_public_key,
-    scalar_to_pub_key, serialize_point, BlsctPubKey, BlsctRetVal, BLSCT_FAILURE, PUBLIC_KEY_SIZE,
+    blsPublicKeyAdd, calc_nonce, err_bool, gen_random_public_key, get_public_key_point,
+    point_to_public_key, scalar_to_pub_key, serialize_point, BlsctPubKey, BlsctRetVal,
+    BLSCT_FAILURE, PUBLIC_KEY_SIZE,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   point::Point,
   scalar::Scalar,
+  signature::{self, from_bls_pub_key, to_bls_pub_key},
   util::{build_succ_blsct_ret_val, c_hex_str_to_array},
 };
 use serde::{Deserialize, Serialize};
@@ -20,10 +22,11 @@ pub struct PublicKey {
 
 impl_from_retval!(PublicKey);
 impl_display!(PublicKey);
+impl_bytes!(PublicKey);
 impl_clone!(PublicKey);
 
 impl PublicKey {
-  pub fn random<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn random() -> Result<Self, blsct_obj::Error> {
     Self::from_retval(unsafe { gen_random_public_key() })
   }
 
@@ -40,10 +43,26 @@ impl PublicKey {
     obj.into()
   }
 
+  // the sum of the keys, whose secret key is the sum of their secret keys.
+  // an aggregate signature is verified against the keys themselves since
+  // libblsct prefixes every message with the key of its signer.
+  pub fn aggregate(pub_keys: &[PublicKey]) -> Result<Self, signature::Error> {
+    let (first, rest) = pub_keys
+      .split_first()
+      .ok_or(signature::Error::NothingToAggregate)?;
+    let mut agg_pub_key = to_bls_pub_key(first)?;
+    for pub_key in rest {
+      unsafe { blsPublicKeyAdd(&mut agg_pub_key, &to_bls_pub_key(pub_key)?) };
+    }
+    from_bls_pub_key(&agg_pub_key)
+  }
+
   impl_value!(BlsctPubKey);
 }
 
 impl BlsctSerde for PublicKey {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     // serialize the PublicKey as a Point
     let point = get_public_key_point(ptr as *const BlsctPubKey);
@@ -63,6 +82,11 @@ impl BlsctSerde for PublicKey {
       Err(_) => err_bool(BLSCT_FAILURE),
     }
   }
+
+  // a public key is held in the same bytes as its point
+  fn is_valid_bytes(bytes: &[u8]) -> bool {
+    <Point as BlsctSerde>::is_valid_bytes(bytes)
+  }
 }
 
 impl From<&PublicKey> for Point {
@@ -98,17 +122,27 @@ impl From<&Scalar> for PublicKey {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, keys::child_key::ChildKey};
+  use crate::keys::child_key::ChildKey;
+
+  #[test]
+  fn test_aggregate() {
+    let pub_key = |n| PublicKey::from(&Scalar::new(n).unwrap());
+    let agg_pub_key = PublicKey::aggregate(&[pub_key(2), pub_key(3)]).unwrap();
+    assert_eq!(agg_pub_key, pub_key(5));
+    assert_eq!(PublicKey::aggregate(&[pub_key(2)]).unwrap(), pub_key(2));
+    assert!(matches!(
+      PublicKey::aggregate(&[]),
+      Err(signature::Error::NothingToAggregate)
+    ));
+  }
 
   #[test]
   fn test_random() {
-    init();
     let _: PublicKey = PublicKey::random().unwrap();
   }
 
   #[test]
   fn test_generate_nonce() {
-    init();
     let pub_key = PublicKey::random().unwrap();
     let child_key = ChildKey::random().unwrap();
     let view_key = child_key.to_tx_key().to_view_key();
@@ -117,28 +151,24 @@ mod tests {
 
   #[test]
   fn test_from_scalar() {
-    init();
     let scalar = Scalar::random().unwrap();
     let _: PublicKey = (&scalar).into();
   }
 
   #[test]
   fn test_from_point() {
-    init();
     let point = Point::random().unwrap();
     let _: PublicKey = (&point).into();
   }
 
   #[test]
   fn test_to_point() {
-    init();
     let pub_key = PublicKey::random().unwrap();
     let _: Point = (&pub_key).into();
   }
 
   #[test]
   fn test_eq() {
-    init();
     let (a, b) = {
       loop {
         let a = PublicKey::random().unwrap();
@@ -156,7 +186,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = PublicKey::random().unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<PublicKey>(&hex).unwrap();
@@ -0,0 +1,580 @@
+use crate::{
+  chain::Chain,
+  keys::child_key::ChildKey,
+  scalar::Scalar,
+  secret::{SecretScalar, REDACTED},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+  aead::{Aead, KeyInit, Payload},
+  Key, XChaCha20Poly1305, XNonce,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+  collections::BTreeMap,
+  fmt, fs,
+  path::Path,
+  time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+use zeroize::Zeroize;
+
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KEY_SIZE: usize = 32;
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 24;
+const SEED_SIZE: usize = 32;
+
+// upper bounds of the parameters read from a keystore file. they keep a
+// crafted file from making unlock allocate or spin without bound.
+pub const MAX_M_COST_KIB: u32 = 4 * 1024 * 1024;
+pub const MAX_T_COST: u32 = 64;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("Wrong password or corrupted keystore")]
+  WrongPassword,
+
+  #[error("Unsupported keystore version {0}")]
+  UnsupportedVersion(u32),
+
+  #[error("Invalid keystore: {0}")]
+  InvalidFormat(String),
+
+  #[error("Invalid KDF parameters: {0}")]
+  InvalidKdfParams(String),
+
+  #[error("Failed to encrypt the seed")]
+  FailedToEncrypt,
+
+  #[error("Failed to read or write the keystore: {0}")]
+  Io(#[from] std::io::Error),
+}
+
+// Argon2id parameters. the defaults follow the OWASP recommendation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+  pub m_cost_kib: u32,
+  pub t_cost: u32,
+  pub p_cost: u32,
+}
+
+impl KdfParams {
+  fn check(&self) -> Result<(), Error> {
+    if self.m_cost_kib > MAX_M_COST_KIB {
+      return Err(Error::InvalidKdfParams(format!(
+        "m_cost_kib {} exceeds {MAX_M_COST_KIB}",
+        self.m_cost_kib
+      )));
+    }
+    if self.t_cost > MAX_T_COST {
+      return Err(Error::InvalidKdfParams(format!(
+        "t_cost {} exceeds {MAX_T_COST}",
+        self.t_cost
+      )));
+    }
+    Ok(())
+  }
+}
+
+impl Default for KdfParams {
+  fn default() -> Self {
+    KdfParams {
+      m_cost_kib: 64 * 1024,
+      t_cost: 3,
+      p_cost: 1,
+    }
+  }
+}
+
+// stored in plaintext so that a wallet can be listed without the password.
+// it is authenticated along with the seed, so it cannot be altered either.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+  pub chain: Chain,
+  pub created_at: u64,
+  pub account_labels: BTreeMap<i64, String>,
+}
+
+impl Metadata {
+  pub fn new(chain: Chain) -> Self {
+    let created_at = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|x| x.as_secs())
+      .unwrap_or(0);
+    Metadata {
+      chain,
+      created_at,
+      account_labels: BTreeMap::new(),
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Kdf {
+  algorithm: KdfAlgorithm,
+  params: KdfParams,
+  #[serde(with = "hex::serde")]
+  salt: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum KdfAlgorithm {
+  Argon2id,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Cipher {
+  algorithm: CipherAlgorithm,
+  #[serde(with = "hex::serde")]
+  nonce: Vec<u8>,
+  #[serde(with = "hex::serde")]
+  ciphertext: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CipherAlgorithm {
+  XChaCha20Poly1305,
+}
+
+// a password-encrypted wallet seed. the 32 bytes of the seed are encrypted
+// with XChaCha20-Poly1305 under a key derived from the password with Argon2id.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+  version: u32,
+  metadata: Metadata,
+  kdf: Kdf,
+  cipher: Cipher,
+}
+
+impl Keystore {
+  pub fn create(seed: &Scalar, password: &str, metadata: Metadata) -> Result<Self, Error> {
+    Keystore::create_with_params(seed, password, metadata, KdfParams::default())
+  }
+
+  pub fn create_with_params(
+    seed: &Scalar,
+    password: &str,
+    metadata: Metadata,
+    kdf_params: KdfParams,
+  ) -> Result<Self, Error> {
+    let mut plaintext = seed.to_bytes();
+    let res = Keystore::seal(&plaintext, password, metadata, kdf_params);
+    plaintext.zeroize();
+    res
+  }
+
+  // returns the seed to be passed to ChildKey::from_seed
+  pub fn unlock(&self, password: &str) -> Result<SecretScalar, Error> {
+    let mut plaintext = self.open(password)?;
+    let seed = if plaintext.len() == SEED_SIZE {
+      Scalar::from_bytes(&plaintext).map_err(|e| Error::InvalidFormat(e.to_string()))
+    } else {
+      Err(Error::InvalidFormat("seed size".to_string()))
+    };
+    plaintext.zeroize();
+    Ok(SecretScalar::new(seed?))
+  }
+
+  pub fn unlock_child_key(&self, password: &str) -> Result<ChildKey, Error> {
+    let seed = self.unlock(password)?;
+    Ok(ChildKey::from_seed(&seed))
+  }
+
+  // re-encrypts the seed with a fresh salt and nonce
+  pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<(), Error> {
+    let mut plaintext = self.open(old_password)?;
+    let res = Keystore::seal(
+      &plaintext,
+      new_password,
+      self.metadata.clone(),
+      self.kdf.params.clone(),
+    );
+    plaintext.zeroize();
+    *self = res?;
+    Ok(())
+  }
+
+  // the metadata is authenticated, so updating it requires the password
+  pub fn set_metadata(&mut self, password: &str, metadata: Metadata) -> Result<(), Error> {
+    let mut plaintext = self.open(password)?;
+    let res = Keystore::seal(&plaintext, password, metadata, self.kdf.params.clone());
+    plaintext.zeroize();
+    *self = res?;
+    Ok(())
+  }
+
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  pub fn metadata(&self) -> &Metadata {
+    &self.metadata
+  }
+
+  pub fn kdf_params(&self) -> &KdfParams {
+    &self.kdf.params
+  }
+
+  pub fn to_json(&self) -> Result<String, Error> {
+    serde_json::to_string_pretty(self).map_err(|e| Error::InvalidFormat(e.to_string()))
+  }
+
+  // keystores written by older versions are migrated to the current version
+  pub fn from_json(json: &str) -> Result<Self, Error> {
+    let value =
+      serde_json::from_str::<Value>(json).map_err(|e| Error::InvalidFormat(e.to_string()))?;
+    let value = migrate(value, MIGRATIONS)?;
+    let keystore =
+      serde_json::from_value::<Keystore>(value).map_err(|e| Error::InvalidFormat(e.to_string()))?;
+    keystore.kdf.params.check()?;
+    Ok(keystore)
+  }
+
+  pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+    fs::write(path, self.to_json()?)?;
+    Ok(())
+  }
+
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+    Keystore::from_json(&fs::read_to_string(path)?)
+  }
+
+  fn seal(
+    plaintext: &[u8],
+    password: &str,
+    metadata: Metadata,
+    kdf_params: KdfParams,
+  ) -> Result<Self, Error> {
+    let mut rng = rand::rng();
+    let mut salt = vec![0u8; SALT_SIZE];
+    rng.fill(&mut salt[..]);
+    let mut nonce = vec![0u8; NONCE_SIZE];
+    rng.fill(&mut nonce[..]);
+
+    let kdf = Kdf {
+      algorithm: KdfAlgorithm::Argon2id,
+      params: kdf_params,
+      salt,
+    };
+    let aad = associated_data(KEYSTORE_VERSION, &metadata)?;
+    let mut key = derive_key(password, &kdf)?;
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&key))
+      .encrypt(
+        XNonce::from_slice(&nonce),
+        Payload {
+          msg: plaintext,
+          aad: &aad,
+        },
+      )
+      .map_err(|_| Error::FailedToEncrypt);
+    key.zeroize();
+
+    Ok(Keystore {
+      version: KEYSTORE_VERSION,
+      metadata,
+      kdf,
+      cipher: Cipher {
+        algorithm: CipherAlgorithm::XChaCha20Poly1305,
+        nonce,
+        ciphertext: ciphertext?,
+      },
+    })
+  }
+
+  fn open(&self, password: &str) -> Result<Vec<u8>, Error> {
+    if self.cipher.nonce.len() != NONCE_SIZE {
+      return Err(Error::InvalidFormat("nonce size".to_string()));
+    }
+    let aad = associated_data(self.version, &self.metadata)?;
+    let mut key = derive_key(password, &self.kdf)?;
+    let plaintext = XChaCha20Poly1305::new(Key::from_slice(&key))
+      .decrypt(
+        XNonce::from_slice(&self.cipher.nonce),
+        Payload {
+          msg: &self.cipher.ciphertext,
+          aad: &aad,
+        },
+      )
+      .map_err(|_| Error::WrongPassword);
+    key.zeroize();
+    plaintext
+  }
+}
+
+// the ciphertext is shown, but it is of no use without the password
+impl fmt::Debug for Keystore {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Keystore")
+      .field("version", &self.version)
+      .field("metadata", &self.metadata)
+      .field("kdf_params", &self.kdf.params)
+      .field("seed", &REDACTED)
+      .finish()
+  }
+}
+
+fn derive_key(password: &str, kdf: &Kdf) -> Result<[u8; KEY_SIZE], Error> {
+  kdf.params.check()?;
+  let params = Params::new(
+    kdf.params.m_cost_kib,
+    kdf.params.t_cost,
+    kdf.params.p_cost,
+    Some(KEY_SIZE),
+  )
+  .map_err(|e| Error::InvalidKdfParams(e.to_string()))?;
+  let mut key = [0u8; KEY_SIZE];
+  Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+    .hash_password_into(password.as_bytes(), &kdf.salt, &mut key)
+    .map_err(|e| Error::InvalidKdfParams(e.to_string()))?;
+  Ok(key)
+}
+
+fn associated_data(version: u32, metadata: &Metadata) -> Result<Vec<u8>, Error> {
+  let mut aad = version.to_le_bytes().to_vec();
+  aad.extend(serde_json::to_vec(metadata).map_err(|e| Error::InvalidFormat(e.to_string()))?);
+  Ok(aad)
+}
+
+// upgrades a keystore of version `n` to version `n + 1`
+pub type Migration = fn(Value) -> Result<Value, Error>;
+
+// MIGRATIONS[i] upgrades version i + 1 to version i + 2.
+// a new version adds its migration here and bumps KEYSTORE_VERSION.
+const MIGRATIONS: &[Migration] = &[];
+
+fn migrate(mut value: Value, migrations: &[Migration]) -> Result<Value, Error> {
+  let current_version = migrations.len() as u32 + 1;
+  loop {
+    let version = value
+      .get("version")
+      .and_then(Value::as_u64)
+      .and_then(|x| u32::try_from(x).ok())
+      .ok_or(Error::InvalidFormat("version".to_string()))?;
+    if version == current_version {
+      return Ok(value);
+    }
+    if version == 0 || version > current_version {
+      return Err(Error::UnsupportedVersion(version));
+    }
+    value = migrations[version as usize - 1](value)?;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // keeps the tests fast. never use these outside of tests.
+  fn weak_params() -> KdfParams {
+    KdfParams {
+      m_cost_kib: 64,
+      t_cost: 1,
+      p_cost: 1,
+    }
+  }
+
+  fn gen_keystore(seed: &Scalar) -> Keystore {
+    let mut metadata = Metadata::new(Chain::Testnet);
+    metadata.account_labels.insert(0, "savings".to_string());
+    Keystore::create_with_params(seed, "correct horse", metadata, weak_params()).unwrap()
+  }
+
+  #[test]
+  fn test_create_unlock() {
+    let seed = Scalar::random().unwrap();
+    let keystore = gen_keystore(&seed);
+    assert_eq!(
+      keystore.unlock("correct horse").unwrap().expose_secret(),
+      &seed
+    );
+    assert_eq!(
+      keystore.unlock_child_key("correct horse").unwrap(),
+      ChildKey::from_seed(&seed)
+    );
+    assert!(matches!(
+      keystore.unlock("battery staple"),
+      Err(Error::WrongPassword)
+    ));
+  }
+
+  // a v1 keystore of the seed 0x01..0x20 under "correct horse", written once
+  // and checked in so that a change to the format cannot go unnoticed
+  const KEYSTORE_V1: &str = include_str!("../testdata/keystore_v1.json");
+
+  #[test]
+  fn test_known_answer() {
+    let keystore = Keystore::from_json(KEYSTORE_V1).unwrap();
+    assert_eq!(keystore.version(), 1);
+    assert_eq!(keystore.metadata().created_at, 1700000000);
+    assert_eq!(keystore.kdf_params(), &weak_params());
+
+    let seed_bytes = (1..=32).collect::<Vec<u8>>();
+    assert_eq!(keystore.open("correct horse").unwrap(), seed_bytes);
+    assert_eq!(
+      keystore
+        .unlock("correct horse")
+        .unwrap()
+        .expose_secret()
+        .to_bytes(),
+      seed_bytes
+    );
+    assert!(matches!(
+      keystore.unlock("battery staple"),
+      Err(Error::WrongPassword)
+    ));
+  }
+
+  #[test]
+  fn test_plaintext_is_seed_bytes() {
+    let seed = Scalar::random().unwrap();
+    let keystore = gen_keystore(&seed);
+    assert_eq!(keystore.open("correct horse").unwrap(), seed.to_bytes());
+  }
+
+  #[test]
+  fn test_kdf_params_are_capped() {
+    let json = KEYSTORE_V1.replace(r#""m_cost_kib": 64"#, r#""m_cost_kib": 4294967295"#);
+    assert!(matches!(
+      Keystore::from_json(&json),
+      Err(Error::InvalidKdfParams(_))
+    ));
+    let json = KEYSTORE_V1.replace(r#""t_cost": 1"#, r#""t_cost": 4294967295"#);
+    assert!(matches!(
+      Keystore::from_json(&json),
+      Err(Error::InvalidKdfParams(_))
+    ));
+
+    let params = KdfParams {
+      t_cost: MAX_T_COST + 1,
+      ..weak_params()
+    };
+    let metadata = Metadata::new(Chain::Testnet);
+    assert!(matches!(
+      Keystore::create_with_params(&Scalar::random().unwrap(), "pw", metadata, params),
+      Err(Error::InvalidKdfParams(_))
+    ));
+  }
+
+  #[test]
+  fn test_change_password() {
+    let seed = Scalar::random().unwrap();
+    let mut keystore = gen_keystore(&seed);
+    let prev_ciphertext = keystore.cipher.ciphertext.clone();
+
+    assert!(matches!(
+      keystore.change_password("wrong", "new"),
+      Err(Error::WrongPassword)
+    ));
+    keystore.change_password("correct horse", "new").unwrap();
+    assert_ne!(keystore.cipher.ciphertext, prev_ciphertext);
+    assert!(keystore.unlock("correct horse").is_err());
+    assert_eq!(keystore.unlock("new").unwrap().expose_secret(), &seed);
+  }
+
+  #[test]
+  fn test_json_round_trip() {
+    let seed = Scalar::random().unwrap();
+    let keystore = gen_keystore(&seed);
+    let json = keystore.to_json().unwrap();
+    let seed_hex = serde_json::to_string(&seed).unwrap();
+    assert!(!json.contains(seed_hex.trim_matches('"')));
+
+    let loaded = Keystore::from_json(&json).unwrap();
+    assert_eq!(loaded, keystore);
+    assert_eq!(loaded.metadata().chain, Chain::Testnet);
+    assert_eq!(loaded.metadata().account_labels[&0], "savings");
+    assert_eq!(
+      loaded.unlock("correct horse").unwrap().expose_secret(),
+      &seed
+    );
+  }
+
+  #[test]
+  fn test_save_load() {
+    let seed = Scalar::random().unwrap();
+    let keystore = gen_keystore(&seed);
+    let path = std::env::temp_dir().join(format!("keystore-{}.json", rand::random::<u64>()));
+    keystore.save(&path).unwrap();
+    let loaded = Keystore::load(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap(), keystore);
+  }
+
+  #[test]
+  fn test_tampered_metadata() {
+    let keystore = gen_keystore(&Scalar::random().unwrap());
+    let json = keystore.to_json().unwrap().replace("savings", "spending");
+    let tampered = Keystore::from_json(&json).unwrap();
+    assert!(matches!(
+      tampered.unlock("correct horse"),
+      Err(Error::WrongPassword)
+    ));
+  }
+
+  #[test]
+  fn test_set_metadata() {
+    let seed = Scalar::random().unwrap();
+    let mut keystore = gen_keystore(&seed);
+    let mut metadata = keystore.metadata().clone();
+    metadata.account_labels.insert(1, "daily".to_string());
+    keystore.set_metadata("correct horse", metadata).unwrap();
+    assert_eq!(keystore.metadata().account_labels.len(), 2);
+    assert_eq!(
+      keystore.unlock("correct horse").unwrap().expose_secret(),
+      &seed
+    );
+  }
+
+  #[test]
+  fn test_debug_is_redacted() {
+    let seed = Scalar::random().unwrap();
+    let keystore = gen_keystore(&seed);
+    let debug = format!("{keystore:?}");
+    assert!(debug.contains(REDACTED));
+    assert!(!debug.contains(&hex::encode(&keystore.cipher.ciphertext)));
+  }
+
+  #[test]
+  fn test_unsupported_version() {
+    let json = r#"{"version": 2}"#;
+    assert!(matches!(
+      Keystore::from_json(json),
+      Err(Error::UnsupportedVersion(2))
+    ));
+    assert!(matches!(
+      Keystore::from_json(r#"{"metadata": {}}"#),
+      Err(Error::InvalidFormat(_))
+    ));
+  }
+
+  #[test]
+  fn test_migrate() {
+    fn v1_to_v2(mut value: Value) -> Result<Value, Error> {
+      value["version"] = 2.into();
+      value["upgraded"] = true.into();
+      Ok(value)
+    }
+    fn v2_to_v3(mut value: Value) -> Result<Value, Error> {
+      value["version"] = 3.into();
+      Ok(value)
+    }
+    let migrations: &[Migration] = &[v1_to_v2, v2_to_v3];
+
+    let value = migrate(serde_json::json!({"version": 1}), migrations).unwrap();
+    assert_eq!(value, serde_json::json!({"version": 3, "upgraded": true}));
+
+    let value = migrate(serde_json::json!({"version": 3}), migrations).unwrap();
+    assert_eq!(value, serde_json::json!({"version": 3}));
+
+    assert!(matches!(
+      migrate(serde_json::json!({"version": 4}), migrations),
+      Err(Error::UnsupportedVersion(4))
+    ));
+  }
+}
@@ -1,32 +1,57 @@
 pub mod address;
 pub mod amount_recovery_req;
 pub mod amount_recovery_res;
+pub mod audit;
+#[cfg(feature = "async")]
+pub mod blocking_pool;
 pub mod chain;
+pub mod chain_source;
 pub mod ctx;
 pub mod ctx_id;
 pub mod ctx_in;
 pub mod ctx_ins;
 pub mod ctx_out;
 pub mod ctx_outs;
+pub mod ctx_view;
+pub mod error;
+pub mod fee;
 pub mod hash_id;
+pub mod history;
 pub mod initializer;
 pub mod keys;
+pub mod keystore;
+pub mod memo;
 pub mod out_point;
+pub mod payment_proof;
+pub mod payment_request;
 pub mod point;
+pub mod pst;
 pub mod range_proof;
+pub mod rbf;
+pub mod rpc;
 pub mod scalar;
 pub mod script;
+pub mod secret;
 pub mod signature;
+pub mod staking;
 pub mod sub_addr;
 pub mod sub_addr_id;
 pub mod token_id;
 pub mod tx_in;
 pub mod tx_out;
+pub mod unsigned_input;
+pub mod unsigned_output;
+pub mod unsigned_transaction;
 pub mod vector_predicate;
 pub mod view_tag;
+pub mod wallet;
+
+pub use error::Error;
+pub use initializer::Blsct;
 
 mod blsct_obj;
 mod blsct_serde;
+mod ctx_ser;
 mod ffi;
 mod macros;
 mod test_util;
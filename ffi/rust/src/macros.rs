@@ -12,7 +12,7 @@ macro_rules! impl_from_retval {
   ($type:ident) => {
     impl $type {
       #[inline]
-      pub fn from_retval<'a>(rv: *mut BlsctRetVal) -> Result<Self, crate::blsct_obj::Error<'a>> {
+      pub fn from_retval(rv: *mut BlsctRetVal) -> Result<Self, crate::blsct_obj::Error> {
         let obj = BlsctObj::from_retval(rv)?;
         let inst = Self { obj };
         Ok(inst)
@@ -48,12 +48,64 @@ macro_rules! impl_value_raw_const_obj {
   };
 }
 
+// for objects held in plain buffers, which are copied as is. copying fails
+// only when memory runs out, which Clone treats as std collections do.
+// use try_clone to handle it instead.
 macro_rules! impl_clone {
   ($name:ident) => {
+    crate::macros::impl_try_clone!($name);
+
     impl Clone for $name {
       fn clone(&self) -> Self {
-        let hex = bincode::serialize(self).unwrap();
-        bincode::deserialize::<$name>(&hex).unwrap()
+        self
+          .try_clone()
+          .expect(concat!("Failed to allocate memory for ", stringify!($name)))
+      }
+    }
+  };
+}
+
+// objects that own C++ resources are copied by a serialization round trip
+// through libblsct, which can fail for reasons other than memory. they
+// implement try_clone only.
+macro_rules! impl_try_clone {
+  ($name:ident) => {
+    impl $name {
+      pub fn try_clone(&self) -> Result<Self, crate::blsct_obj::Error> {
+        Ok(self.obj.try_clone()?.into())
+      }
+    }
+  };
+}
+
+// the raw bytes of objects held in plain buffers, without the hex round trip
+// through libblsct
+macro_rules! impl_bytes {
+  ($name:ident) => {
+    impl $name {
+      pub fn to_bytes(&self) -> Vec<u8> {
+        self.obj.as_slice().to_vec()
+      }
+
+      pub fn from_bytes(bytes: &[u8]) -> Result<Self, crate::blsct_obj::Error> {
+        Ok(crate::blsct_obj::BlsctObj::from_bytes(bytes)?.into())
+      }
+    }
+  };
+}
+
+// objects holding secret key material print only their type name
+macro_rules! impl_redacted {
+  ($name:ident) => {
+    impl std::fmt::Debug for $name {
+      fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", stringify!($name), crate::secret::REDACTED)
+      }
+    }
+
+    impl std::fmt::Display for $name {
+      fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", stringify!($name), crate::secret::REDACTED)
       }
     }
   };
@@ -64,9 +116,34 @@ macro_rules! impl_key {
     use crate::{blsct_obj::BlsctObj, ffi::BlsctScalar, scalar::Scalar};
     use serde::{Deserialize, Serialize};
 
-    #[derive(PartialEq, Eq, Debug, Deserialize, Serialize)]
+    // holds secret key material that is wiped on drop and never printed
+    #[derive(Deserialize, Serialize)]
     pub struct $name(Scalar);
 
+    impl subtle::ConstantTimeEq for $name {
+      fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.0, &other.0)
+      }
+    }
+
+    impl PartialEq for $name {
+      fn eq(&self, other: &Self) -> bool {
+        subtle::ConstantTimeEq::ct_eq(self, other).into()
+      }
+    }
+
+    impl Eq for $name {}
+
+    impl Drop for $name {
+      fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+      }
+    }
+
+    impl zeroize::ZeroizeOnDrop for $name {}
+
+    crate::macros::impl_redacted!($name);
+
     impl $name {
       pub fn value(&self) -> *const BlsctScalar {
         self.0.value()
@@ -79,19 +156,30 @@ macro_rules! impl_key {
       }
     }
 
+    impl $name {
+      pub fn try_clone(&self) -> Result<Self, crate::blsct_obj::Error> {
+        Ok($name(self.0.try_clone()?))
+      }
+    }
+
+    // the scalar is a plain buffer, see impl_clone
     impl Clone for $name {
       fn clone(&self) -> Self {
-        let hex = bincode::serialize(self).unwrap();
-        bincode::deserialize::<$name>(&hex).unwrap()
+        self
+          .try_clone()
+          .expect(concat!("Failed to allocate memory for ", stringify!($name)))
       }
     }
   };
 }
 
+pub(crate) use impl_bytes;
 pub(crate) use impl_clone;
 pub(crate) use impl_display;
 pub(crate) use impl_from_retval;
 pub(crate) use impl_key;
+pub(crate) use impl_redacted;
 pub(crate) use impl_size;
+pub(crate) use impl_try_clone;
 pub(crate) use impl_value;
 pub(crate) use impl_value_raw_const_obj;
@@ -0,0 +1,425 @@
+use crate::amount_recovery_res::AmountRecoveryRes;
+pub use crate::ffi::MAX_MEMO_LEN;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, str::Utf8Error};
+
+// structured memos are encoded as text so that they survive the C string
+// round trip. they start with STRUCTURED_PREFIX followed by `key=value`
+// fields separated by FIELD_SEPARATOR.
+const STRUCTURED_PREFIX: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+const PAYMENT_ID_KEY: &str = "p";
+const INVOICE_REF_KEY: &str = "i";
+const RETURN_ADDR_HINT_KEY: &str = "r";
+
+pub const PAYMENT_ID_LEN: usize = 8;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+  TooLong(usize),
+  ContainsNul,
+  InvalidUtf8(Utf8Error),
+  ReservedPrefix,
+  ReservedChar(&'static str),
+  EmptyStructuredMemo,
+  MalformedField(String),
+  UnknownField(String),
+  DuplicateField(String),
+  InvalidPaymentId(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::TooLong(len) => {
+        write!(
+          f,
+          "Memo is {len} bytes long, exceeding {MAX_MEMO_LEN} bytes"
+        )
+      }
+      Error::ContainsNul => write!(f, "Memo contains a NUL character"),
+      Error::InvalidUtf8(e) => write!(f, "Memo is not valid UTF-8: {e:?}"),
+      Error::ReservedPrefix => write!(f, "Text memo starts with the structured memo prefix"),
+      Error::ReservedChar(field) => write!(f, "{field} contains a reserved character"),
+      Error::EmptyStructuredMemo => write!(f, "Structured memo has no fields"),
+      Error::MalformedField(field) => write!(f, "Malformed field: {field}"),
+      Error::UnknownField(key) => write!(f, "Unknown field: {key}"),
+      Error::DuplicateField(key) => write!(f, "Duplicate field: {key}"),
+      Error::InvalidPaymentId(s) => write!(f, "Invalid payment id: {s}"),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct PaymentId([u8; PAYMENT_ID_LEN]);
+
+impl PaymentId {
+  pub fn new(bytes: [u8; PAYMENT_ID_LEN]) -> Self {
+    PaymentId(bytes)
+  }
+
+  pub fn random() -> Self {
+    PaymentId(rand::random())
+  }
+
+  pub fn as_bytes(&self) -> &[u8; PAYMENT_ID_LEN] {
+    &self.0
+  }
+}
+
+impl fmt::Display for PaymentId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", hex::encode(self.0))
+  }
+}
+
+impl FromStr for PaymentId {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut bytes = [0u8; PAYMENT_ID_LEN];
+    hex::decode_to_slice(s, &mut bytes).map_err(|_| Error::InvalidPaymentId(s.to_string()))?;
+    Ok(PaymentId(bytes))
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct StructuredMemo {
+  pub payment_id: Option<PaymentId>,
+  pub invoice_ref: Option<String>,
+  pub return_addr_hint: Option<String>,
+}
+
+impl StructuredMemo {
+  fn encode(&self) -> String {
+    let mut fields = vec![];
+    if let Some(payment_id) = &self.payment_id {
+      fields.push(format!("{PAYMENT_ID_KEY}={payment_id}"));
+    }
+    if let Some(invoice_ref) = &self.invoice_ref {
+      fields.push(format!("{INVOICE_REF_KEY}={invoice_ref}"));
+    }
+    if let Some(return_addr_hint) = &self.return_addr_hint {
+      fields.push(format!("{RETURN_ADDR_HINT_KEY}={return_addr_hint}"));
+    }
+    format!(
+      "{STRUCTURED_PREFIX}{}",
+      fields.join(&FIELD_SEPARATOR.to_string())
+    )
+  }
+
+  // `s` is the encoded memo without the prefix
+  fn decode(s: &str) -> Result<Self, Error> {
+    let mut memo = StructuredMemo::default();
+    if s.is_empty() {
+      return Err(Error::EmptyStructuredMemo);
+    }
+    for field in s.split(FIELD_SEPARATOR) {
+      let (key, value) = field
+        .split_once('=')
+        .ok_or(Error::MalformedField(field.to_string()))?;
+      let is_dup = match key {
+        PAYMENT_ID_KEY => memo.payment_id.replace(value.parse()?).is_some(),
+        INVOICE_REF_KEY => memo.invoice_ref.replace(value.to_string()).is_some(),
+        RETURN_ADDR_HINT_KEY => memo.return_addr_hint.replace(value.to_string()).is_some(),
+        _ => return Err(Error::UnknownField(key.to_string())),
+      };
+      if is_dup {
+        return Err(Error::DuplicateField(key.to_string()));
+      }
+    }
+    Ok(memo)
+  }
+
+  fn validate(&self) -> Result<(), Error> {
+    if self.payment_id.is_none() && self.invoice_ref.is_none() && self.return_addr_hint.is_none() {
+      return Err(Error::EmptyStructuredMemo);
+    }
+    let is_reserved = |c: char| c == STRUCTURED_PREFIX || c == FIELD_SEPARATOR || c == '\0';
+    if let Some(invoice_ref) = &self.invoice_ref {
+      if invoice_ref.contains(is_reserved) {
+        return Err(Error::ReservedChar("Invoice reference"));
+      }
+    }
+    if let Some(return_addr_hint) = &self.return_addr_hint {
+      if return_addr_hint.contains(is_reserved) {
+        return Err(Error::ReservedChar("Return address hint"));
+      }
+    }
+    Ok(())
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Memo {
+  Text(String),
+  Structured(StructuredMemo),
+}
+
+impl Memo {
+  pub fn text(s: &str) -> Result<Self, Error> {
+    if s.starts_with(STRUCTURED_PREFIX) {
+      return Err(Error::ReservedPrefix);
+    }
+    let memo = Memo::Text(s.to_string());
+    memo.validate()?;
+    Ok(memo)
+  }
+
+  pub fn structured(memo: StructuredMemo) -> Result<Self, Error> {
+    memo.validate()?;
+    let memo = Memo::Structured(memo);
+    memo.validate()?;
+    Ok(memo)
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+    let s = std::str::from_utf8(bytes).map_err(Error::InvalidUtf8)?;
+    s.parse()
+  }
+
+  // fails with InvalidUtf8 rather than accepting the replaced text of res.msg
+  pub fn from_recovery_res(res: &AmountRecoveryRes) -> Result<Self, Error> {
+    Memo::from_bytes(&res.msg_bytes)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    matches!(self, Memo::Text(s) if s.is_empty())
+  }
+
+  pub fn payment_id(&self) -> Option<&PaymentId> {
+    match self {
+      Memo::Structured(memo) => memo.payment_id.as_ref(),
+      Memo::Text(_) => None,
+    }
+  }
+
+  pub fn invoice_ref(&self) -> Option<&str> {
+    match self {
+      Memo::Structured(memo) => memo.invoice_ref.as_deref(),
+      Memo::Text(_) => None,
+    }
+  }
+
+  pub fn return_addr_hint(&self) -> Option<&str> {
+    match self {
+      Memo::Structured(memo) => memo.return_addr_hint.as_deref(),
+      Memo::Text(_) => None,
+    }
+  }
+
+  // returns the string to be passed to TxOut::new
+  pub fn encode(&self) -> String {
+    match self {
+      Memo::Text(s) => s.clone(),
+      Memo::Structured(memo) => memo.encode(),
+    }
+  }
+
+  fn validate(&self) -> Result<(), Error> {
+    let s = self.encode();
+    if s.contains('\0') {
+      return Err(Error::ContainsNul);
+    }
+    if s.len() > MAX_MEMO_LEN {
+      return Err(Error::TooLong(s.len()));
+    }
+    Ok(())
+  }
+}
+
+impl Default for Memo {
+  fn default() -> Self {
+    Memo::Text(String::new())
+  }
+}
+
+impl fmt::Display for Memo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.encode())
+  }
+}
+
+impl FromStr for Memo {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let memo = match s.strip_prefix(STRUCTURED_PREFIX) {
+      Some(fields) => Memo::Structured(StructuredMemo::decode(fields)?),
+      None => Memo::Text(s.to_string()),
+    };
+    memo.validate()?;
+    Ok(memo)
+  }
+}
+
+impl TryFrom<&AmountRecoveryRes> for Memo {
+  type Error = Error;
+
+  fn try_from(res: &AmountRecoveryRes) -> Result<Self, Self::Error> {
+    Memo::from_recovery_res(res)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ffi::TxOutputType, keys::child_key::ChildKey, keys::public_key::PublicKey, sub_addr::SubAddr,
+    sub_addr_id::SubAddrId, token_id::TokenId, tx_out::TxOut,
+  };
+
+  fn gen_structured_memo() -> StructuredMemo {
+    StructuredMemo {
+      payment_id: Some(PaymentId::new([1, 2, 3, 4, 5, 6, 7, 8])),
+      invoice_ref: Some("INV-2024-0001".to_string()),
+      return_addr_hint: Some("tnv1qqq".to_string()),
+    }
+  }
+
+  #[test]
+  fn test_text() {
+    let memo = Memo::text("navio").unwrap();
+    assert_eq!(memo.encode(), "navio");
+    assert_eq!(memo.payment_id(), None);
+    assert_eq!("navio".parse::<Memo>().unwrap(), memo);
+  }
+
+  #[test]
+  fn test_text_validation() {
+    let s = "a".repeat(MAX_MEMO_LEN);
+    assert!(Memo::text(&s).is_ok());
+
+    let s = "a".repeat(MAX_MEMO_LEN + 1);
+    assert_eq!(Memo::text(&s), Err(Error::TooLong(MAX_MEMO_LEN + 1)));
+
+    // multi-byte characters count by bytes
+    let s = "ä".repeat(MAX_MEMO_LEN / 2 + 1);
+    assert_eq!(Memo::text(&s), Err(Error::TooLong(MAX_MEMO_LEN + 2)));
+
+    assert_eq!(Memo::text("na\0vio"), Err(Error::ContainsNul));
+    assert_eq!(Memo::text("\u{1e}navio"), Err(Error::ReservedPrefix));
+  }
+
+  #[test]
+  fn test_from_bytes() {
+    assert_eq!(
+      Memo::from_bytes(b"navio").unwrap(),
+      Memo::text("navio").unwrap()
+    );
+    assert!(matches!(
+      Memo::from_bytes(&[0xff, 0xfe]),
+      Err(Error::InvalidUtf8(_))
+    ));
+  }
+
+  #[test]
+  fn test_structured_round_trip() {
+    let memo = Memo::structured(gen_structured_memo()).unwrap();
+    let s = memo.encode();
+    assert!(s.len() <= MAX_MEMO_LEN);
+
+    let parsed = s.parse::<Memo>().unwrap();
+    assert_eq!(parsed, memo);
+    assert_eq!(
+      parsed.payment_id(),
+      Some(&PaymentId::new([1, 2, 3, 4, 5, 6, 7, 8]))
+    );
+    assert_eq!(parsed.invoice_ref(), Some("INV-2024-0001"));
+    assert_eq!(parsed.return_addr_hint(), Some("tnv1qqq"));
+  }
+
+  #[test]
+  fn test_structured_partial() {
+    let memo = Memo::structured(StructuredMemo {
+      invoice_ref: Some("INV-1".to_string()),
+      ..Default::default()
+    })
+    .unwrap();
+    let parsed = memo.encode().parse::<Memo>().unwrap();
+    assert_eq!(parsed.payment_id(), None);
+    assert_eq!(parsed.invoice_ref(), Some("INV-1"));
+  }
+
+  #[test]
+  fn test_structured_validation() {
+    assert_eq!(
+      Memo::structured(StructuredMemo::default()),
+      Err(Error::EmptyStructuredMemo)
+    );
+    assert_eq!(
+      Memo::structured(StructuredMemo {
+        invoice_ref: Some("INV\u{1f}1".to_string()),
+        ..Default::default()
+      }),
+      Err(Error::ReservedChar("Invoice reference"))
+    );
+    assert!(matches!(
+      Memo::structured(StructuredMemo {
+        invoice_ref: Some("a".repeat(MAX_MEMO_LEN)),
+        ..Default::default()
+      }),
+      Err(Error::TooLong(_))
+    ));
+  }
+
+  #[test]
+  fn test_parse_malformed() {
+    assert_eq!("\u{1e}".parse::<Memo>(), Err(Error::EmptyStructuredMemo));
+    assert_eq!(
+      "\u{1e}x=1".parse::<Memo>(),
+      Err(Error::UnknownField("x".to_string()))
+    );
+    assert_eq!(
+      "\u{1e}i=1\u{1f}i=2".parse::<Memo>(),
+      Err(Error::DuplicateField("i".to_string()))
+    );
+    assert_eq!(
+      "\u{1e}i".parse::<Memo>(),
+      Err(Error::MalformedField("i".to_string()))
+    );
+    assert_eq!(
+      "\u{1e}p=xyz".parse::<Memo>(),
+      Err(Error::InvalidPaymentId("xyz".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_payment_id() {
+    let payment_id = PaymentId::random();
+    let s = payment_id.to_string();
+    assert_eq!(s.len(), PAYMENT_ID_LEN * 2);
+    assert_eq!(s.parse::<PaymentId>().unwrap(), payment_id);
+  }
+
+  #[test]
+  fn test_from_recovery_res() {
+    let memo = Memo::structured(gen_structured_memo()).unwrap();
+    let res = AmountRecoveryRes::new(true, 1000, &memo.encode());
+    assert_eq!(Memo::try_from(&res).unwrap(), memo);
+  }
+
+  #[test]
+  fn test_tx_out_round_trip() {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let spending_pub_key = PublicKey::random().unwrap();
+    let dest = SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(0, 0));
+    let memo = Memo::structured(gen_structured_memo()).unwrap();
+
+    let tx_out = TxOut::new(
+      &dest,
+      1000,
+      &memo.encode(),
+      &TokenId::default().unwrap(),
+      TxOutputType::Normal,
+      0,
+      false,
+      None,
+    )
+    .unwrap();
+    assert_eq!(tx_out.memo().unwrap().parse::<Memo>().unwrap(), memo);
+  }
+}
@@ -3,7 +3,7 @@ use crate::{
   blsct_serde::BlsctSerde,
   ctx_id::CTxId,
   ffi::{deserialize_out_point, gen_out_point, serialize_out_point, BlsctOutPoint, BlsctRetVal},
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::c_char;
@@ -15,10 +15,11 @@ pub struct OutPoint {
 
 impl_from_retval!(OutPoint);
 impl_display!(OutPoint);
+impl_bytes!(OutPoint);
 impl_clone!(OutPoint);
 
 impl OutPoint {
-  pub fn new<'a>(ctx_id: &CTxId) -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn new(ctx_id: &CTxId) -> Result<Self, blsct_obj::Error> {
     let rv = unsafe { gen_out_point(ctx_id.value() as *const c_char) };
     let obj = BlsctObj::from_retval(rv)?;
     Ok(obj.into())
@@ -28,6 +29,8 @@ impl OutPoint {
 }
 
 impl BlsctSerde for OutPoint {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_out_point(ptr as *const BlsctOutPoint)
   }
@@ -52,11 +55,10 @@ impl From<BlsctObj<OutPoint, BlsctOutPoint>> for OutPoint {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{ctx_id::CTxId, initializer::init};
+  use crate::ctx_id::CTxId;
 
   #[test]
   fn test_deser() {
-    init();
     let ctx_id = CTxId::random();
     let a = OutPoint::new(&ctx_id).unwrap();
     let hex = bincode::serialize(&a).unwrap();
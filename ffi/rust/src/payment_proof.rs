@@ -0,0 +1,208 @@
+use crate::{
+  address::Address,
+  amount_recovery_req::AmountRecoveryReq,
+  ctx::CTx,
+  ctx_id::CTxId,
+  ctx_out::CTxOut,
+  ctx_outs,
+  keys::{double_public_key::DoublePublicKey, public_key::PublicKey},
+  point::Point,
+  range_proof::RangeProof,
+  scalar::Scalar,
+  token_id::TokenId,
+  view_tag::ViewTag,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("The proof is for a different transaction")]
+  TxIdMismatch,
+
+  #[error("Invalid output index")]
+  IndexOutOfRange(#[from] ctx_outs::Error),
+
+  #[error("The output is not addressed to the given destination")]
+  WrongDestination,
+
+  #[error("Failed to recover the amount of the output")]
+  AmountNotRecovered,
+
+  #[error("Failed to read the transaction: {0}")]
+  FailedToReadCTx(String),
+}
+
+// what the verifier learns about a proven output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentDetails {
+  pub amount: u64,
+  pub token_id: TokenId,
+  pub memo: String,
+}
+
+// discloses the blinding key the sender used for an output of a CTx.
+// with it, anyone holding the recipient's address can recompute the nonce
+// the recipient would derive with the view key and recover the amount and
+// the memo of that output, but of no other output.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PaymentProof {
+  txid: CTxId,
+  index: usize,
+  blinding_key: Scalar,
+}
+
+impl PaymentProof {
+  pub fn new(ctx: &CTx, index: usize, blinding_key: &Scalar) -> Result<Self, Error> {
+    ctx.get_ctx_outs().at(index)?;
+    Ok(PaymentProof {
+      txid: read_txid(ctx)?,
+      index,
+      blinding_key: blinding_key.clone(),
+    })
+  }
+
+  pub fn txid(&self) -> &CTxId {
+    &self.txid
+  }
+
+  pub fn index(&self) -> usize {
+    self.index
+  }
+
+  pub fn blinding_key(&self) -> &Scalar {
+    &self.blinding_key
+  }
+
+  pub fn verify_address(&self, ctx: &CTx, address: &Address) -> Result<PaymentDetails, Error> {
+    self.verify(ctx, address.dpk())
+  }
+
+  // the output carries b*D as its blinding key and its range proof is made
+  // with the nonce b*C, where (C, D) are the view and spending keys of the
+  // destination. the recipient derives the same nonce as v*(b*D).
+  pub fn verify(&self, ctx: &CTx, dpk: &DoublePublicKey) -> Result<PaymentDetails, Error> {
+    if read_txid(ctx)? != self.txid {
+      return Err(Error::TxIdMismatch);
+    }
+    let ctx_out = ctx.get_ctx_outs().at(self.index)?;
+    let view_pub_key = dpk.view_pub_key().map_err(read_error)?;
+    let spending_pub_key = dpk.spending_pub_key().map_err(read_error)?;
+
+    let blinding_pub_key: PublicKey = (&spending_pub_key
+      .get_point()
+      .scalar_multiply(&self.blinding_key))
+      .into();
+    if ctx_out.blsct_data_blinding_pub_key() != blinding_pub_key {
+      return Err(Error::WrongDestination);
+    }
+    let view_tag = ViewTag::new(&view_pub_key, &self.blinding_key);
+    if view_tag.value() != ctx_out.blsct_data_view_tag() as u64 {
+      return Err(Error::WrongDestination);
+    }
+
+    let nonce: Point = (&view_pub_key.generate_nonce(&self.blinding_key)).into();
+    recover(&ctx_out, &nonce)
+  }
+}
+
+fn read_error(e: impl ToString) -> Error {
+  Error::FailedToReadCTx(e.to_string())
+}
+
+fn read_txid(ctx: &CTx) -> Result<CTxId, Error> {
+  ctx.get_ctx_id().map_err(read_error)
+}
+
+fn recover(ctx_out: &CTxOut, nonce: &Point) -> Result<PaymentDetails, Error> {
+  let range_proof = ctx_out.blsct_data_range_proof().map_err(read_error)?;
+  let token_id = ctx_out.token_id();
+  let req = AmountRecoveryReq::new_with_token_id(&range_proof, nonce, &token_id);
+  let res = RangeProof::recover_amounts(vec![req])
+    .map_err(read_error)?
+    .into_iter()
+    .find(|x| x.is_succ)
+    .ok_or(Error::AmountNotRecovered)?;
+
+  Ok(PaymentDetails {
+    amount: res.amount,
+    token_id,
+    memo: res.msg,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    keys::child_key::ChildKey, sub_addr::SubAddr, sub_addr_id::SubAddrId, test_util::gen_ctx_actual,
+  };
+
+  fn gen_destination() -> SubAddr {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let spending_pub_key = PublicKey::random().unwrap();
+    SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(1, 2))
+  }
+
+  fn find_output(ctx: &CTx, blinding_key: &Scalar, dpk: &DoublePublicKey) -> PaymentProof {
+    (0..ctx.get_ctx_outs().len())
+      .map(|i| PaymentProof::new(ctx, i, blinding_key).unwrap())
+      .find(|x| x.verify(ctx, dpk).is_ok())
+      .unwrap()
+  }
+
+  #[test]
+  fn test_verify() {
+    let destination = gen_destination();
+    let dpk: DoublePublicKey = destination.clone().into();
+    let blinding_key = Scalar::random().unwrap();
+    let ctx = gen_ctx_actual(12345, "order #42", &destination, &blinding_key);
+
+    let proof = find_output(&ctx, &blinding_key, &dpk);
+    let details = proof.verify(&ctx, &dpk).unwrap();
+    assert_eq!(details.amount, 12345);
+    assert_eq!(details.memo, "order #42");
+    assert_eq!(details.token_id, TokenId::default().unwrap());
+
+    // survives being sent to the verifier
+    let json = serde_json::to_string(&proof).unwrap();
+    let proof: PaymentProof = serde_json::from_str(&json).unwrap();
+    assert_eq!(proof.verify(&ctx, &dpk).unwrap(), details);
+  }
+
+  #[test]
+  fn test_wrong_destination_is_rejected() {
+    let destination = gen_destination();
+    let dpk: DoublePublicKey = destination.clone().into();
+    let blinding_key = Scalar::random().unwrap();
+    let ctx = gen_ctx_actual(12345, "navio", &destination, &blinding_key);
+    let proof = find_output(&ctx, &blinding_key, &dpk);
+
+    let other: DoublePublicKey = gen_destination().into();
+    assert!(matches!(
+      proof.verify(&ctx, &other),
+      Err(Error::WrongDestination)
+    ));
+
+    let forged = PaymentProof::new(&ctx, proof.index(), &Scalar::random().unwrap()).unwrap();
+    assert!(forged.verify(&ctx, &dpk).is_err());
+
+    let other_ctx = gen_ctx_actual(12345, "navio", &destination, &blinding_key);
+    assert!(matches!(
+      proof.verify(&other_ctx, &dpk),
+      Err(Error::TxIdMismatch)
+    ));
+  }
+
+  #[test]
+  fn test_index_out_of_range() {
+    let destination = gen_destination();
+    let blinding_key = Scalar::random().unwrap();
+    let ctx = gen_ctx_actual(12345, "navio", &destination, &blinding_key);
+    let len = ctx.get_ctx_outs().len();
+    assert!(matches!(
+      PaymentProof::new(&ctx, len, &blinding_key),
+      Err(Error::IndexOutOfRange(_))
+    ));
+  }
+}
@@ -0,0 +1,418 @@
+use crate::{
+  address::Address,
+  blsct_obj,
+  chain::{Chain, COIN},
+  memo::{self, Memo},
+  token_id::TokenId,
+};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+pub const URI_SCHEME: &str = "navio";
+
+const COIN_DECIMALS: usize = 8;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error("Invalid URI scheme: {0}")]
+  InvalidScheme(String),
+
+  #[error("Invalid address: {0}")]
+  InvalidAddress(String),
+
+  #[error("Invalid amount: {0}")]
+  InvalidAmount(String),
+
+  #[error("Invalid token: {0}")]
+  InvalidToken(String),
+
+  #[error("Invalid percent encoding: {0}")]
+  InvalidPercentEncoding(String),
+
+  #[error("Unknown required parameter: {0}")]
+  UnknownRequiredParameter(String),
+
+  #[error("Duplicate parameter: {0}")]
+  DuplicateParameter(String),
+
+  #[error("Invalid memo")]
+  MemoError(#[source] memo::Error),
+
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentRequest {
+  address: Address,
+  pub amount: Option<u64>,
+  pub token_id: Option<TokenId>,
+  pub memo: Option<Memo>,
+  pub label: Option<String>,
+}
+
+impl PaymentRequest {
+  pub fn new(address: Address) -> Self {
+    PaymentRequest {
+      address,
+      amount: None,
+      token_id: None,
+      memo: None,
+      label: None,
+    }
+  }
+
+  pub fn chain(&self) -> Chain {
+    self.address.chain()
+  }
+
+  pub fn address(&self) -> &Address {
+    &self.address
+  }
+
+  // testnet and signet addresses share an HRP and are parsed by
+  // parse_for_chain instead
+  pub fn parse(uri: &str) -> Result<Self, Error> {
+    Self::parse_with_chain(uri, None)
+  }
+
+  pub fn parse_for_chain(uri: &str, expected: Chain) -> Result<Self, Error> {
+    Self::parse_with_chain(uri, Some(expected))
+  }
+
+  fn parse_with_chain(uri: &str, expected: Option<Chain>) -> Result<Self, Error> {
+    // schemes are case-insensitive (RFC 3986 section 3.1) and QR code
+    // scanners often report them in upper case
+    let rest = uri
+      .split_once(':')
+      .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(URI_SCHEME))
+      .map(|(_, rest)| rest)
+      .ok_or(Error::InvalidScheme(uri.to_string()))?;
+    let (address, query) = match rest.split_once('?') {
+      Some((address, query)) => (address, Some(query)),
+      None => (rest, None),
+    };
+    let address = match expected {
+      Some(chain) => Address::parse_for_chain(address, chain),
+      None => Address::parse(address),
+    }
+    .map_err(|_| Error::InvalidAddress(address.to_string()))?;
+    let mut req = PaymentRequest::new(address);
+
+    let Some(query) = query else {
+      return Ok(req);
+    };
+    let mut seen = vec![];
+    for param in query.split('&').filter(|x| !x.is_empty()) {
+      let (key, value) = param.split_once('=').unwrap_or((param, ""));
+      if seen.contains(&key) {
+        return Err(Error::DuplicateParameter(key.to_string()));
+      }
+      seen.push(key);
+
+      // as in BIP21, unknown parameters are ignored unless they are
+      // prefixed with req-, which marks them as required
+      match key {
+        "amount" => req.amount = Some(parse_amount(&percent_decode(value)?)?),
+        "token" => req.token_id = Some(parse_token_id(&percent_decode(value)?)?),
+        "memo" => {
+          let memo = percent_decode(value)?.parse().map_err(Error::MemoError)?;
+          req.memo = Some(memo);
+        }
+        "label" => req.label = Some(percent_decode(value)?),
+        _ if key.starts_with("req-") => {
+          return Err(Error::UnknownRequiredParameter(key.to_string()))
+        }
+        _ => {}
+      }
+    }
+    Ok(req)
+  }
+
+  pub fn to_uri(&self) -> String {
+    let mut params = vec![];
+    if let Some(amount) = self.amount {
+      params.push(format!("amount={}", format_amount(amount)));
+    }
+    if let Some(token_id) = &self.token_id {
+      params.push(format!("token={}", format_token_id(token_id)));
+    }
+    if let Some(memo) = &self.memo {
+      params.push(format!("memo={}", percent_encode(&memo.encode())));
+    }
+    if let Some(label) = &self.label {
+      params.push(format!("label={}", percent_encode(label)));
+    }
+    if params.is_empty() {
+      format!("{URI_SCHEME}:{}", self.address)
+    } else {
+      format!("{URI_SCHEME}:{}?{}", self.address, params.join("&"))
+    }
+  }
+}
+
+impl fmt::Display for PaymentRequest {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.to_uri())
+  }
+}
+
+impl FromStr for PaymentRequest {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    PaymentRequest::parse(s)
+  }
+}
+
+// amounts are expressed in coins with up to 8 decimal places
+fn parse_amount(s: &str) -> Result<u64, Error> {
+  let invalid = || Error::InvalidAmount(s.to_string());
+
+  let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+  if int_part.is_empty() || frac_part.len() > COIN_DECIMALS {
+    return Err(invalid());
+  }
+  let is_digits = |x: &str| x.bytes().all(|c| c.is_ascii_digit());
+  if !is_digits(int_part) || !is_digits(frac_part) {
+    return Err(invalid());
+  }
+  let int_part = int_part.parse::<u64>().map_err(|_| invalid())?;
+  let frac_part = format!("{frac_part:0<COIN_DECIMALS$}")
+    .parse::<u64>()
+    .map_err(|_| invalid())?;
+
+  int_part
+    .checked_mul(COIN)
+    .and_then(|x| x.checked_add(frac_part))
+    .ok_or_else(invalid)
+}
+
+fn format_amount(amount: u64) -> String {
+  let int_part = amount / COIN;
+  let frac_part = amount % COIN;
+  if frac_part == 0 {
+    return int_part.to_string();
+  }
+  let frac_part = format!("{frac_part:0>COIN_DECIMALS$}");
+  format!("{int_part}.{}", frac_part.trim_end_matches('0'))
+}
+
+// a token is given as `<token>` or `<token>:<subid>`
+fn parse_token_id(s: &str) -> Result<TokenId, Error> {
+  let invalid = || Error::InvalidToken(s.to_string());
+
+  let (token, subid) = match s.split_once(':') {
+    Some((token, subid)) => (token, subid.parse::<u64>().map_err(|_| invalid())?),
+    None => (s, u64::MAX),
+  };
+  let token = token.parse::<u64>().map_err(|_| invalid())?;
+  TokenId::from_token_and_subid(token, subid).map_err(Error::BlsctObjError)
+}
+
+fn format_token_id(token_id: &TokenId) -> String {
+  if token_id.subid() == u64::MAX {
+    token_id.token().to_string()
+  } else {
+    format!("{}:{}", token_id.token(), token_id.subid())
+  }
+}
+
+fn percent_encode(s: &str) -> String {
+  let mut encoded = String::with_capacity(s.len());
+  for b in s.bytes() {
+    if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+      encoded.push(b as char);
+    } else {
+      encoded.push_str(&format!("%{b:02X}"));
+    }
+  }
+  encoded
+}
+
+fn percent_decode(s: &str) -> Result<String, Error> {
+  let invalid = || Error::InvalidPercentEncoding(s.to_string());
+
+  let bytes = s.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'%' => {
+        // exactly two hex digits. from_str_radix alone would take a sign.
+        let hex = bytes
+          .get(i + 1..i + 3)
+          .filter(|x| x.iter().all(u8::is_ascii_hexdigit))
+          .ok_or_else(invalid)?;
+        let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+        decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+        i += 3;
+      }
+      b'+' => {
+        decoded.push(b' ');
+        i += 1;
+      }
+      b => {
+        decoded.push(b);
+        i += 1;
+      }
+    }
+  }
+  String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ffi::AddressEncoding,
+    keys::double_public_key::DoublePublicKey,
+    memo::{PaymentId, StructuredMemo},
+  };
+
+  fn gen_address() -> Address {
+    let dpk = DoublePublicKey::random().unwrap();
+    Address::new(dpk, Chain::Mainnet, AddressEncoding::Bech32M).unwrap()
+  }
+
+  #[test]
+  fn test_address_only() {
+    let address = gen_address();
+    let uri = format!("navio:{address}");
+    let req = PaymentRequest::parse(&uri).unwrap();
+    assert_eq!(req.address(), &address);
+    assert_eq!(req.chain(), Chain::Mainnet);
+    assert_eq!(req.amount, None);
+    assert_eq!(req.to_uri(), uri);
+  }
+
+  #[test]
+  fn test_scheme_is_case_insensitive() {
+    let address = gen_address();
+    for scheme in ["NAVIO", "Navio", "nAvIo"] {
+      let req = PaymentRequest::parse(&format!("{scheme}:{address}?amount=1")).unwrap();
+      assert_eq!(req.address(), &address);
+      assert_eq!(req.amount, Some(COIN));
+      assert_eq!(req.to_uri(), format!("navio:{address}?amount=1"));
+    }
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let mut req = PaymentRequest::new(gen_address());
+    req.amount = Some(150000000);
+    req.token_id = Some(TokenId::from_token_and_subid(123, 456).unwrap());
+    req.memo = Some(
+      Memo::structured(StructuredMemo {
+        payment_id: Some(PaymentId::random()),
+        invoice_ref: Some("INV 42".to_string()),
+        ..Default::default()
+      })
+      .unwrap(),
+    );
+    req.label = Some("Coffee & Cake".to_string());
+
+    let uri = req.to_uri();
+    assert!(uri.contains("amount=1.5&token=123:456&memo="));
+    assert!(uri.ends_with("&label=Coffee%20%26%20Cake"));
+    assert_eq!(uri.parse::<PaymentRequest>().unwrap(), req);
+  }
+
+  #[test]
+  fn test_chain() {
+    let dpk = DoublePublicKey::random().unwrap();
+    let address = Address::new(dpk, Chain::Regtest, AddressEncoding::Bech32M).unwrap();
+    let req = PaymentRequest::parse(&format!("navio:{address}?amount=1")).unwrap();
+    assert_eq!(req.chain(), Chain::Regtest);
+
+    for chain in [Chain::Testnet, Chain::Signet] {
+      let address = Address::new(address.dpk().clone(), chain, AddressEncoding::Bech32M).unwrap();
+      let uri = format!("navio:{address}");
+      let req = PaymentRequest::parse_for_chain(&uri, chain).unwrap();
+      assert_eq!(req.chain(), chain);
+      assert!(matches!(
+        PaymentRequest::parse_for_chain(&uri, Chain::Mainnet),
+        Err(Error::InvalidAddress(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn test_token_without_subid() {
+    let uri = format!("navio:{}?token=123", gen_address());
+    let req = PaymentRequest::parse(&uri).unwrap();
+    assert_eq!(req.token_id, Some(TokenId::from_token(123).unwrap()));
+    assert_eq!(req.to_uri(), uri);
+  }
+
+  #[test]
+  fn test_amount() {
+    assert_eq!(parse_amount("1").unwrap(), COIN);
+    assert_eq!(parse_amount("0.00000001").unwrap(), 1);
+    assert_eq!(parse_amount("12.5").unwrap(), 1250000000);
+    assert!(parse_amount("").is_err());
+    assert!(parse_amount(".5").is_err());
+    assert!(parse_amount("1.000000001").is_err());
+    assert!(parse_amount("-1").is_err());
+    assert!(parse_amount("1e8").is_err());
+    assert!(parse_amount("999999999999999999").is_err());
+
+    for amount in [0, 1, COIN, 1250000000, u64::MAX] {
+      assert_eq!(parse_amount(&format_amount(amount)).unwrap(), amount);
+    }
+  }
+
+  #[test]
+  fn test_percent_encoding() {
+    let s = "a b&c=d%é";
+    assert_eq!(percent_decode(&percent_encode(s)).unwrap(), s);
+    assert_eq!(percent_decode("a+b").unwrap(), "a b");
+    assert!(percent_decode("%4").is_err());
+    assert!(percent_decode("%zz").is_err());
+    assert!(percent_decode("%ff").is_err());
+    assert!(percent_decode("%+1").is_err());
+    assert!(percent_decode("%-1").is_err());
+    assert_eq!(percent_decode("%2B1").unwrap(), "+1");
+  }
+
+  #[test]
+  fn test_unknown_parameters_are_ignored() {
+    let address = gen_address();
+    let req = PaymentRequest::parse(&format!("navio:{address}?foo=%zz&amount=1&bar")).unwrap();
+    assert_eq!(req.amount, Some(COIN));
+    assert_eq!(req.to_uri(), format!("navio:{address}?amount=1"));
+  }
+
+  #[test]
+  fn test_invalid_uri() {
+    let address = gen_address();
+    assert!(matches!(
+      PaymentRequest::parse(&format!("bitcoin:{address}")),
+      Err(Error::InvalidScheme(_))
+    ));
+    assert!(matches!(
+      PaymentRequest::parse(&format!("navi:{address}")),
+      Err(Error::InvalidScheme(_))
+    ));
+    assert!(matches!(
+      PaymentRequest::parse(&address.to_string()),
+      Err(Error::InvalidScheme(_))
+    ));
+    assert!(matches!(
+      PaymentRequest::parse("navio:xyz"),
+      Err(Error::InvalidAddress(_))
+    ));
+    assert_eq!(
+      PaymentRequest::parse(&format!("navio:{address}?req-foo=1")),
+      Err(Error::UnknownRequiredParameter("req-foo".to_string()))
+    );
+    assert_eq!(
+      PaymentRequest::parse(&format!("navio:{address}?amount=1&amount=2")),
+      Err(Error::DuplicateParameter("amount".to_string()))
+    );
+    assert!(matches!(
+      PaymentRequest::parse(&format!("navio:{address}?token=abc")),
+      Err(Error::InvalidToken(_))
+    ));
+  }
+}
@@ -1,4 +1,4 @@
-use crate::macros::{impl_clone, impl_display, impl_from_retval, impl_value};
+use crate::macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value};
 use crate::scalar::Scalar;
 use crate::{
   blsct_obj::{self, BlsctObj},
@@ -15,16 +15,17 @@ pub struct Point {
   obj: BlsctObj<Point, BlsctPoint>,
 }
 
+impl_bytes!(Point);
 impl_clone!(Point);
 impl_from_retval!(Point);
 impl_display!(Point);
 
 impl Point {
-  pub fn base<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn base() -> Result<Self, blsct_obj::Error> {
     Self::from_retval(unsafe { gen_base_point() })
   }
 
-  pub fn random<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn random() -> Result<Self, blsct_obj::Error> {
     Self::from_retval(unsafe { gen_random_point() })
   }
 
@@ -60,6 +61,8 @@ impl From<&Scalar> for Point {
 }
 
 impl BlsctSerde for Point {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_point(ptr as *const BlsctPoint)
   }
@@ -67,6 +70,11 @@ impl BlsctSerde for Point {
   unsafe fn deserialize(hex: *const i8) -> *mut BlsctRetVal {
     deserialize_point(hex)
   }
+
+  // expects `bytes` to be of the size of BlsctPoint
+  fn is_valid_bytes(bytes: &[u8]) -> bool {
+    unsafe { is_valid_point(bytes.as_ptr() as *const BlsctPoint) != 0 }
+  }
 }
 
 impl PartialEq for Point {
@@ -85,11 +93,9 @@ impl Eq for Point {}
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
 
   #[test]
   fn test_base() {
-    init();
     let a = Point::base().unwrap();
     let b = Point::base().unwrap();
     assert!(a == b);
@@ -97,7 +103,6 @@ mod tests {
 
   #[test]
   fn test_random() {
-    init();
     let mut prev: Point = Point::base().unwrap();
     let mut dup_tolerance = 5;
 
@@ -118,22 +123,18 @@ mod tests {
 
   #[test]
   fn test_is_valid() {
-    init();
     let x = Point::base().unwrap();
     assert!(x.is_valid());
   }
 
   #[test]
   fn test_from_scalar() {
-    init();
-
     let scalar = Scalar::new(123).unwrap();
     let _ = Point::from(&scalar);
   }
 
   #[test]
   fn test_eq() {
-    init();
     let (a, b) = {
       loop {
         let a = Point::random().unwrap();
@@ -151,7 +152,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = Point::base().unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<Point>(&hex).unwrap();
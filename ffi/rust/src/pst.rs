@@ -0,0 +1,667 @@
+use crate::{
+  blsct_obj, ctx::CTx, keys::public_key::PublicKey, out_point::OutPoint, scalar::Scalar,
+  secret::SecretScalar, sub_addr::SubAddr, tx_in::TxIn, unsigned_input::UnsignedInput,
+  unsigned_output::UnsignedOutput, unsigned_transaction::UnsignedTransaction,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use chacha20poly1305::{
+  aead::{Aead, KeyInit, Payload},
+  Key, XChaCha20Poly1305, XNonce,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+// version 1 carried the gamma and spending key of each input in the clear and
+// version 2 did not commit to the sealed inputs
+pub const PST_VERSION: u8 = 3;
+
+const PST_MAGIC: &[u8; 4] = b"npst";
+
+const SEAL_DOMAIN: &[u8] = b"navio-pst-sealed-input";
+const COMMITMENT_DOMAIN: &[u8] = b"navio-pst-input-commitment";
+const NONCE_SIZE: usize = 24;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("Invalid PST: {0}")]
+  InvalidFormat(String),
+
+  #[error("Unsupported PST version {0}")]
+  UnsupportedVersion(u8),
+
+  #[error("Conflicting {0}")]
+  Conflict(String),
+
+  #[error("No input spends the outpoint of the given TxIn")]
+  UnknownInput,
+
+  #[error("The spending key does not belong to the required signer")]
+  WrongSigner,
+
+  #[error("The key does not belong to the finalizer of the PST")]
+  WrongFinalizer,
+
+  #[error("Failed to seal the input")]
+  FailedToSeal,
+
+  #[error("Failed to open the sealed input of {0}")]
+  FailedToOpen(String),
+
+  #[error("{0} inputs are not handed over yet")]
+  Incomplete(usize),
+
+  #[error("Failed to build the transaction: {0}")]
+  FailedToBuild(String),
+}
+
+// the gamma and spending key of an input sealed to the finalizer of the PST.
+// the other parties relay it but cannot read it. the commitment lets them
+// tell whether two sealed inputs carry the same secrets.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+struct SealedInput {
+  commitment: [u8; 32],
+  ephemeral_key: PublicKey,
+  nonce: Vec<u8>,
+  ciphertext: Vec<u8>,
+}
+
+impl fmt::Debug for SealedInput {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "SealedInput")
+  }
+}
+
+// the key shared by the holders of `secret` and of the secret key of
+// `pub_key`, bound to the ephemeral key of the sender
+fn seal_key(secret: &Scalar, pub_key: &PublicKey, ephemeral_key: &PublicKey) -> [u8; 32] {
+  let mut shared = pub_key.get_point().scalar_multiply(secret).to_bytes();
+  let mut hasher = Blake2b::<U32>::new();
+  hasher.update(SEAL_DOMAIN);
+  hasher.update(&shared);
+  hasher.update(ephemeral_key.to_bytes());
+  shared.zeroize();
+  hasher.finalize().into()
+}
+
+// the secrets are uniformly random scalars, so the hash does not reveal them
+fn commitment(out_point: &OutPoint, plaintext: &[u8]) -> [u8; 32] {
+  let mut hasher = Blake2b::<U32>::new();
+  hasher.update(COMMITMENT_DOMAIN);
+  hasher.update(out_point.to_bytes());
+  hasher.update(plaintext);
+  hasher.finalize().into()
+}
+
+// the ciphertext is bound to the outpoint and to the commitment
+fn seal_aad(out_point: &OutPoint, commitment: &[u8; 32]) -> Vec<u8> {
+  let mut aad = out_point.to_bytes();
+  aad.extend_from_slice(commitment);
+  aad
+}
+
+impl SealedInput {
+  fn seal(
+    input: &UnsignedInput,
+    out_point: &OutPoint,
+    finalizer: &PublicKey,
+  ) -> Result<Self, Error> {
+    let ephemeral_secret =
+      SecretScalar::new(Scalar::random().map_err(|e| Error::FailedToBuild(e.to_string()))?);
+    let ephemeral_key = PublicKey::from(ephemeral_secret.expose_secret());
+    let mut nonce = vec![0u8; NONCE_SIZE];
+    rand::rng().fill(&mut nonce[..]);
+
+    let mut plaintext = input
+      .to_bytes()
+      .map_err(|e| Error::FailedToBuild(e.to_string()))?;
+    let commitment = commitment(out_point, &plaintext);
+    let mut key = seal_key(&ephemeral_secret, finalizer, &ephemeral_key);
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&key))
+      .encrypt(
+        XNonce::from_slice(&nonce),
+        Payload {
+          msg: &plaintext,
+          aad: &seal_aad(out_point, &commitment),
+        },
+      )
+      .map_err(|_| Error::FailedToSeal);
+    key.zeroize();
+    plaintext.zeroize();
+
+    Ok(SealedInput {
+      commitment,
+      ephemeral_key,
+      nonce,
+      ciphertext: ciphertext?,
+    })
+  }
+
+  fn open(&self, out_point: &OutPoint, finalizer_key: &Scalar) -> Result<UnsignedInput, Error> {
+    let failed = || Error::FailedToOpen(hex::encode(out_point.to_bytes()));
+    if self.nonce.len() != NONCE_SIZE {
+      return Err(failed());
+    }
+    let mut key = seal_key(finalizer_key, &self.ephemeral_key, &self.ephemeral_key);
+    let plaintext = XChaCha20Poly1305::new(Key::from_slice(&key))
+      .decrypt(
+        XNonce::from_slice(&self.nonce),
+        Payload {
+          msg: &self.ciphertext,
+          aad: &seal_aad(out_point, &self.commitment),
+        },
+      )
+      .map_err(|_| failed());
+    key.zeroize();
+    let mut plaintext = plaintext?;
+    // the sender may have committed to other secrets than it sealed
+    let input = if commitment(out_point, &plaintext) == self.commitment {
+      UnsignedInput::from_bytes(&plaintext).map_err(|_| failed())
+    } else {
+      Err(failed())
+    };
+    plaintext.zeroize();
+    input
+  }
+}
+
+// an input to be spent along with what the parties know about it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PstInput {
+  pub out_point: OutPoint,
+  pub sub_addr: Option<SubAddr>,
+  pub signer: Option<PublicKey>,
+  pub amount: Option<u64>,
+  sealed: Option<SealedInput>,
+}
+
+impl PstInput {
+  pub fn new(out_point: &OutPoint) -> Self {
+    PstInput {
+      out_point: out_point.clone(),
+      sub_addr: None,
+      signer: None,
+      amount: None,
+      sealed: None,
+    }
+  }
+
+  pub fn is_sealed(&self) -> bool {
+    self.sealed.is_some()
+  }
+
+  fn merge(&mut self, other: &PstInput) -> Result<(), Error> {
+    merge_field(&mut self.sub_addr, &other.sub_addr, "sub-address")?;
+    merge_field(&mut self.signer, &other.signer, "signer")?;
+    merge_field(&mut self.amount, &other.amount, "amount")?;
+    // sealed inputs with the same commitment carry the same secrets, so
+    // the first one is kept
+    match (&self.sealed, &other.sealed) {
+      (Some(x), Some(y)) if x.commitment != y.commitment => {
+        Err(Error::Conflict("sealed input".to_string()))
+      }
+      (None, Some(y)) => {
+        self.sealed = Some(y.clone());
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+fn merge_field<T: Clone + PartialEq>(
+  a: &mut Option<T>,
+  b: &Option<T>,
+  name: &str,
+) -> Result<(), Error> {
+  match (a.as_ref(), b) {
+    (Some(x), Some(y)) if x != y => Err(Error::Conflict(name.to_string())),
+    (None, Some(y)) => {
+      *a = Some(y.clone());
+      Ok(())
+    }
+    _ => Ok(()),
+  }
+}
+
+// unsigned outputs are compared by their serialization
+fn obj_bytes(bytes: Result<Vec<u8>, blsct_obj::Error>) -> Result<Vec<u8>, Error> {
+  bytes.map_err(|e| Error::InvalidFormat(e.to_string()))
+}
+
+fn obj_copy<T>(obj: Result<T, blsct_obj::Error>) -> Result<T, Error> {
+  obj.map_err(|e| Error::InvalidFormat(e.to_string()))
+}
+
+fn merge_input(inputs: &mut Vec<PstInput>, input: PstInput) -> Result<(), Error> {
+  match inputs.iter_mut().find(|x| x.out_point == input.out_point) {
+    Some(x) => {
+      let mut merged = x.clone();
+      merged.merge(&input)?;
+      *x = merged;
+    }
+    None => inputs.push(input),
+  }
+  Ok(())
+}
+
+// a partially signed transaction that several parties fill in before the
+// finalizer turns it into a CTx. it is encoded as the magic bytes and the
+// version followed by the bincode of the container.
+//
+// libblsct has no per-input signatures: a transaction is signed as a whole
+// with the gamma and spending key of every input. the parties therefore do
+// not sign their inputs but hand these secrets over to the finalizer, which
+// can spend the inputs in any transaction it likes. use a PST only with a
+// finalizer that every party trusts with its inputs.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Pst {
+  fee: u64,
+  finalizer: PublicKey,
+  inputs: Vec<PstInput>,
+  outputs: Vec<UnsignedOutput>,
+}
+
+impl Pst {
+  // `finalizer` is the public key of the party that finalizes the PST
+  pub fn new(fee: u64, finalizer: &PublicKey) -> Self {
+    Pst {
+      fee,
+      finalizer: finalizer.clone(),
+      inputs: vec![],
+      outputs: vec![],
+    }
+  }
+
+  pub fn fee(&self) -> u64 {
+    self.fee
+  }
+
+  pub fn finalizer(&self) -> &PublicKey {
+    &self.finalizer
+  }
+
+  pub fn inputs(&self) -> &[PstInput] {
+    &self.inputs
+  }
+
+  pub fn outputs_len(&self) -> usize {
+    self.outputs.len()
+  }
+
+  // an input that is already in the PST is merged with the given one
+  pub fn add_input(&mut self, input: PstInput) -> Result<(), Error> {
+    merge_input(&mut self.inputs, input)
+  }
+
+  // adding an output that is already in the PST has no effect
+  pub fn add_output(&mut self, output: UnsignedOutput) -> Result<(), Error> {
+    let bytes = obj_bytes(output.to_bytes())?;
+    for x in &self.outputs {
+      if obj_bytes(x.to_bytes())? == bytes {
+        return Ok(());
+      }
+    }
+    self.outputs.push(output);
+    Ok(())
+  }
+
+  // seals the gamma and spending key of the input spending the outpoint of
+  // `tx_in` to the finalizer. handing it over again replaces the sealed input.
+  pub fn hand_over_input(&mut self, tx_in: &TxIn) -> Result<(), Error> {
+    let out_point = tx_in.out_point();
+    let input = self
+      .inputs
+      .iter_mut()
+      .find(|x| x.out_point == out_point)
+      .ok_or(Error::UnknownInput)?;
+
+    if let Some(signer) = &input.signer {
+      if &PublicKey::from(tx_in.spending_key().expose_secret()) != signer {
+        return Err(Error::WrongSigner);
+      }
+    }
+    if input.amount.is_some_and(|x| x != tx_in.amount()) {
+      return Err(Error::Conflict("amount".to_string()));
+    }
+    let secrets = UnsignedInput::new(tx_in).map_err(|e| Error::FailedToBuild(e.to_string()))?;
+    input.sealed = Some(SealedInput::seal(&secrets, &out_point, &self.finalizer)?);
+    input.amount = Some(tx_in.amount());
+    Ok(())
+  }
+
+  pub fn unsealed_inputs_len(&self) -> usize {
+    self.inputs.iter().filter(|x| !x.is_sealed()).count()
+  }
+
+  pub fn is_complete(&self) -> bool {
+    !self.inputs.is_empty() && self.unsealed_inputs_len() == 0
+  }
+
+  // merges what another party has added. nothing is merged if any of the
+  // data conflicts.
+  pub fn combine(&mut self, other: &Pst) -> Result<(), Error> {
+    if self.fee != other.fee {
+      return Err(Error::Conflict("fee".to_string()));
+    }
+    if self.finalizer != other.finalizer {
+      return Err(Error::Conflict("finalizer".to_string()));
+    }
+    let mut known = vec![];
+    for output in &self.outputs {
+      known.push(obj_bytes(output.to_bytes())?);
+    }
+    let mut outputs = vec![];
+    for output in &other.outputs {
+      let bytes = obj_bytes(output.to_bytes())?;
+      if !known.contains(&bytes) {
+        outputs.push(obj_copy(output.try_clone())?);
+        known.push(bytes);
+      }
+    }
+    let mut inputs = self.inputs.clone();
+    for input in &other.inputs {
+      merge_input(&mut inputs, input.clone())?;
+    }
+    self.inputs = inputs;
+    self.outputs.extend(outputs);
+    Ok(())
+  }
+
+  // only the finalizer can open the sealed inputs
+  pub fn finalize(&self, finalizer_key: &Scalar) -> Result<CTx, Error> {
+    if PublicKey::from(finalizer_key) != self.finalizer {
+      return Err(Error::WrongFinalizer);
+    }
+    if self.inputs.is_empty() {
+      return Err(Error::Incomplete(0));
+    }
+    let num_unsealed = self.unsealed_inputs_len();
+    if num_unsealed > 0 {
+      return Err(Error::Incomplete(num_unsealed));
+    }
+    let mut unsigned_tx =
+      UnsignedTransaction::new().map_err(|e| Error::FailedToBuild(e.to_string()))?;
+    for input in &self.inputs {
+      if let Some(sealed) = &input.sealed {
+        unsigned_tx.add_input(&sealed.open(&input.out_point, finalizer_key)?);
+      }
+    }
+    for output in &self.outputs {
+      unsigned_tx.add_output(output);
+    }
+    unsigned_tx.set_fee(self.fee);
+    unsigned_tx
+      .sign()
+      .map_err(|e| Error::FailedToBuild(e.to_string()))
+  }
+
+  pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+    let body = bincode::serialize(self).map_err(|e| Error::InvalidFormat(e.to_string()))?;
+    let mut bytes = Vec::with_capacity(PST_MAGIC.len() + 1 + body.len());
+    bytes.extend_from_slice(PST_MAGIC);
+    bytes.push(PST_VERSION);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+    let body = bytes
+      .strip_prefix(PST_MAGIC)
+      .ok_or(Error::InvalidFormat("missing magic bytes".to_string()))?;
+    let (&version, body) = body
+      .split_first()
+      .ok_or(Error::InvalidFormat("missing version".to_string()))?;
+    if version != PST_VERSION {
+      return Err(Error::UnsupportedVersion(version));
+    }
+    let pst: Pst = bincode::deserialize(body).map_err(|e| Error::InvalidFormat(e.to_string()))?;
+
+    // the same outpoint must not be spent twice
+    for (i, input) in pst.inputs.iter().enumerate() {
+      if pst.inputs[..i]
+        .iter()
+        .any(|x| x.out_point == input.out_point)
+      {
+        return Err(Error::InvalidFormat("duplicate input".to_string()));
+      }
+    }
+    Ok(pst)
+  }
+
+  pub fn to_base64(&self) -> Result<String, Error> {
+    Ok(STANDARD.encode(self.to_bytes()?))
+  }
+
+  pub fn from_base64(s: &str) -> Result<Self, Error> {
+    let bytes = STANDARD
+      .decode(s.trim())
+      .map_err(|e| Error::InvalidFormat(e.to_string()))?;
+    Self::from_bytes(&bytes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ctx_id::CTxId, ffi::TxOutputType, keys::child_key::ChildKey, scalar::Scalar,
+    sub_addr_id::SubAddrId, token_id::TokenId, tx_out::TxOut, util::gen_random_view_key,
+  };
+
+  fn gen_tx_in(amount: u64) -> TxIn {
+    let spending_key = ChildKey::random().unwrap().to_tx_key().to_spending_key();
+    TxIn::new(
+      amount,
+      &Scalar::new(100).unwrap(),
+      &spending_key,
+      &TokenId::default().unwrap(),
+      &OutPoint::new(&CTxId::random()).unwrap(),
+      false,
+      false,
+    )
+    .unwrap()
+  }
+
+  fn gen_output(amount: u64) -> UnsignedOutput {
+    let destination = {
+      let view_key = gen_random_view_key().unwrap();
+      let spending_pub_key = PublicKey::random().unwrap();
+      SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(7, 9))
+    };
+    let tx_out = TxOut::new(
+      &destination,
+      amount,
+      "navio",
+      &TokenId::default().unwrap(),
+      TxOutputType::Normal,
+      0,
+      false,
+      None,
+    )
+    .unwrap();
+    UnsignedOutput::new(&tx_out).unwrap()
+  }
+
+  fn gen_input(tx_in: &TxIn) -> PstInput {
+    let mut input = PstInput::new(&tx_in.out_point());
+    input.signer = Some(PublicKey::from(tx_in.spending_key().expose_secret()));
+    input.amount = Some(tx_in.amount());
+    input
+  }
+
+  fn gen_finalizer() -> (Scalar, PublicKey) {
+    let key = Scalar::random().unwrap();
+    let pub_key = PublicKey::from(&key);
+    (key, pub_key)
+  }
+
+  #[test]
+  fn test_combine_and_finalize() {
+    let tx_in_a = gen_tx_in(150000);
+    let tx_in_b = gen_tx_in(150000);
+    let (finalizer_key, finalizer) = gen_finalizer();
+
+    let mut pst = Pst::new(1000, &finalizer);
+    pst.add_input(gen_input(&tx_in_a)).unwrap();
+    pst.add_input(gen_input(&tx_in_b)).unwrap();
+    pst.add_output(gen_output(10000)).unwrap();
+    assert!(matches!(
+      pst.finalize(&finalizer_key),
+      Err(Error::Incomplete(2))
+    ));
+
+    // each party hands its input over in its own copy
+    let mut pst_a = Pst::from_base64(&pst.to_base64().unwrap()).unwrap();
+    pst_a.hand_over_input(&tx_in_a).unwrap();
+    let mut pst_b = Pst::from_bytes(&pst.to_bytes().unwrap()).unwrap();
+    pst_b.hand_over_input(&tx_in_b).unwrap();
+
+    pst_a.combine(&pst_b).unwrap();
+    assert!(pst_a.is_complete());
+    assert_eq!(pst_a.outputs_len(), 1);
+
+    assert!(matches!(
+      pst_a.finalize(&Scalar::random().unwrap()),
+      Err(Error::WrongFinalizer)
+    ));
+    let ctx = pst_a.finalize(&finalizer_key).unwrap();
+    assert_eq!(ctx.get_ctx_ins().len(), 2);
+  }
+
+  #[test]
+  fn test_secrets_are_sealed() {
+    let tx_in = gen_tx_in(150000);
+    let (finalizer_key, finalizer) = gen_finalizer();
+    let mut pst = Pst::new(1000, &finalizer);
+    pst.add_input(gen_input(&tx_in)).unwrap();
+    pst.hand_over_input(&tx_in).unwrap();
+
+    let bytes = pst.to_bytes().unwrap();
+    let spending_key = tx_in.spending_key().expose_secret().to_bytes();
+    let gamma = tx_in.gamma().expose_secret().to_bytes();
+    for secret in [spending_key, gamma] {
+      assert!(!bytes.windows(secret.len()).any(|x| x == secret));
+      assert!(!format!("{pst:?}").contains(&hex::encode(&secret)));
+    }
+
+    // the sealed input is bound to its outpoint
+    let mut moved = Pst::new(1000, &finalizer);
+    let mut input = pst.inputs()[0].clone();
+    input.out_point = OutPoint::new(&CTxId::random()).unwrap();
+    moved.add_input(input).unwrap();
+    assert!(matches!(
+      moved.finalize(&finalizer_key),
+      Err(Error::FailedToOpen(_))
+    ));
+  }
+
+  #[test]
+  fn test_wrong_signer_is_rejected() {
+    let tx_in = gen_tx_in(150000);
+    let mut input = gen_input(&tx_in);
+    input.signer = Some(PublicKey::random().unwrap());
+
+    let mut pst = Pst::new(1000, &gen_finalizer().1);
+    pst.add_input(input).unwrap();
+    assert!(matches!(
+      pst.hand_over_input(&tx_in),
+      Err(Error::WrongSigner)
+    ));
+    assert!(matches!(
+      pst.hand_over_input(&gen_tx_in(150000)),
+      Err(Error::UnknownInput)
+    ));
+  }
+
+  #[test]
+  fn test_conflicts_are_rejected() {
+    let tx_in = gen_tx_in(150000);
+    let (_, finalizer) = gen_finalizer();
+    let mut pst = Pst::new(1000, &finalizer);
+    pst.add_input(gen_input(&tx_in)).unwrap();
+
+    let mut other = Pst::new(1000, &finalizer);
+    let mut input = gen_input(&tx_in);
+    input.amount = Some(1);
+    other.add_input(input).unwrap();
+    other.add_output(gen_output(10000)).unwrap();
+
+    assert!(matches!(pst.combine(&other), Err(Error::Conflict(_))));
+    // nothing is merged on a conflict
+    assert_eq!(pst.outputs_len(), 0);
+    assert_eq!(pst.inputs()[0].amount, Some(150000));
+
+    assert!(matches!(
+      pst.combine(&Pst::new(2000, &finalizer)),
+      Err(Error::Conflict(_))
+    ));
+    assert!(matches!(
+      pst.combine(&Pst::new(1000, &gen_finalizer().1)),
+      Err(Error::Conflict(_))
+    ));
+  }
+
+  #[test]
+  fn test_conflicting_sealed_inputs_are_rejected() {
+    let tx_in = gen_tx_in(150000);
+    // the same outpoint with other secrets
+    let other_tx_in = TxIn::new(
+      tx_in.amount(),
+      &Scalar::new(200).unwrap(),
+      &ChildKey::random().unwrap().to_tx_key().to_spending_key(),
+      &TokenId::default().unwrap(),
+      &tx_in.out_point(),
+      false,
+      false,
+    )
+    .unwrap();
+    let (finalizer_key, finalizer) = gen_finalizer();
+    let mut input = PstInput::new(&tx_in.out_point());
+    input.amount = Some(tx_in.amount());
+
+    let mut pst = Pst::new(1000, &finalizer);
+    pst.add_input(input).unwrap();
+    let mut pst_a = Pst::from_bytes(&pst.to_bytes().unwrap()).unwrap();
+    pst_a.hand_over_input(&tx_in).unwrap();
+    let mut pst_b = Pst::from_bytes(&pst.to_bytes().unwrap()).unwrap();
+    pst_b.hand_over_input(&tx_in).unwrap();
+    let mut pst_c = Pst::from_bytes(&pst.to_bytes().unwrap()).unwrap();
+    pst_c.hand_over_input(&other_tx_in).unwrap();
+
+    // sealing the same secrets again does not conflict
+    pst_a.combine(&pst_b).unwrap();
+    assert!(matches!(
+      pst_a.combine(&pst_c),
+      Err(Error::Conflict(x)) if x == "sealed input"
+    ));
+
+    // the commitment has to match what is sealed
+    let mut forged = Pst::new(1000, &finalizer);
+    let mut input = pst_c.inputs()[0].clone();
+    input.sealed.as_mut().unwrap().commitment =
+      pst_a.inputs()[0].sealed.as_ref().unwrap().commitment;
+    forged.add_input(input).unwrap();
+    assert!(matches!(
+      forged.finalize(&finalizer_key),
+      Err(Error::FailedToOpen(_))
+    ));
+  }
+
+  #[test]
+  fn test_invalid_encoding_is_rejected() {
+    let mut bytes = Pst::new(1000, &gen_finalizer().1).to_bytes().unwrap();
+    assert_eq!(Pst::from_bytes(&bytes).unwrap().fee(), 1000);
+
+    bytes[PST_MAGIC.len()] = PST_VERSION + 1;
+    assert!(matches!(
+      Pst::from_bytes(&bytes),
+      Err(Error::UnsupportedVersion(_))
+    ));
+    assert!(Pst::from_bytes(b"psbt").is_err());
+    assert!(Pst::from_base64("not base64!").is_err());
+  }
+}
@@ -1,8 +1,11 @@
+#[cfg(feature = "async")]
+use crate::blocking_pool::BlockingPool;
 use crate::{
   amount_recovery_req::AmountRecoveryReq,
   amount_recovery_res::AmountRecoveryRes,
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
+  error::{result_code, ResultCode},
   ffi::{
     add_to_amount_recovery_req_vec, add_to_range_proof_vec, add_to_uint64_vec, build_range_proof,
     create_amount_recovery_req_vec, create_range_proof_vec, create_uint64_vec,
@@ -15,48 +18,40 @@ use crate::{
     get_range_proof_tau_x, recover_amount, serialize_range_proof, verify_range_proofs, BlsctPoint,
     BlsctRangeProof, BlsctRetVal, BlsctScalar,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_size, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_size, impl_value},
   point::Point,
   scalar::Scalar,
   token_id::TokenId,
 };
 use serde::{Deserialize, Serialize};
-use std::{
-  ffi::{c_char, c_void, CStr, CString, NulError},
-  fmt,
-};
+use std::ffi::{c_char, c_void, CStr, CString, NulError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum Error<'a> {
-  BlsctObjError(blsct_obj::Error<'a>),
+  #[error("Failed to create uint64_t vector")]
   FailedToCreateUint64Vec,
-  FailedToCreateCString(NulError),
+
+  #[error("Failed to create CString")]
+  FailedToCreateCString(#[source] NulError),
+
+  #[error("Failed to create range proof vector")]
   FailedToCreateRangeProofVector,
-  FailedToVerifyRangeProofs(u8),
+
+  #[error("Failed to verify range proofs")]
+  FailedToVerifyRangeProofs(#[source] ResultCode),
+
+  #[error("Failed to create amount recovery request vector")]
   FailedToCreateAmountRecoveryRequestVector,
-  FailedToCreateDefaultTokenId,
-  FailedToRecoverAmount(u8),
-}
 
-impl<'a> std::error::Error for Error<'a> {}
+  #[error("Failed to create default token id")]
+  FailedToCreateDefaultTokenId,
 
-impl<'a> fmt::Display for Error<'a> {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    match self {
-      Error::BlsctObjError(e) => write!(f, "BlsctObjError: {e:?}"),
-      Error::FailedToCreateUint64Vec => write!(f, "Failed to create uint64_t vector"),
-      Error::FailedToCreateCString(e) => write!(f, "Failed to create CString: {e:?}"),
-      Error::FailedToCreateRangeProofVector => {
-        write!(f, "Failed to create range proof vector")
-      }
-      Error::FailedToVerifyRangeProofs(e) => write!(f, "Failed to verify range proofs: {e}"),
-      Error::FailedToCreateAmountRecoveryRequestVector => {
-        write!(f, "Failed to create amount recovery request vector")
-      }
-      Error::FailedToCreateDefaultTokenId => write!(f, "Failed to create default token id"),
-      Error::FailedToRecoverAmount(e) => write!(f, "Failed to recover amount: {e}"),
-    }
-  }
+  #[error("Failed to recover amount")]
+  FailedToRecoverAmount(#[source] ResultCode),
 }
 
 #[derive(Debug, Deserialize, Serialize, Eq)]
@@ -66,15 +61,16 @@ pub struct RangeProof {
 
 impl_from_retval!(RangeProof);
 impl_display!(RangeProof);
+impl_bytes!(RangeProof);
 impl_clone!(RangeProof);
 
 impl RangeProof {
-  pub fn new<'a>(
+  pub fn new(
     amounts: &Vec<u64>,
     nonce: &Point,
     msg: &str,
     token_id: &TokenId,
-  ) -> Result<Self, Error<'a>> {
+  ) -> Result<Self, Error> {
     let vp_u64_vec = {
       let vec = unsafe { create_uint64_vec() };
       if vec.is_null() {
@@ -95,7 +91,7 @@ impl RangeProof {
     Ok(obj.into())
   }
 
-  pub fn verify_proofs<'a>(proofs: &Vec<RangeProof>) -> Result<bool, Error<'a>> {
+  pub fn verify_proofs(proofs: &Vec<RangeProof>) -> Result<bool, Error> {
     let range_proofs = unsafe { create_range_proof_vec() };
     if range_proofs.is_null() {
       return Err(Error::FailedToCreateRangeProofVector);
@@ -107,6 +103,12 @@ impl RangeProof {
     }
 
     let rv = unsafe { verify_range_proofs(range_proofs) };
+    if rv.is_null() {
+      unsafe { delete_range_proof_vec(range_proofs) };
+      return Err(Error::BlsctObjError(
+        blsct_obj::Error::FailedToAllocateMemory("BlsctBoolRetVal"),
+      ));
+    }
     let (result, value) = unsafe { ((*rv).result, (*rv).value) };
     unsafe {
       delete_range_proof_vec(range_proofs);
@@ -116,24 +118,24 @@ impl RangeProof {
     if result == 0 {
       Ok(value)
     } else {
-      Err(Error::FailedToVerifyRangeProofs(result))
+      Err(Error::FailedToVerifyRangeProofs(result_code(result)))
     }
   }
 
-  pub fn recover_amounts<'a>(
-    reqs: Vec<AmountRecoveryReq>,
-  ) -> Result<Vec<AmountRecoveryRes>, Error<'a>> {
+  pub fn recover_amounts(reqs: Vec<AmountRecoveryReq>) -> Result<Vec<AmountRecoveryRes>, Error> {
     let req_vec = unsafe { create_amount_recovery_req_vec() };
     if req_vec.is_null() {
       return Err(Error::FailedToCreateAmountRecoveryRequestVector);
     }
+    let default_token_id = match TokenId::default() {
+      Ok(token_id) => token_id,
+      Err(_) => {
+        unsafe { delete_amount_recovery_req_vec(req_vec) };
+        return Err(Error::FailedToCreateDefaultTokenId);
+      }
+    };
     for req in reqs {
-      let default_token_id = if req.token_id.is_none() {
-        Some(TokenId::default().map_err(|_| Error::FailedToCreateDefaultTokenId)?)
-      } else {
-        None
-      };
-      let token_id = req.token_id.as_ref().or(default_token_id.as_ref()).unwrap();
+      let token_id = req.token_id.as_ref().unwrap_or(&default_token_id);
 
       let blsct_req = unsafe {
         gen_amount_recovery_req(
@@ -149,11 +151,16 @@ impl RangeProof {
 
     let rv = unsafe { recover_amount(req_vec) };
     unsafe { delete_amount_recovery_req_vec(req_vec) };
+    if rv.is_null() {
+      return Err(Error::BlsctObjError(
+        blsct_obj::Error::FailedToAllocateMemory("BlsctAmountsRetVal"),
+      ));
+    }
 
     let (result, value) = unsafe { ((*rv).result, (*rv).value) };
     if result != 0 {
       unsafe { free_amounts_ret_val(rv) };
-      return Err(Error::FailedToRecoverAmount(result));
+      return Err(Error::FailedToRecoverAmount(result_code(result)));
     }
 
     let mut results: Vec<AmountRecoveryRes> = vec![];
@@ -163,14 +170,15 @@ impl RangeProof {
       let is_succ = unsafe { get_amount_recovery_result_is_succ(value, i) };
       let amount = unsafe { get_amount_recovery_result_amount(value, i) };
       let msg_c_str = unsafe { get_amount_recovery_result_msg(value, i) };
-      let msg = if msg_c_str.is_null() {
-        ""
+      let msg_bytes = if msg_c_str.is_null() {
+        &[]
       } else {
-        unsafe { CStr::from_ptr(msg_c_str) }.to_str().unwrap()
+        unsafe { CStr::from_ptr(msg_c_str) }.to_bytes()
       };
-      let result = AmountRecoveryRes::new(is_succ, amount, msg);
+      let result = AmountRecoveryRes::from_msg_bytes(is_succ, amount, msg_bytes);
       results.push(result);
     }
+    unsafe { free_amounts_ret_val(rv) };
 
     Ok(results)
   }
@@ -235,7 +243,39 @@ impl RangeProof {
   impl_value!(BlsctRangeProof);
 }
 
+// the async counterparts run on the blocking pool, so the arguments are owned
+#[cfg(feature = "async")]
+impl RangeProof {
+  pub async fn new_async(
+    amounts: Vec<u64>,
+    nonce: Point,
+    msg: String,
+    token_id: TokenId,
+  ) -> Result<Self, crate::error::Error> {
+    let pool = BlockingPool::global()?;
+    pool
+      .spawn(move || Ok(Self::new(&amounts, &nonce, &msg, &token_id)?))
+      .await?
+  }
+
+  pub async fn verify_proofs_async(proofs: Vec<RangeProof>) -> Result<bool, crate::error::Error> {
+    let pool = BlockingPool::global()?;
+    pool
+      .spawn(move || Ok(Self::verify_proofs(&proofs)?))
+      .await?
+  }
+
+  pub async fn recover_amounts_async(
+    reqs: Vec<AmountRecoveryReq>,
+  ) -> Result<Vec<AmountRecoveryRes>, crate::error::Error> {
+    let pool = BlockingPool::global()?;
+    pool.spawn(move || Ok(Self::recover_amounts(reqs)?)).await?
+  }
+}
+
 impl BlsctSerde for RangeProof {
+  const VARIABLE_SIZE: bool = true;
+
   unsafe fn serialize(ptr: *const u8, size: usize) -> *const i8 {
     serialize_range_proof(ptr as *const BlsctRangeProof, size)
   }
@@ -268,7 +308,7 @@ impl PartialEq for RangeProof {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
+  use std::{sync::Arc, thread};
 
   fn gen_range_proof() -> RangeProof {
     let values = vec![123u64];
@@ -280,7 +320,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_A() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_A();
   }
@@ -288,7 +327,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_A_wip() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_A_wip();
   }
@@ -296,7 +334,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_B() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_B();
   }
@@ -304,7 +341,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_r_prime() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_r_prime();
   }
@@ -312,7 +348,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_s_prime() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_s_prime();
   }
@@ -320,7 +355,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_delta_prime() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_delta_prime();
   }
@@ -328,7 +362,6 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_alpha_hat() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_alpha_hat();
   }
@@ -336,23 +369,49 @@ mod tests {
   #[test]
   #[allow(non_snake_case)]
   fn test_get_tau_x() {
-    init();
     let rp = gen_range_proof();
     let _ = rp.get_tau_x();
   }
 
   #[test]
   fn test_prove_and_verify() {
-    init();
     let rp = gen_range_proof();
     let result = RangeProof::verify_proofs(&vec![rp]).unwrap();
     assert!(result);
   }
 
   #[test]
-  fn test_recover_amounts() {
-    init();
+  fn test_parallel_prove_and_verify() {
+    let shared_rp = Arc::new(gen_range_proof());
+    let handles = (0..8u64)
+      .map(|i| {
+        let shared_rp = Arc::clone(&shared_rp);
+        thread::spawn(move || {
+          let mut rps = vec![];
+          for j in 0..10u64 {
+            let nonce = Point::random().unwrap();
+            let token_id = TokenId::default().unwrap();
+            let rp = RangeProof::new(&vec![i * 100 + j], &nonce, "navio", &token_id).unwrap();
+            let proofs = vec![rp.clone(), (*shared_rp).clone()];
+            assert!(RangeProof::verify_proofs(&proofs).unwrap());
+            rps.push(rp);
+          }
+          rps
+        })
+      })
+      .collect::<Vec<_>>();
+
+    // proofs generated on other threads verify on this one
+    let rps = handles
+      .into_iter()
+      .flat_map(|handle| handle.join().unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(rps.len(), 80);
+    assert!(RangeProof::verify_proofs(&rps).unwrap());
+  }
 
+  #[test]
+  fn test_recover_amounts() {
     let msg = "navio";
     let amount = 123u64;
 
@@ -373,8 +432,6 @@ mod tests {
 
   #[test]
   fn test_recover_amounts_with_non_default_token() {
-    init();
-
     let msg = "navio-token";
     let amount = 321u64;
 
@@ -393,12 +450,41 @@ mod tests {
     assert_eq!(res[0].msg, msg);
   }
 
+  #[cfg(feature = "async")]
+  #[test]
+  fn test_async() {
+    use crate::test_util::block_on;
+
+    let nonce = Point::random().unwrap();
+    let token_id = TokenId::default().unwrap();
+    let rp = block_on(RangeProof::new_async(
+      vec![123],
+      nonce.clone(),
+      "navio".to_string(),
+      token_id,
+    ))
+    .unwrap();
+    assert!(block_on(RangeProof::verify_proofs_async(vec![rp.clone()])).unwrap());
+
+    let req = AmountRecoveryReq::new(&rp, &nonce);
+    let res = block_on(RangeProof::recover_amounts_async(vec![req])).unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].amount, 123);
+    assert_eq!(res[0].msg, "navio");
+  }
+
   #[test]
   fn test_deser() {
-    init();
     let a = gen_range_proof();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<RangeProof>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_bytes() {
+    let a = gen_range_proof();
+    let b = RangeProof::from_bytes(&a.to_bytes()).unwrap();
+    assert_eq!(a, b);
+  }
 }
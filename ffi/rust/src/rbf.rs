@@ -0,0 +1,298 @@
+use crate::{
+  blsct_obj,
+  ctx::CTx,
+  fee::calc_fee,
+  token_id::TokenId,
+  tx_in::TxIn,
+  tx_out::{self, TxOut},
+  unsigned_input::UnsignedInput,
+  unsigned_output::UnsignedOutput,
+  unsigned_transaction::{self, UnsignedTransaction},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error("Input at {0} does not signal RBF")]
+  NotReplaceable(usize),
+
+  #[error("Out-amount {out_amount} exceeds in-amount {in_amount}")]
+  OutputsExceedInputs { in_amount: u64, out_amount: u64 },
+
+  #[error("New fee {new_fee} does not exceed the original fee {original_fee}")]
+  FeeNotIncreased { original_fee: u64, new_fee: u64 },
+
+  #[error("Change output index {0} is out of range")]
+  ChangeOutputOutOfRange(usize),
+
+  #[error("Change output at {0} is not of the default token")]
+  NonDefaultTokenChangeOutput(usize),
+
+  #[error("No change output to take the fee increase from")]
+  NoChangeOutput,
+
+  #[error("Insufficient funds: available {available}, required {required}")]
+  InsufficientFunds { available: u64, required: u64 },
+
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
+
+  #[error(transparent)]
+  TxOutError(#[from] tx_out::Error),
+
+  #[error(transparent)]
+  UnsignedTransactionError(#[from] unsigned_transaction::Error),
+}
+
+fn sum_default_token_amounts<T>(
+  xs: &[T],
+  default_token_id: &TokenId,
+  token_id: impl Fn(&T) -> TokenId,
+  amount: impl Fn(&T) -> u64,
+) -> u64 {
+  xs.iter()
+    .filter(|x| &token_id(*x) == default_token_id)
+    .map(amount)
+    .sum()
+}
+
+fn with_rbf(tx_in: &TxIn) -> Result<TxIn, Error> {
+  TxIn::new(
+    tx_in.amount(),
+    &tx_in.gamma(),
+    &tx_in.spending_key(),
+    &tx_in.token_id(),
+    &tx_in.out_point(),
+    tx_in.is_staked_commitment(),
+    true,
+  )
+  .map_err(Error::BlsctObjError)
+}
+
+// the resized output gets a fresh blinding key. reusing the original one
+// would link the replacement output to the output it replaces.
+fn with_amount(tx_out: &TxOut, amount: u64) -> Result<TxOut, Error> {
+  let memo = tx_out.memo()?;
+  TxOut::new(
+    &tx_out.destination(),
+    amount,
+    &memo,
+    &tx_out.token_id(),
+    tx_out.output_type(),
+    tx_out.min_stake(),
+    tx_out.subtract_fee_from_amount(),
+    None,
+  )
+  .map_err(Error::TxOutError)
+}
+
+// rebuilds the transaction spending `original_inputs` with a fee of `new_fee_rate`.
+// the fee increase is taken from the change output at `change_index`. if the change
+// is not large enough, inputs from `spare_inputs` are added in order until it is.
+// returns the replacement transaction and the fee increase.
+pub fn bump_fee(
+  original_inputs: &[TxIn],
+  original_outputs: &[TxOut],
+  new_fee_rate: u64,
+  change_index: Option<usize>,
+  spare_inputs: &[TxIn],
+) -> Result<(CTx, u64), Error> {
+  for (i, tx_in) in original_inputs.iter().enumerate() {
+    if !tx_in.is_rbf() {
+      return Err(Error::NotReplaceable(i));
+    }
+  }
+  let default_token_id = TokenId::default()?;
+  let in_amount = sum_default_token_amounts(
+    original_inputs,
+    &default_token_id,
+    TxIn::token_id,
+    TxIn::amount,
+  );
+  let out_amount = sum_default_token_amounts(
+    original_outputs,
+    &default_token_id,
+    TxOut::token_id,
+    TxOut::amount,
+  );
+  if out_amount > in_amount {
+    return Err(Error::OutputsExceedInputs {
+      in_amount,
+      out_amount,
+    });
+  }
+  let original_fee = in_amount - out_amount;
+
+  let new_fee = calc_fee(original_inputs.len(), original_outputs.len(), new_fee_rate);
+  if new_fee <= original_fee {
+    return Err(Error::FeeNotIncreased {
+      original_fee,
+      new_fee,
+    });
+  }
+  let change_index = change_index.ok_or(Error::NoChangeOutput)?;
+  let change_tx_out = original_outputs
+    .get(change_index)
+    .ok_or(Error::ChangeOutputOutOfRange(change_index))?;
+  if change_tx_out.token_id() != default_token_id {
+    return Err(Error::NonDefaultTokenChangeOutput(change_index));
+  }
+  let non_change_out_amount = out_amount - change_tx_out.amount();
+
+  // add spare inputs until the inputs cover the non-change outputs and the fee
+  let mut tx_ins = original_inputs
+    .iter()
+    .map(with_rbf)
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut in_amount = in_amount;
+  let mut spare_inputs = spare_inputs
+    .iter()
+    .filter(|x| x.token_id() == default_token_id);
+  let fee = loop {
+    let fee = calc_fee(tx_ins.len(), original_outputs.len(), new_fee_rate);
+    if in_amount >= non_change_out_amount + fee {
+      break fee;
+    }
+    match spare_inputs.next() {
+      Some(tx_in) => {
+        in_amount += tx_in.amount();
+        tx_ins.push(with_rbf(tx_in)?);
+      }
+      None => {
+        return Err(Error::InsufficientFunds {
+          available: in_amount,
+          required: non_change_out_amount + fee,
+        })
+      }
+    }
+  };
+  let change = in_amount - non_change_out_amount - fee;
+
+  let mut unsigned_tx = UnsignedTransaction::new()?;
+  for tx_in in &tx_ins {
+    let input = UnsignedInput::new(tx_in)?;
+    unsigned_tx.add_input(&input);
+  }
+  for (i, tx_out) in original_outputs.iter().enumerate() {
+    let output = if i == change_index {
+      UnsignedOutput::new(&with_amount(tx_out, change)?)
+    } else {
+      UnsignedOutput::new(tx_out)
+    }?;
+    unsigned_tx.add_output(&output);
+  }
+  unsigned_tx.set_fee(fee);
+
+  let ctx = unsigned_tx.sign()?;
+  Ok((ctx, fee - original_fee))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ctx_id::CTxId, fee::DEFAULT_FEE_RATE, ffi::TxOutputType, keys::public_key::PublicKey,
+    out_point::OutPoint, scalar::Scalar, sub_addr::SubAddr, sub_addr_id::SubAddrId,
+    util::gen_random_view_key,
+  };
+
+  fn gen_tx_in(amount: u64, is_rbf: bool) -> TxIn {
+    TxIn::new(
+      amount,
+      &Scalar::new(100).unwrap(),
+      &Scalar::new(101).unwrap(),
+      &TokenId::default().unwrap(),
+      &OutPoint::new(&CTxId::random()).unwrap(),
+      false,
+      is_rbf,
+    )
+    .unwrap()
+  }
+
+  fn gen_tx_out(amount: u64) -> TxOut {
+    let destination = {
+      let view_key = gen_random_view_key().unwrap();
+      let spending_pub_key = PublicKey::random().unwrap();
+      SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(0, 0))
+    };
+    TxOut::new(
+      &destination,
+      amount,
+      "navio",
+      &TokenId::default().unwrap(),
+      TxOutputType::Normal,
+      0,
+      false,
+      Some(&Scalar::random().unwrap()),
+    )
+    .unwrap()
+  }
+
+  // 1 input, 2 outputs (payment and change) paying the default fee rate
+  fn gen_original(change: u64) -> (Vec<TxIn>, Vec<TxOut>) {
+    let fee = calc_fee(1, 2, DEFAULT_FEE_RATE);
+    let tx_ins = vec![gen_tx_in(10000 + change + fee, true)];
+    let tx_outs = vec![gen_tx_out(10000), gen_tx_out(change)];
+    (tx_ins, tx_outs)
+  }
+
+  #[test]
+  fn test_bump_fee_from_change() {
+    let (tx_ins, tx_outs) = gen_original(1000000);
+    let (ctx, delta) = bump_fee(&tx_ins, &tx_outs, DEFAULT_FEE_RATE * 2, Some(1), &[]).unwrap();
+
+    assert_eq!(delta, calc_fee(1, 2, DEFAULT_FEE_RATE));
+    assert_eq!(ctx.get_ctx_ins().len(), 1);
+  }
+
+  #[test]
+  fn test_resized_change_is_not_linked() {
+    let tx_out = gen_tx_out(1000000);
+    let resized = with_amount(&tx_out, 500000).unwrap();
+    assert_eq!(resized.amount(), 500000);
+    assert_eq!(resized.destination(), tx_out.destination());
+    assert_ne!(resized.blinding_key(), tx_out.blinding_key());
+  }
+
+  #[test]
+  fn test_bump_fee_adds_inputs() {
+    let (tx_ins, tx_outs) = gen_original(0);
+    let spare_inputs = vec![gen_tx_in(10000000, false)];
+    let (ctx, delta) = bump_fee(
+      &tx_ins,
+      &tx_outs,
+      DEFAULT_FEE_RATE * 2,
+      Some(1),
+      &spare_inputs,
+    )
+    .unwrap();
+
+    assert_eq!(
+      delta,
+      calc_fee(2, 2, DEFAULT_FEE_RATE * 2) - calc_fee(1, 2, DEFAULT_FEE_RATE)
+    );
+    assert_eq!(ctx.get_ctx_ins().len(), 2);
+  }
+
+  #[test]
+  fn test_bump_fee_insufficient_funds() {
+    let (tx_ins, tx_outs) = gen_original(0);
+    let res = bump_fee(&tx_ins, &tx_outs, DEFAULT_FEE_RATE * 2, Some(1), &[]);
+    assert!(matches!(res, Err(Error::InsufficientFunds { .. })));
+  }
+
+  #[test]
+  fn test_bump_fee_not_replaceable() {
+    let tx_ins = vec![gen_tx_in(1000000, false)];
+    let tx_outs = vec![gen_tx_out(10000)];
+    let res = bump_fee(&tx_ins, &tx_outs, DEFAULT_FEE_RATE * 2, Some(0), &[]);
+    assert_eq!(res.unwrap_err(), Error::NotReplaceable(0));
+  }
+
+  #[test]
+  fn test_bump_fee_not_increased() {
+    let (tx_ins, tx_outs) = gen_original(1000000);
+    let res = bump_fee(&tx_ins, &tx_outs, DEFAULT_FEE_RATE, Some(1), &[]);
+    assert!(matches!(res, Err(Error::FeeNotIncreased { .. })));
+  }
+}
@@ -1,15 +1,17 @@
-use crate::macros::{impl_display, impl_from_retval, impl_value};
+use crate::macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value};
 use crate::{
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
   ffi::{
-    are_scalar_equal, deserialize_scalar, gen_random_scalar, gen_scalar, scalar_to_uint64,
-    serialize_scalar, BlsctRetVal, BlsctScalar,
+    deserialize_scalar, gen_random_scalar, gen_scalar, scalar_to_uint64, serialize_scalar,
+    BlsctRetVal, BlsctScalar,
   },
   util::pad_hex_left,
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::c_char;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Scalar {
@@ -18,13 +20,15 @@ pub struct Scalar {
 
 impl_from_retval!(Scalar);
 impl_display!(Scalar);
+impl_bytes!(Scalar);
+impl_clone!(Scalar);
 
 impl Scalar {
-  pub fn new<'a>(n: u64) -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn new(n: u64) -> Result<Self, blsct_obj::Error> {
     Self::from_retval(unsafe { gen_scalar(n) })
   }
 
-  pub fn random<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn random() -> Result<Self, blsct_obj::Error> {
     Self::from_retval(unsafe { gen_random_scalar() })
   }
 
@@ -55,28 +59,36 @@ impl From<BlsctObj<Scalar, BlsctScalar>> for Scalar {
   }
 }
 
+// scalars mostly hold secrets such as keys and gammas, so they are compared
+// in constant time. the encoding of a scalar is canonical, so comparing the
+// bytes is equivalent to comparing the values.
+impl ConstantTimeEq for Scalar {
+  fn ct_eq(&self, other: &Self) -> Choice {
+    self.obj.ct_eq(&other.obj)
+  }
+}
+
 impl PartialEq for Scalar {
   fn eq(&self, other: &Self) -> bool {
-    unsafe {
-      are_scalar_equal(
-        self.obj.as_ptr() as *const BlsctScalar,
-        other.obj.as_ptr() as *const BlsctScalar,
-      ) != 0
-    }
+    self.ct_eq(other).into()
   }
 }
 
 impl Eq for Scalar {}
 
+// wiping a scalar leaves it set to zero
+impl Zeroize for Scalar {
+  fn zeroize(&mut self) {
+    self.obj.zeroize();
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
 
   #[test]
   fn test_new() {
-    init();
-
     let x = Scalar::new(123).unwrap();
     let x_u64: u64 = x.into();
     assert!(x_u64 == 123);
@@ -84,8 +96,6 @@ mod tests {
 
   #[test]
   fn test_random() {
-    init();
-
     let mut prev: u64 = 0;
     let mut dup_tolerance = 5;
 
@@ -107,7 +117,6 @@ mod tests {
 
   #[test]
   fn test_from() {
-    init();
     let x = Scalar::new(12345).unwrap();
     let x_u64: u64 = x.into();
     assert!(x_u64 == 12345);
@@ -115,7 +124,6 @@ mod tests {
 
   #[test]
   fn test_eq() {
-    init();
     let a = Scalar::new(123);
     let b = Scalar::new(456);
 
@@ -127,7 +135,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = Scalar::new(12345).unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<Scalar>(&hex).unwrap();
@@ -137,7 +144,6 @@ mod tests {
   #[test]
   fn test_display() {
     use regex::Regex;
-    init();
     let x = Scalar::random().unwrap();
     let s = format!("{}", x);
 
@@ -2,12 +2,16 @@ use crate::{
   blsct_obj::BlsctObj,
   blsct_serde::BlsctSerde,
   ffi::{deserialize_script, serialize_script, BlsctRetVal, BlsctScript, SCRIPT_SIZE},
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   util::gen_random_malloced_buf,
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::c_char;
 
+// opcodes as numbered in opcodetype of navio-core (script/script.h)
+pub const OP_RETURN: u8 = 0x6a;
+pub const OP_STAKED_COMMITMENT: u8 = 0xbc;
+
 #[derive(Debug, Deserialize, Serialize, Eq)]
 pub struct Script {
   obj: BlsctObj<Script, BlsctScript>,
@@ -15,6 +19,7 @@ pub struct Script {
 
 impl_from_retval!(Script);
 impl_display!(Script);
+impl_bytes!(Script);
 impl_clone!(Script);
 
 impl Script {
@@ -28,10 +33,20 @@ impl Script {
     obj.into()
   }
 
+  pub fn is_staked_commitment(&self) -> bool {
+    self.obj.as_slice().first() == Some(&OP_STAKED_COMMITMENT)
+  }
+
+  pub fn is_op_return(&self) -> bool {
+    self.obj.as_slice().first() == Some(&OP_RETURN)
+  }
+
   impl_value!(BlsctScript);
 }
 
 impl BlsctSerde for Script {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_script(ptr as *const BlsctScript)
   }
@@ -56,24 +71,74 @@ impl From<BlsctObj<Script, BlsctScript>> for Script {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
+  use crate::{
+    chain::{Chain, COIN},
+    keys::{child_key::ChildKey, public_key::PublicKey},
+    scalar::Scalar,
+    staking::build_stake_ctx,
+    sub_addr::SubAddr,
+    sub_addr_id::SubAddrId,
+    test_util::{gen_ctx, gen_out_point},
+    token_id::TokenId,
+    tx_in::TxIn,
+  };
 
   #[test]
   fn test_new() {
-    init();
     let blsct_script = gen_random_malloced_buf::<SCRIPT_SIZE>();
     let _ = Script::new(blsct_script);
   }
 
   #[test]
   fn test_random() {
-    init();
     let _ = Script::random();
   }
 
+  // the scriptPubKey libblsct gives a staked commitment output starts with
+  // OP_STAKED_COMMITMENT, the way navio-core builds it
+  #[test]
+  fn test_is_staked_commitment() {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let dest = SubAddr::new(
+      &view_key,
+      &PublicKey::random().unwrap(),
+      &SubAddrId::new(0, 0),
+    );
+    let amount = Chain::get().unwrap().min_stake();
+    let tx_in = TxIn::new(
+      amount + COIN,
+      &Scalar::new(100).unwrap(),
+      &Scalar::random().unwrap(),
+      &TokenId::default().unwrap(),
+      &gen_out_point(),
+      false,
+      false,
+    )
+    .unwrap();
+    let ctx = build_stake_ctx(&vec![tx_in], &dest, amount, amount, &dest).unwrap();
+
+    let ctx_outs = ctx.get_ctx_outs();
+    let staked = (0..ctx_outs.len())
+      .map(|i| ctx_outs.at(i).unwrap().script_pub_key())
+      .filter(|x| x.is_staked_commitment())
+      .collect::<Vec<_>>();
+    assert_eq!(staked.len(), 1);
+    assert_eq!(staked[0].to_bytes()[0], OP_STAKED_COMMITMENT);
+
+    // neither the payment nor the fee output of a normal CTx is staked
+    let ctx = gen_ctx();
+    let ctx_outs = ctx.get_ctx_outs();
+    for i in 0..ctx_outs.len() {
+      assert!(!ctx_outs
+        .at(i)
+        .unwrap()
+        .script_pub_key()
+        .is_staked_commitment());
+    }
+  }
+
   #[test]
   fn test_eq() {
-    init();
     let (a, b) = {
       loop {
         let a = Script::random();
@@ -91,7 +156,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = Script::random();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<Script>(&hex).unwrap();
@@ -0,0 +1,187 @@
+use crate::scalar::Scalar;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, ops::Deref};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const REDACTED: &str = "<redacted>";
+
+// holds secret key material such as view keys, spending keys, gammas and seeds.
+// the buffer is wiped before it is freed and is never printed.
+pub struct Secret<T: Zeroize>(T);
+
+pub type SecretScalar = Secret<Scalar>;
+
+impl<T: Zeroize> Secret<T> {
+  pub fn new(value: T) -> Self {
+    Secret(value)
+  }
+
+  pub fn expose_secret(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+  fn from(value: T) -> Self {
+    Secret(value)
+  }
+}
+
+// lets a secret be passed wherever a reference to the inner value is expected
+impl<T: Zeroize> Deref for Secret<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+  fn drop(&mut self) {
+    self.0.zeroize();
+  }
+}
+
+impl<T: Zeroize> ZeroizeOnDrop for Secret<T> {}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{REDACTED}")
+  }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{REDACTED}")
+  }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+  fn clone(&self) -> Self {
+    Secret(self.0.clone())
+  }
+}
+
+impl<T: Zeroize + ConstantTimeEq> ConstantTimeEq for Secret<T> {
+  fn ct_eq(&self, other: &Self) -> Choice {
+    self.0.ct_eq(&other.0)
+  }
+}
+
+// secrets are only compared in constant time
+impl<T: Zeroize + ConstantTimeEq> PartialEq for Secret<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.ct_eq(other).into()
+  }
+}
+
+impl<T: Zeroize + ConstantTimeEq> Eq for Secret<T> {}
+
+impl<T: Zeroize + Serialize> Serialize for Secret<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.0.serialize(serializer)
+  }
+}
+
+impl<'de, T: Zeroize + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    T::deserialize(deserializer).map(Secret)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    keys::child_key::ChildKey,
+    test_util::{assert_constant_time, timing_t_stat, TIMING_LEAK_T},
+  };
+  use std::hint::black_box;
+
+  #[test]
+  fn test_redacted() {
+    let secret = SecretScalar::new(Scalar::random().unwrap());
+    assert_eq!(format!("{secret}"), REDACTED);
+    assert_eq!(format!("{secret:?}"), REDACTED);
+    assert!(!format!("{:?}", Some(&secret)).contains(&secret.expose_secret().to_string()));
+  }
+
+  #[test]
+  fn test_zeroize() {
+    let mut scalar = Scalar::new(12345).unwrap();
+    scalar.zeroize();
+    assert_eq!(u64::from(scalar), 0);
+  }
+
+  #[test]
+  fn test_deref_and_clone() {
+    let secret = SecretScalar::new(Scalar::new(42).unwrap());
+    let scalar: &Scalar = &secret;
+    assert_eq!(scalar, &Scalar::new(42).unwrap());
+    assert_eq!(secret.clone(), secret);
+  }
+
+  #[test]
+  fn test_deser() {
+    let secret = SecretScalar::new(Scalar::random().unwrap());
+    let bytes = bincode::serialize(&secret).unwrap();
+    assert_eq!(
+      bincode::deserialize::<SecretScalar>(&bytes).unwrap(),
+      secret
+    );
+  }
+
+  #[test]
+  fn test_ct_eq() {
+    let a = SecretScalar::new(Scalar::new(7).unwrap());
+    let b = SecretScalar::new(Scalar::new(7).unwrap());
+    let c = SecretScalar::new(Scalar::new(8).unwrap());
+    assert!(bool::from(a.ct_eq(&b)));
+    assert!(!bool::from(a.ct_eq(&c)));
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+  }
+
+  // the harness must be able to tell a short-circuiting comparison apart
+  #[test]
+  fn test_timing_harness_detects_leak() {
+    let a = vec![0u8; 1 << 16];
+    let mut b_early = a.clone();
+    b_early[0] = 1;
+    let mut b_late = a.clone();
+    b_late[(1 << 16) - 1] = 1;
+
+    let t = timing_t_stat(
+      |class| {
+        let b = if class { &b_late } else { &b_early };
+        black_box(black_box(&a) == black_box(b));
+      },
+      2000,
+    );
+    assert!(t.abs() >= TIMING_LEAK_T, "leak not detected: t = {t}");
+  }
+
+  #[test]
+  fn test_scalar_eq_is_constant_time() {
+    let x = Scalar::random().unwrap();
+    let same = x.clone();
+    let other = Scalar::random().unwrap();
+    assert_constant_time(|class| {
+      let y = if class { &same } else { &other };
+      black_box(black_box(&x) == black_box(y));
+    });
+  }
+
+  #[test]
+  fn test_secret_eq_is_constant_time() {
+    let tx_key = ChildKey::random().unwrap().to_tx_key();
+    let x = tx_key.to_view_key();
+    let same = tx_key.to_view_key();
+    let other = tx_key.to_spending_key();
+    assert_constant_time(|class| {
+      let y = if class { &same } else { &other };
+      black_box(black_box(&x) == black_box(y));
+    });
+  }
+}
@@ -1,12 +1,41 @@
 use crate::{
-  blsct_obj::BlsctObj,
+  blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
-  ffi::{deserialize_signature, serialize_signature, BlsctRetVal, BlsctSignature, SIGNATURE_SIZE},
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  ffi::{
+    blsAggregateSignature, blsGetGeneratorOfPublicKey, blsPublicKeyDeserialize,
+    blsPublicKeySerialize, blsSignatureDeserialize, blsSignatureSerialize, deserialize_signature,
+    mclBnG1_neg, mclBnG2_hashAndMapTo, mclBnGT_isOne, mclBn_finalExp, mclBn_millerLoopVec,
+    serialize_signature, BlsPublicKey, BlsSignature, BlsctRetVal, BlsctSignature, MclBnG1, MclBnG2,
+    MclBnGT, PUBLIC_KEY_SIZE, SIGNATURE_SIZE,
+  },
+  keys::public_key::PublicKey,
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   util::gen_random_malloced_buf,
 };
 use serde::{Deserialize, Serialize};
-use std::ffi::c_char;
+use std::ffi::{c_char, c_void};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error("Nothing to aggregate")]
+  NothingToAggregate,
+
+  #[error("Got {0} public keys but {1} messages")]
+  LengthMismatch(usize, usize),
+
+  #[error("Invalid public key")]
+  InvalidPublicKey,
+
+  #[error("Invalid signature")]
+  InvalidSignature,
+
+  #[error("Failed to hash the message to a point")]
+  FailedToHashMessage,
+
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
+}
 
 #[derive(Debug, Deserialize, Serialize, Eq)]
 pub struct Signature {
@@ -15,6 +44,7 @@ pub struct Signature {
 
 impl_from_retval!(Signature);
 impl_display!(Signature);
+impl_bytes!(Signature);
 impl_clone!(Signature);
 
 impl Signature {
@@ -29,6 +59,122 @@ impl Signature {
   }
 
   impl_value!(BlsctSignature);
+
+  // the aggregate of signatures of any messages by any keys. it is as large
+  // as a single signature.
+  pub fn aggregate(sigs: &[Signature]) -> Result<Signature, Error> {
+    if sigs.is_empty() {
+      return Err(Error::NothingToAggregate);
+    }
+    let bls_sigs = sigs.iter().map(to_bls_sig).collect::<Result<Vec<_>, _>>()?;
+    let mut agg_sig = BlsSignature { v: MclBnG2::ZERO };
+    unsafe { blsAggregateSignature(&mut agg_sig, bls_sigs.as_ptr(), bls_sigs.len()) };
+    from_bls_sig(&agg_sig)
+  }
+
+  // verifies an aggregate signature where `pub_keys[i]` signed `msgs[i]`
+  pub fn verify_aggregate(&self, pub_keys: &[PublicKey], msgs: &[&str]) -> Result<bool, Error> {
+    if pub_keys.len() != msgs.len() {
+      return Err(Error::LengthMismatch(pub_keys.len(), msgs.len()));
+    }
+    if pub_keys.is_empty() {
+      return Err(Error::NothingToAggregate);
+    }
+
+    // checks e(-g, sig) * e(pk_1, H(m_1)) * .. * e(pk_n, H(m_n)) == 1
+    let mut g = BlsPublicKey { v: MclBnG1::ZERO };
+    let mut neg_g = MclBnG1::ZERO;
+    unsafe {
+      blsGetGeneratorOfPublicKey(&mut g);
+      mclBnG1_neg(&mut neg_g, &g.v);
+    }
+    let mut g1_points = vec![neg_g];
+    let mut g2_points = vec![to_bls_sig(self)?.v];
+    for (pub_key, msg) in pub_keys.iter().zip(msgs) {
+      // libblsct signs the message prefixed with the key of the signer
+      let mut aug_msg = pub_key.to_bytes();
+      aug_msg.extend_from_slice(msg.as_bytes());
+      let mut hash = MclBnG2::ZERO;
+      let rc = unsafe {
+        mclBnG2_hashAndMapTo(&mut hash, aug_msg.as_ptr() as *const c_void, aug_msg.len())
+      };
+      if rc != 0 {
+        return Err(Error::FailedToHashMessage);
+      }
+      g1_points.push(to_bls_pub_key(pub_key)?.v);
+      g2_points.push(hash);
+    }
+
+    let mut miller_loop = MclBnGT::ZERO;
+    let mut pairing = MclBnGT::ZERO;
+    unsafe {
+      mclBn_millerLoopVec(
+        &mut miller_loop,
+        g1_points.as_ptr(),
+        g2_points.as_ptr(),
+        g1_points.len(),
+      );
+      mclBn_finalExp(&mut pairing, &miller_loop);
+    }
+    Ok(unsafe { mclBnGT_isOne(&pairing) } == 1)
+  }
+
+  // verifies an aggregate signature of `msg` by every key in `pub_keys`.
+  // since each signer prefixes the message with its own key, the keys need
+  // no proof of possession.
+  pub fn verify_aggregate_same_msg(
+    &self,
+    pub_keys: &[PublicKey],
+    msg: &str,
+  ) -> Result<bool, Error> {
+    self.verify_aggregate(pub_keys, &vec![msg; pub_keys.len()])
+  }
+}
+
+// libblsct keeps keys and signatures serialized, while bls384_256 operates
+// on the deserialized points
+pub(crate) fn to_bls_pub_key(pub_key: &PublicKey) -> Result<BlsPublicKey, Error> {
+  let mut bls_pub_key = BlsPublicKey { v: MclBnG1::ZERO };
+  let bytes = pub_key.to_bytes();
+  let n = unsafe {
+    blsPublicKeyDeserialize(
+      &mut bls_pub_key,
+      bytes.as_ptr() as *const c_void,
+      bytes.len(),
+    )
+  };
+  if n != PUBLIC_KEY_SIZE {
+    return Err(Error::InvalidPublicKey);
+  }
+  Ok(bls_pub_key)
+}
+
+pub(crate) fn from_bls_pub_key(bls_pub_key: &BlsPublicKey) -> Result<PublicKey, Error> {
+  let mut buf = [0u8; PUBLIC_KEY_SIZE];
+  let n = unsafe { blsPublicKeySerialize(buf.as_mut_ptr() as *mut c_void, buf.len(), bls_pub_key) };
+  if n != PUBLIC_KEY_SIZE {
+    return Err(Error::InvalidPublicKey);
+  }
+  Ok(PublicKey::from_bytes(&buf)?)
+}
+
+fn to_bls_sig(sig: &Signature) -> Result<BlsSignature, Error> {
+  let mut bls_sig = BlsSignature { v: MclBnG2::ZERO };
+  let n =
+    unsafe { blsSignatureDeserialize(&mut bls_sig, sig.value() as *const c_void, SIGNATURE_SIZE) };
+  if n != SIGNATURE_SIZE {
+    return Err(Error::InvalidSignature);
+  }
+  Ok(bls_sig)
+}
+
+fn from_bls_sig(bls_sig: &BlsSignature) -> Result<Signature, Error> {
+  let mut buf = [0u8; SIGNATURE_SIZE];
+  let n = unsafe { blsSignatureSerialize(buf.as_mut_ptr() as *mut c_void, buf.len(), bls_sig) };
+  if n != SIGNATURE_SIZE {
+    return Err(Error::InvalidSignature);
+  }
+  Ok(Signature::from_bytes(&buf)?)
 }
 
 impl BlsctSerde for Signature {
@@ -56,24 +202,37 @@ impl From<BlsctObj<Signature, BlsctSignature>> for Signature {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
+  use crate::{ffi::sign_message, scalar::Scalar};
+  use std::ffi::CString;
+
+  fn sign(key: &Scalar, msg: &str) -> Signature {
+    let msg = CString::new(msg).unwrap();
+    BlsctObj::from_c_obj(unsafe { sign_message(key.value(), msg.as_ptr()) }).into()
+  }
+
+  fn gen_keys(n: usize) -> Vec<(Scalar, PublicKey)> {
+    (0..n)
+      .map(|_| {
+        let key = Scalar::random().unwrap();
+        let pub_key = PublicKey::from(&key);
+        (key, pub_key)
+      })
+      .collect()
+  }
 
   #[test]
   fn test_new() {
-    init();
     let blsct_signature = gen_random_malloced_buf::<SIGNATURE_SIZE>();
     let _ = Signature::new(blsct_signature);
   }
 
   #[test]
   fn test_random() {
-    init();
     let _ = Signature::random();
   }
 
   #[test]
   fn test_eq() {
-    init();
     let (a, b) = {
       loop {
         let a = Signature::random();
@@ -91,10 +250,69 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = Signature::random();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<Signature>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_aggregate_distinct_msgs() {
+    let keys = gen_keys(3);
+    let msgs = ["alpha", "beta", "gamma"];
+    let sigs: Vec<_> = keys
+      .iter()
+      .zip(msgs)
+      .map(|((key, _), msg)| sign(key, msg))
+      .collect();
+    let pub_keys: Vec<_> = keys.into_iter().map(|(_, pub_key)| pub_key).collect();
+
+    let agg_sig = Signature::aggregate(&sigs).unwrap();
+    assert_eq!(agg_sig.to_bytes().len(), SIGNATURE_SIZE);
+    assert!(agg_sig.verify_aggregate(&pub_keys, &msgs).unwrap());
+
+    // a single signature is an aggregate of one
+    assert!(sigs[0]
+      .verify_aggregate(&pub_keys[..1], &msgs[..1])
+      .unwrap());
+
+    let swapped = ["beta", "alpha", "gamma"];
+    assert!(!agg_sig.verify_aggregate(&pub_keys, &swapped).unwrap());
+    assert!(!agg_sig
+      .verify_aggregate(&pub_keys[..2], &msgs[..2])
+      .unwrap());
+    assert_eq!(
+      agg_sig.verify_aggregate(&pub_keys, &msgs[..2]),
+      Err(Error::LengthMismatch(3, 2))
+    );
+  }
+
+  #[test]
+  fn test_aggregate_same_msg() {
+    let keys = gen_keys(3);
+    let msg = "block 1000 is final";
+    let sigs: Vec<_> = keys.iter().map(|(key, _)| sign(key, msg)).collect();
+    let mut pub_keys: Vec<_> = keys.into_iter().map(|(_, pub_key)| pub_key).collect();
+
+    let agg_sig = Signature::aggregate(&sigs).unwrap();
+    assert!(agg_sig.verify_aggregate_same_msg(&pub_keys, msg).unwrap());
+    assert!(!agg_sig
+      .verify_aggregate_same_msg(&pub_keys, "block 1001 is final")
+      .unwrap());
+
+    pub_keys[1] = PublicKey::random().unwrap();
+    assert!(!agg_sig.verify_aggregate_same_msg(&pub_keys, msg).unwrap());
+  }
+
+  #[test]
+  fn test_aggregate_nothing() {
+    assert!(matches!(
+      Signature::aggregate(&[]),
+      Err(Error::NothingToAggregate)
+    ));
+    assert_eq!(
+      Signature::random().verify_aggregate(&[], &[]),
+      Err(Error::NothingToAggregate)
+    );
+  }
 }
@@ -0,0 +1,309 @@
+use crate::{
+  blsct_obj,
+  chain::{self, Chain},
+  ctx::{self, CTx},
+  ctx_outs,
+  fee::{calc_fee, DEFAULT_FEE_RATE},
+  ffi::TxOutputType,
+  out_point::OutPoint,
+  range_proof,
+  scalar::Scalar,
+  sub_addr::SubAddr,
+  token_id::TokenId,
+  tx_in::TxIn,
+  tx_out::{self, TxOut},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error("Min stake {min_stake} is below the chain minimum {chain_min_stake}")]
+  MinStakeBelowChainMinimum {
+    min_stake: u64,
+    chain_min_stake: u64,
+  },
+
+  #[error("Stake amount {amount} is below the min stake {min_stake}")]
+  AmountBelowMinStake { amount: u64, min_stake: u64 },
+
+  #[error("Input at {0} is not of the default token")]
+  NonDefaultTokenInput(usize),
+
+  #[error("Input at {0} is already a staked commitment")]
+  StakedCommitmentInput(usize),
+
+  #[error("Insufficient funds: available {available}, required {required}")]
+  InsufficientFunds { available: u64, required: u64 },
+
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
+
+  #[error(transparent)]
+  TxOutError(#[from] tx_out::Error),
+
+  #[error(transparent)]
+  RangeProofError(#[from] range_proof::Error),
+
+  #[error(transparent)]
+  CTxError(#[from] ctx::Error),
+
+  #[error(transparent)]
+  CTxOutsError(#[from] ctx_outs::Error),
+
+  #[error(transparent)]
+  ChainError(#[from] chain::Error),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StakedCommitment {
+  pub index: usize,
+  pub amount: u64,
+}
+
+pub fn validate_stake(chain: Chain, amount: u64, min_stake: u64) -> Result<(), Error> {
+  let chain_min_stake = chain.min_stake();
+  if min_stake < chain_min_stake {
+    return Err(Error::MinStakeBelowChainMinimum {
+      min_stake,
+      chain_min_stake,
+    });
+  }
+  if amount < min_stake {
+    return Err(Error::AmountBelowMinStake { amount, min_stake });
+  }
+  Ok(())
+}
+
+// locks `amount` in a staked commitment output sent to `stake_dest`.
+// the remainder of the inputs after the fee goes to `change_dest`.
+pub fn build_stake_ctx(
+  tx_ins: &Vec<TxIn>,
+  stake_dest: &SubAddr,
+  amount: u64,
+  min_stake: u64,
+  change_dest: &SubAddr,
+) -> Result<CTx, Error> {
+  let chain = Chain::get()?;
+  validate_stake(chain, amount, min_stake)?;
+
+  let default_token_id = TokenId::default()?;
+  for (i, tx_in) in tx_ins.iter().enumerate() {
+    if tx_in.token_id() != default_token_id {
+      return Err(Error::NonDefaultTokenInput(i));
+    }
+    if tx_in.is_staked_commitment() {
+      return Err(Error::StakedCommitmentInput(i));
+    }
+  }
+  let available = tx_ins.iter().map(|x| x.amount()).sum::<u64>();
+
+  let stake_tx_out = TxOut::new(
+    stake_dest,
+    amount,
+    "",
+    &default_token_id,
+    TxOutputType::StakedCommitment,
+    min_stake,
+    false,
+    None,
+  )?;
+  let mut tx_outs = vec![stake_tx_out];
+
+  // no change output is needed if the inputs exactly cover the stake and the fee
+  let fee_wo_change = calc_fee(tx_ins.len(), 1, DEFAULT_FEE_RATE);
+  if available != amount + fee_wo_change {
+    let required = amount + calc_fee(tx_ins.len(), 2, DEFAULT_FEE_RATE);
+    if available < required {
+      return Err(Error::InsufficientFunds {
+        available,
+        required,
+      });
+    }
+    let change_tx_out = TxOut::new(
+      change_dest,
+      available - required,
+      "",
+      &default_token_id,
+      TxOutputType::Normal,
+      0,
+      false,
+      None,
+    )?;
+    tx_outs.push(change_tx_out);
+  }
+
+  CTx::new(tx_ins, &tx_outs).map_err(Error::CTxError)
+}
+
+// returns the staked commitment outputs of `ctx` addressed to the owner of `view_key`
+pub fn find_staked_commitments(
+  ctx: &CTx,
+  view_key: &Scalar,
+) -> Result<Vec<StakedCommitment>, Error> {
+  let ctx_outs = ctx.get_ctx_outs();
+  let mut staked_commitments = vec![];
+
+  for index in 0..ctx_outs.len() {
+    let ctx_out = ctx_outs.at(index)?;
+    if !ctx_out.is_staked_commitment() {
+      continue;
+    }
+    let res = ctx_out.recover_amount(view_key)?;
+    if let Some(res) = res {
+      staked_commitments.push(StakedCommitment {
+        index,
+        amount: res.amount,
+      });
+    }
+  }
+  Ok(staked_commitments)
+}
+
+// spends the staked commitment at `out_point` and sends the amount less the fee to `dest`
+pub fn build_unstake_ctx(
+  amount: u64,
+  gamma: &Scalar,
+  spending_key: &Scalar,
+  out_point: &OutPoint,
+  dest: &SubAddr,
+) -> Result<CTx, Error> {
+  let fee = calc_fee(1, 1, DEFAULT_FEE_RATE);
+  if amount <= fee {
+    return Err(Error::InsufficientFunds {
+      available: amount,
+      required: fee + 1,
+    });
+  }
+  let default_token_id = TokenId::default()?;
+
+  let tx_in = TxIn::new(
+    amount,
+    gamma,
+    spending_key,
+    &default_token_id,
+    out_point,
+    true,
+    false,
+  )?;
+
+  let tx_out = TxOut::new(
+    dest,
+    amount - fee,
+    "",
+    &default_token_id,
+    TxOutputType::Normal,
+    0,
+    false,
+    None,
+  )?;
+
+  CTx::new(&vec![tx_in], &vec![tx_out]).map_err(Error::CTxError)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    chain::COIN, ctx_id::CTxId, keys::child_key::ChildKey, keys::public_key::PublicKey,
+    sub_addr_id::SubAddrId,
+  };
+
+  fn gen_sub_addr(view_key: &Scalar) -> SubAddr {
+    let spending_pub_key = PublicKey::random().unwrap();
+    SubAddr::new(view_key, &spending_pub_key, &SubAddrId::new(0, 0))
+  }
+
+  fn gen_tx_in(amount: u64, is_staked_commitment: bool) -> TxIn {
+    let spending_key = ChildKey::random().unwrap().to_tx_key().to_spending_key();
+    let out_point = OutPoint::new(&CTxId::random()).unwrap();
+    TxIn::new(
+      amount,
+      &Scalar::new(100).unwrap(),
+      &spending_key,
+      &TokenId::default().unwrap(),
+      &out_point,
+      is_staked_commitment,
+      false,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_validate_stake() {
+    let min_stake = Chain::Mainnet.min_stake();
+    assert!(validate_stake(Chain::Mainnet, min_stake, min_stake).is_ok());
+    assert_eq!(
+      validate_stake(Chain::Mainnet, min_stake, min_stake - 1),
+      Err(Error::MinStakeBelowChainMinimum {
+        min_stake: min_stake - 1,
+        chain_min_stake: min_stake,
+      })
+    );
+    assert_eq!(
+      validate_stake(Chain::Mainnet, min_stake - 1, min_stake),
+      Err(Error::AmountBelowMinStake {
+        amount: min_stake - 1,
+        min_stake,
+      })
+    );
+  }
+
+  #[test]
+  fn test_stake_and_find() {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let dest = gen_sub_addr(&view_key);
+    let amount = Chain::get().unwrap().min_stake();
+    let tx_ins = vec![gen_tx_in(amount + COIN, false)];
+
+    let ctx = build_stake_ctx(&tx_ins, &dest, amount, amount, &dest).unwrap();
+    let staked_commitments = find_staked_commitments(&ctx, &view_key).unwrap();
+    assert_eq!(staked_commitments.len(), 1);
+    assert_eq!(staked_commitments[0].amount, amount);
+
+    let other_view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    assert!(find_staked_commitments(&ctx, &other_view_key)
+      .unwrap()
+      .is_empty());
+  }
+
+  #[test]
+  fn test_stake_insufficient_funds() {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let dest = gen_sub_addr(&view_key);
+    let amount = Chain::get().unwrap().min_stake();
+    let tx_ins = vec![gen_tx_in(amount, false)];
+
+    let res = build_stake_ctx(&tx_ins, &dest, amount, amount, &dest);
+    assert!(matches!(res, Err(Error::InsufficientFunds { .. })));
+  }
+
+  #[test]
+  fn test_stake_rejects_staked_commitment_input() {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let dest = gen_sub_addr(&view_key);
+    let amount = Chain::get().unwrap().min_stake();
+    let tx_ins = vec![gen_tx_in(amount + COIN, true)];
+
+    let res = build_stake_ctx(&tx_ins, &dest, amount, amount, &dest);
+    assert_eq!(res.unwrap_err(), Error::StakedCommitmentInput(0));
+  }
+
+  #[test]
+  fn test_unstake() {
+    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+    let dest = gen_sub_addr(&view_key);
+    let spending_key = ChildKey::random().unwrap().to_tx_key().to_spending_key();
+    let out_point = OutPoint::new(&CTxId::random()).unwrap();
+    let amount = Chain::get().unwrap().min_stake();
+
+    let ctx = build_unstake_ctx(
+      amount,
+      &Scalar::new(100).unwrap(),
+      &spending_key,
+      &out_point,
+      &dest,
+    )
+    .unwrap();
+    assert_eq!(ctx.get_ctx_ins().len(), 1);
+  }
+}
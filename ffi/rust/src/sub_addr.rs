@@ -1,12 +1,12 @@
 use crate::{
-  blsct_obj::BlsctObj,
+  blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
   ffi::{
     derive_sub_address, deserialize_sub_addr, dpk_to_sub_addr, serialize_sub_addr, BlsctRetVal,
     BlsctSubAddr,
   },
   keys::{double_public_key::DoublePublicKey, public_key::PublicKey},
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
   scalar::Scalar,
   sub_addr_id::SubAddrId,
 };
@@ -20,6 +20,7 @@ pub struct SubAddr {
 
 impl_from_retval!(SubAddr);
 impl_display!(SubAddr);
+impl_bytes!(SubAddr);
 impl_clone!(SubAddr);
 
 impl SubAddr {
@@ -37,12 +38,13 @@ impl SubAddr {
   impl_value!(BlsctSubAddr);
 }
 
-impl From<DoublePublicKey> for SubAddr {
-  fn from(dpk: DoublePublicKey) -> SubAddr {
+impl TryFrom<DoublePublicKey> for SubAddr {
+  type Error = blsct_obj::Error;
+
+  fn try_from(dpk: DoublePublicKey) -> Result<SubAddr, Self::Error> {
     let rv = unsafe { dpk_to_sub_addr(dpk.value()) };
-    BlsctObj::<SubAddr, BlsctSubAddr>::from_retval(rv)
-      .unwrap()
-      .into()
+    let obj = BlsctObj::<SubAddr, BlsctSubAddr>::from_retval(rv)?;
+    Ok(obj.into())
   }
 }
 
@@ -71,20 +73,16 @@ impl From<BlsctObj<SubAddr, BlsctSubAddr>> for SubAddr {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{
-    initializer::init, keys::double_public_key::DoublePublicKey, util::gen_random_view_key,
-  };
+  use crate::{keys::double_public_key::DoublePublicKey, util::gen_random_view_key};
 
   #[test]
   fn test_from_dpk() {
-    init();
     let dpk = DoublePublicKey::random().unwrap();
-    let _: SubAddr = dpk.into();
+    let _: SubAddr = dpk.try_into().unwrap();
   }
 
   #[test]
   fn test_eq() {
-    init();
     let spending_pub_key = PublicKey::random().unwrap();
     let view_key = gen_random_view_key().unwrap();
     let sub_addr_id_a = SubAddrId::new(123, 456);
@@ -101,7 +99,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let spending_pub_key = PublicKey::random().unwrap();
     let view_key = gen_random_view_key().unwrap();
     let sub_addr_id = SubAddrId::new(123, 456);
@@ -5,7 +5,7 @@ use crate::{
     deserialize_sub_addr_id, gen_sub_addr_id, get_sub_addr_id_account, get_sub_addr_id_address,
     serialize_sub_addr_id, BlsctRetVal, BlsctSubAddrId,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::c_char;
@@ -17,6 +17,7 @@ pub struct SubAddrId {
 
 impl_from_retval!(SubAddrId);
 impl_display!(SubAddrId);
+impl_bytes!(SubAddrId);
 impl_clone!(SubAddrId);
 
 impl SubAddrId {
@@ -37,6 +38,8 @@ impl SubAddrId {
 }
 
 impl BlsctSerde for SubAddrId {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_sub_addr_id(ptr as *const BlsctSubAddrId)
   }
@@ -61,25 +64,21 @@ impl From<BlsctObj<SubAddrId, BlsctSubAddrId>> for SubAddrId {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
 
   #[test]
   fn test_account() {
-    init();
     let a = SubAddrId::new(123, 456);
     assert_eq!(a.account(), 123);
   }
 
   #[test]
   fn test_address() {
-    init();
     let a = SubAddrId::new(123, 456);
     assert_eq!(a.address(), 456);
   }
 
   #[test]
   fn test_eq() {
-    init();
     let a = SubAddrId::new(123, 456);
     let b = SubAddrId::new(234, 567);
 
@@ -91,7 +90,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = SubAddrId::new(123, 456);
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<SubAddrId>(&hex).unwrap();
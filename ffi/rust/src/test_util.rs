@@ -1,66 +1,343 @@
 #[cfg(test)]
 use crate::{
-  ctx::CTx, ctx_id::CTxId, ffi::TxOutputType, keys::child_key::ChildKey,
-  keys::public_key::PublicKey, out_point::OutPoint, scalar::Scalar, sub_addr::SubAddr,
-  sub_addr_id::SubAddrId, token_id::TokenId, tx_in::TxIn, tx_out::TxOut,
+  ctx::CTx,
+  ctx_id::CTxId,
+  fee::{calc_fee, DEFAULT_FEE_RATE},
+  ffi::TxOutputType,
+  keys::child_key::ChildKey,
+  keys::public_key::PublicKey,
+  out_point::OutPoint,
+  scalar::Scalar,
+  secret::SecretScalar,
+  sub_addr::SubAddr,
+  sub_addr_id::SubAddrId,
+  token_id::TokenId,
+  tx_in::TxIn,
+  tx_out::TxOut,
+  wallet::Wallet,
 };
 
+// the keys of a wallet that receives the outputs of a test
 #[cfg(test)]
-pub fn gen_ctx_actual(
-  out_amount: u64,
-  msg: &str,
-  destination: &SubAddr,
-  blinding_key: &Scalar,
-) -> CTx {
-  let spending_key = ChildKey::random().unwrap().to_tx_key().to_spending_key();
-  let out_point = {
-    let ctx_id = CTxId::random();
-    OutPoint::new(&ctx_id).unwrap()
-  };
-  let num_tx_in = 1;
-  let num_tx_out = 1;
-  let default_fee = 200000;
-  let fee = (num_tx_in + num_tx_out) * default_fee;
-  let in_amount = fee + out_amount;
+pub struct TestKeys {
+  pub view_key: SecretScalar,
+  pub spending_pub_key: PublicKey,
+}
+
+#[cfg(test)]
+impl TestKeys {
+  pub fn random() -> Self {
+    let tx_key = ChildKey::random().unwrap().to_tx_key();
+    TestKeys {
+      view_key: tx_key.to_view_key(),
+      spending_pub_key: PublicKey::from(tx_key.to_spending_key().expose_secret()),
+    }
+  }
+
+  pub fn sub_addr(&self, address: u64) -> SubAddr {
+    SubAddr::new(
+      &self.view_key,
+      &self.spending_pub_key,
+      &SubAddrId::new(0, address),
+    )
+  }
 
-  let gamma = Scalar::new(100).unwrap();
+  // a wallet watching the first few sub-addresses of the default account
+  pub fn wallet(&self) -> Wallet {
+    let sub_addr_ids: Vec<_> = (0..4).map(|i| SubAddrId::new(0, i)).collect();
+    Wallet::new(&self.view_key, &self.spending_pub_key, &sub_addr_ids).unwrap()
+  }
+}
+
+#[cfg(test)]
+pub fn gen_out_point() -> OutPoint {
+  OutPoint::new(&CTxId::random()).unwrap()
+}
+
+// a CTx spending `prev_out` of `in_amount` and paying each
+// (destination, amount, memo) of `payments`. the outputs are blinded with
+// `blinding_key`, or with a random key each if it is None.
+#[cfg(test)]
+pub fn gen_ctx_paying(
+  prev_out: &OutPoint,
+  in_amount: u64,
+  payments: &[(&SubAddr, u64, &str)],
+  blinding_key: Option<&Scalar>,
+) -> CTx {
+  let token_id = TokenId::default().unwrap();
   let tx_in = TxIn::new(
     in_amount,
-    &gamma,
-    &spending_key,
-    &TokenId::default().unwrap(),
-    &out_point,
+    &Scalar::new(100).unwrap(),
+    &Scalar::random().unwrap(),
+    &token_id,
+    prev_out,
     false,
     false,
   )
   .unwrap();
+  let tx_outs = payments
+    .iter()
+    .map(|(destination, amount, memo)| {
+      let random_key = Scalar::random().unwrap();
+      TxOut::new(
+        destination,
+        *amount,
+        memo,
+        &token_id,
+        TxOutputType::Normal,
+        0,
+        false,
+        Some(blinding_key.unwrap_or(&random_key)),
+      )
+      .unwrap()
+    })
+    .collect();
+  CTx::new(&vec![tx_in], &tx_outs).unwrap()
+}
 
-  let tx_out = TxOut::new(
-    &destination,
-    out_amount,
-    msg,
-    &TokenId::default().unwrap(),
-    TxOutputType::Normal,
-    0,
-    false,
+// the out point of the output of `txid` owned by `wallet`
+#[cfg(test)]
+pub fn owned_out_point(wallet: &Wallet, txid: &str) -> OutPoint {
+  let output = wallet.outputs().iter().find(|x| x.txid == txid).unwrap();
+  output.out_point.clone()
+}
+
+#[cfg(test)]
+pub fn gen_ctx_actual(
+  out_amount: u64,
+  msg: &str,
+  destination: &SubAddr,
+  blinding_key: &Scalar,
+) -> CTx {
+  gen_ctx_spending_actual(&gen_out_point(), out_amount, msg, destination, blinding_key)
+}
+
+#[cfg(test)]
+fn gen_ctx_spending_actual(
+  out_point: &OutPoint,
+  out_amount: u64,
+  msg: &str,
+  destination: &SubAddr,
+  blinding_key: &Scalar,
+) -> CTx {
+  let in_amount = calc_fee(1, 1, DEFAULT_FEE_RATE) + out_amount;
+  gen_ctx_paying(
+    out_point,
+    in_amount,
+    &[(destination, out_amount, msg)],
     Some(blinding_key),
   )
-  .unwrap();
+}
 
-  let tx_ins = vec![tx_in];
-  let tx_outs = vec![tx_out];
-  CTx::new(&tx_ins, &tx_outs).unwrap()
+#[cfg(test)]
+fn gen_destination() -> SubAddr {
+  let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
+  let spending_pub_key = PublicKey::random().unwrap();
+  let sub_addr_id = SubAddrId::new(67, 78);
+  SubAddr::new(&view_key, &spending_pub_key, &sub_addr_id)
 }
 
 #[cfg(test)]
 pub fn gen_ctx() -> CTx {
-  let destination = {
-    let view_key = ChildKey::random().unwrap().to_tx_key().to_view_key();
-    let spending_pub_key = PublicKey::random().unwrap();
-    let sub_addr_id = SubAddrId::new(67, 78);
-    SubAddr::new(&view_key, &spending_pub_key, &sub_addr_id)
-  };
   let blinding_key = Scalar::random().unwrap();
+  gen_ctx_actual(10000, "navio", &gen_destination(), &blinding_key)
+}
+
+#[cfg(test)]
+pub fn gen_ctx_spending(out_point: &OutPoint) -> CTx {
+  let blinding_key = Scalar::random().unwrap();
+  gen_ctx_spending_actual(out_point, 10000, "navio", &gen_destination(), &blinding_key)
+}
+
+// |t| above this means the timing depends on the data (the threshold used by dudect)
+#[cfg(test)]
+pub const TIMING_LEAK_T: f64 = 10.0;
+
+// runs `op` on randomly interleaved inputs of two classes and returns Welch's
+// t-statistic of the measured durations. `op(false)` and `op(true)` must
+// perform the same operation on data of the first and the second class.
+#[cfg(test)]
+pub fn timing_t_stat(mut op: impl FnMut(bool), num_samples: usize) -> f64 {
+  use rand::Rng;
+  use std::time::Instant;
+
+  // a single operation is too fast for the clock, so each sample is a batch
+  const BATCH_SIZE: usize = 32;
+
+  let mut rng = rand::rng();
+  let mut samples = Vec::with_capacity(num_samples);
+  for _ in 0..num_samples {
+    let class = rng.random::<bool>();
+    let start = Instant::now();
+    for _ in 0..BATCH_SIZE {
+      op(std::hint::black_box(class));
+    }
+    samples.push((class, start.elapsed().as_nanos() as f64));
+  }
+
+  // drop the slowest samples, which are mostly caused by preemption
+  let mut durations = samples.iter().map(|(_, d)| *d).collect::<Vec<_>>();
+  durations.sort_by(|a, b| a.total_cmp(b));
+  let cutoff = durations[durations.len() * 9 / 10];
+
+  let stats = |class: bool| {
+    let xs = samples
+      .iter()
+      .filter(|(c, d)| *c == class && *d <= cutoff)
+      .map(|(_, d)| *d)
+      .collect::<Vec<_>>();
+    let n = xs.len() as f64;
+    let mean = xs.iter().sum::<f64>() / n;
+    let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (n, mean, var)
+  };
+  let (n0, mean0, var0) = stats(false);
+  let (n1, mean1, var1) = stats(true);
+  (mean0 - mean1) / (var0 / n0 + var1 / n1).sqrt()
+}
+
+#[cfg(test)]
+pub fn assert_constant_time(op: impl FnMut(bool)) {
+  let t = timing_t_stat(op, 20000);
+  assert!(
+    t.abs() < TIMING_LEAK_T,
+    "timing depends on the data: t = {t}"
+  );
+}
+
+// a navio-core JSON-RPC server answering each request with the result or
+// the (code, message) error `handler` returns for its method and params
+#[cfg(test)]
+pub struct MockRpcServer {
+  addr: std::net::SocketAddr,
+}
+
+#[cfg(test)]
+impl MockRpcServer {
+  pub fn start<F>(mut handler: F) -> Self
+  where
+    F: FnMut(&str, &serde_json::Value) -> Result<serde_json::Value, (i64, String)> + Send + 'static,
+  {
+    use serde_json::json;
+    use std::io::Write;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+      for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+          continue;
+        };
+        let Some(req) = read_rpc_request(&mut stream) else {
+          continue;
+        };
+        let method = req["method"].as_str().unwrap_or_default();
+        let (status, res) = match handler(method, &req["params"]) {
+          Ok(result) => (
+            "200 OK",
+            json!({ "result": result, "error": null, "id": req["id"] }),
+          ),
+          Err((code, message)) => (
+            "500 Internal Server Error",
+            json!({ "result": null, "error": { "code": code, "message": message }, "id": req["id"] }),
+          ),
+        };
+        let body = res.to_string();
+        let _ = write!(
+          stream,
+          "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+          body.len()
+        );
+      }
+    });
+    MockRpcServer { addr }
+  }
+
+  pub fn url(&self) -> String {
+    format!("http://user:pass@{}", self.addr)
+  }
+}
+
+#[cfg(test)]
+fn read_rpc_request(stream: &mut std::net::TcpStream) -> Option<serde_json::Value> {
+  use std::io::Read;
+
+  let mut buf = vec![];
+  let mut chunk = [0u8; 4096];
+  loop {
+    if let Some(end) = buf.windows(4).position(|x| x == b"\r\n\r\n") {
+      let head = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+      let len = head
+        .lines()
+        .find_map(|x| x.strip_prefix("content-length:"))
+        .and_then(|x| x.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+      if buf.len() >= end + 4 + len {
+        return serde_json::from_slice(&buf[end + 4..end + 4 + len]).ok();
+      }
+    }
+    let n = stream.read(&mut chunk).ok()?;
+    if n == 0 {
+      return None;
+    }
+    buf.extend_from_slice(&chunk[..n]);
+  }
+}
+
+// serves `blocks` as a navio-core node whose chain they make up
+#[cfg(test)]
+pub fn serve_blocks(blocks: Vec<crate::chain_source::Block>) -> MockRpcServer {
+  use crate::rpc::RpcErrorCode;
+  use serde_json::json;
+
+  MockRpcServer::start(move |method, params| match method {
+    "getblockcount" => Ok(json!(blocks.len() - 1)),
+    "getblockhash" => blocks
+      .get(params[0].as_u64().unwrap_or(u64::MAX) as usize)
+      .map(|x| json!(x.hash))
+      .ok_or((
+        RpcErrorCode::InvalidParameter.code(),
+        "Block height out of range".to_string(),
+      )),
+    "getblock" => blocks
+      .iter()
+      .find(|x| x.hash == params[0])
+      .map(|x| json!(x))
+      .ok_or((
+        RpcErrorCode::InvalidAddressOrKey.code(),
+        "Block not found".to_string(),
+      )),
+    _ => Err((
+      RpcErrorCode::MethodNotFound.code(),
+      "Method not found".to_string(),
+    )),
+  })
+}
+
+// polls `fut` to completion on the current thread
+#[cfg(all(test, feature = "async"))]
+pub fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+  use std::{
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+  };
+
+  struct ThreadWaker(Thread);
+
+  impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+      self.0.unpark();
+    }
+  }
 
-  gen_ctx_actual(10000, "navio", &destination, &blinding_key)
+  let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+  let mut cx = Context::from_waker(&waker);
+  let mut fut = std::pin::pin!(fut);
+  loop {
+    if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+      return output;
+    }
+    thread::park();
+  }
 }
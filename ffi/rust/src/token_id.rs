@@ -5,7 +5,7 @@ use crate::{
     deserialize_token_id, gen_default_token_id, gen_token_id, gen_token_id_with_token_and_subid,
     get_token_id_subid, get_token_id_token, serialize_token_id, BlsctRetVal, BlsctTokenId,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_value},
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::c_char;
@@ -17,22 +17,23 @@ pub struct TokenId {
 
 impl_from_retval!(TokenId);
 impl_display!(TokenId);
+impl_bytes!(TokenId);
 impl_clone!(TokenId);
 
 impl TokenId {
-  pub fn default<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn default() -> Result<Self, blsct_obj::Error> {
     let rv = unsafe { gen_default_token_id() };
     let obj = BlsctObj::from_retval(rv)?;
     Ok(obj.into())
   }
 
-  pub fn from_token<'a>(token: u64) -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn from_token(token: u64) -> Result<Self, blsct_obj::Error> {
     let rv = unsafe { gen_token_id(token) };
     let obj = BlsctObj::from_retval(rv)?;
     Ok(obj.into())
   }
 
-  pub fn from_token_and_subid<'a>(token: u64, subid: u64) -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn from_token_and_subid(token: u64, subid: u64) -> Result<Self, blsct_obj::Error> {
     let rv = unsafe { gen_token_id_with_token_and_subid(token, subid) };
     let obj = BlsctObj::from_retval(rv)?;
     Ok(obj.into())
@@ -50,6 +51,8 @@ impl TokenId {
 }
 
 impl BlsctSerde for TokenId {
+  const PLAIN_BYTES: bool = true;
+
   unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
     serialize_token_id(ptr as *const BlsctTokenId)
   }
@@ -74,11 +77,9 @@ impl PartialEq for TokenId {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
 
   #[test]
   fn test_default() {
-    init();
     let token_id = TokenId::default().unwrap();
     assert_eq!(token_id.token(), 0);
     assert_eq!(token_id.subid(), u64::MAX); // should be uint64 max
@@ -86,7 +87,6 @@ mod tests {
 
   #[test]
   fn test_from_token() {
-    init();
     let token = 123u64;
     let token_id = TokenId::from_token(token).unwrap();
     assert_eq!(token_id.token(), token);
@@ -95,7 +95,6 @@ mod tests {
 
   #[test]
   fn test_from_token_and_subid() {
-    init();
     let token = 123u64;
     let subid = 456u64;
     let token_id = TokenId::from_token_and_subid(token, subid).unwrap();
@@ -105,7 +104,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = TokenId::from_token_and_subid(123, 456).unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<TokenId>(&hex).unwrap();
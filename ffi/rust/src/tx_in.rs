@@ -2,29 +2,36 @@ use crate::{
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
   ffi::{
-    buf_to_malloced_hex_c_str, build_tx_in, get_tx_in_amount, get_tx_in_gamma, get_tx_in_out_point,
-    get_tx_in_rbf, get_tx_in_spending_key, get_tx_in_staked_commitment, get_tx_in_token_id,
-    hex_to_malloced_buf, succ, BlsctOutPoint, BlsctRetVal, BlsctScalar, BlsctTokenId, BlsctTxIn,
+    buf_to_malloced_hex_c_str, build_tx_in, err_bool, get_tx_in_amount, get_tx_in_gamma,
+    get_tx_in_out_point, get_tx_in_rbf, get_tx_in_spending_key, get_tx_in_staked_commitment,
+    get_tx_in_token_id, hex_to_malloced_buf, succ, BlsctOutPoint, BlsctRetVal, BlsctScalar,
+    BlsctTokenId, BlsctTxIn, BLSCT_FAILURE, TX_IN_BOOL_OFFSETS,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_from_retval, impl_redacted, impl_value},
   out_point::OutPoint,
   scalar::Scalar,
+  secret::SecretScalar,
   token_id::TokenId,
+  util::{is_valid_bool, is_valid_hex_of_size},
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::{c_char, c_void, CStr};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Debug, Deserialize, Serialize, Eq)]
+// holds the secrets of the input, so the buffer is wiped on drop and never printed
+#[derive(Deserialize, Serialize, Eq)]
 pub struct TxIn {
   obj: BlsctObj<TxIn, BlsctTxIn>,
 }
 
+impl_bytes!(TxIn);
 impl_clone!(TxIn);
-impl_display!(TxIn);
+impl_redacted!(TxIn);
 impl_from_retval!(TxIn);
 
 impl TxIn {
-  pub fn new<'a>(
+  pub fn new(
     amount: u64,
     gamma: &Scalar,
     spending_key: &Scalar,
@@ -32,7 +39,7 @@ impl TxIn {
     out_point: &OutPoint,
     is_staked_commitment: bool,
     is_rbf: bool,
-  ) -> Result<Self, blsct_obj::Error<'a>> {
+  ) -> Result<Self, blsct_obj::Error> {
     let rv = unsafe {
       build_tx_in(
         amount,
@@ -53,14 +60,14 @@ impl TxIn {
     unsafe { get_tx_in_amount(self.value()) }
   }
 
-  pub fn gamma(&self) -> Scalar {
+  pub fn gamma(&self) -> SecretScalar {
     let rv = unsafe { get_tx_in_gamma(self.value()) };
-    BlsctObj::<Scalar, BlsctScalar>::copy_from_c_obj(rv).into()
+    SecretScalar::new(BlsctObj::<Scalar, BlsctScalar>::copy_from_c_obj(rv).into())
   }
 
-  pub fn spending_key(&self) -> Scalar {
+  pub fn spending_key(&self) -> SecretScalar {
     let rv = unsafe { get_tx_in_spending_key(self.value()) };
-    BlsctObj::<Scalar, BlsctScalar>::copy_from_c_obj(rv).into()
+    SecretScalar::new(BlsctObj::<Scalar, BlsctScalar>::copy_from_c_obj(rv).into())
   }
 
   pub fn token_id(&self) -> TokenId {
@@ -85,21 +92,34 @@ impl TxIn {
 }
 
 impl BlsctSerde for TxIn {
+  fn is_valid_bytes(bytes: &[u8]) -> bool {
+    TX_IN_BOOL_OFFSETS
+      .iter()
+      .all(|offset| is_valid_bool(bytes, *offset))
+  }
+
   unsafe fn serialize(ptr: *const u8, size: usize) -> *const i8 {
     buf_to_malloced_hex_c_str(ptr, size)
   }
 
   unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
+    let size = std::mem::size_of::<BlsctTxIn>();
+    if !is_valid_hex_of_size(CStr::from_ptr(hex).to_bytes(), size, Self::is_valid_bytes) {
+      return err_bool(BLSCT_FAILURE);
+    }
     let buf = hex_to_malloced_buf(hex);
-    let len = CStr::from_ptr(hex)
-      .to_str()
-      .expect("Malformed c-string found")
-      .len()
-      / 2;
-    succ(buf as *mut c_void, len)
+    succ(buf as *mut c_void, size)
+  }
+}
+
+impl Drop for TxIn {
+  fn drop(&mut self) {
+    self.obj.zeroize();
   }
 }
 
+impl ZeroizeOnDrop for TxIn {}
+
 impl From<BlsctObj<TxIn, BlsctTxIn>> for TxIn {
   fn from(obj: BlsctObj<TxIn, BlsctTxIn>) -> TxIn {
     TxIn { obj }
@@ -108,9 +128,11 @@ impl From<BlsctObj<TxIn, BlsctTxIn>> for TxIn {
 
 impl PartialEq for TxIn {
   fn eq(&self, other: &Self) -> bool {
+    // the secrets are compared without short-circuiting on either of them
+    let secrets_eq =
+      self.gamma().ct_eq(&other.gamma()) & self.spending_key().ct_eq(&other.spending_key());
     self.amount() == other.amount()
-      && self.gamma() == other.gamma()
-      && self.spending_key() == other.spending_key()
+      && bool::from(secrets_eq)
       && self.token_id() == other.token_id()
       && self.out_point() == other.out_point()
       && self.is_staked_commitment() == other.is_staked_commitment()
@@ -122,8 +144,8 @@ impl PartialEq for TxIn {
 mod tests {
   use super::*;
   use crate::{
-    ctx_id::CTxId, initializer::init, keys::child_key::ChildKey, out_point::OutPoint,
-    scalar::Scalar, token_id::TokenId,
+    ctx_id::CTxId, keys::child_key::ChildKey, out_point::OutPoint, scalar::Scalar,
+    token_id::TokenId,
   };
 
   fn gen_tx_in(amount: u64) -> TxIn {
@@ -151,7 +173,6 @@ mod tests {
 
   #[test]
   fn test_amount() {
-    init();
     let tx_in = gen_tx_in(123);
     let amount = tx_in.amount();
     assert_eq!(amount, 123);
@@ -159,22 +180,19 @@ mod tests {
 
   #[test]
   fn test_gamma() {
-    init();
     let tx_in = gen_tx_in(123);
     let gamma = tx_in.gamma();
-    assert_eq!(gamma, Scalar::new(42).unwrap());
+    assert_eq!(gamma.expose_secret(), &Scalar::new(42).unwrap());
   }
 
   #[test]
   fn test_spending_key() {
-    init();
     let tx_in = gen_tx_in(123);
     let _ = tx_in.spending_key();
   }
 
   #[test]
   fn test_token_id() {
-    init();
     let tx_in = gen_tx_in(123);
     let token_id = tx_in.token_id();
     assert_eq!(token_id, TokenId::default().unwrap());
@@ -182,14 +200,12 @@ mod tests {
 
   #[test]
   fn test_out_point() {
-    init();
     let tx_in = gen_tx_in(123);
     let _ = tx_in.out_point();
   }
 
   #[test]
   fn test_is_staked_commitment() {
-    init();
     let tx_in = gen_tx_in(123);
     let is_staked_commitment = tx_in.is_staked_commitment();
     assert_eq!(is_staked_commitment, false);
@@ -197,7 +213,6 @@ mod tests {
 
   #[test]
   fn test_is_rbf() {
-    init();
     let tx_in = gen_tx_in(123);
     let is_rbf = tx_in.is_rbf();
     assert_eq!(is_rbf, false);
@@ -205,7 +220,6 @@ mod tests {
 
   #[test]
   fn test_eq() {
-    init();
     let a = gen_tx_in(123);
     let b = gen_tx_in(456);
 
@@ -215,12 +229,40 @@ mod tests {
     assert!(b == b);
   }
 
+  #[test]
+  fn test_redacted() {
+    let tx_in = gen_tx_in(123);
+    assert_eq!(format!("{tx_in:?}"), "TxIn(<redacted>)");
+    assert_eq!(format!("{tx_in}"), "TxIn(<redacted>)");
+    let unsigned_input = crate::unsigned_input::UnsignedInput::new(&tx_in).unwrap();
+    assert_eq!(format!("{unsigned_input:?}"), "UnsignedInput(<redacted>)");
+  }
+
+  // what drop does before the buffer is freed
+  #[test]
+  fn test_zeroize() {
+    let mut tx_in = gen_tx_in(123);
+    tx_in.obj.zeroize();
+    assert_eq!(tx_in.amount(), 0);
+    assert_eq!(u64::from(tx_in.gamma().expose_secret().clone()), 0);
+  }
+
   #[test]
   fn test_deser() {
-    init();
     let a = gen_tx_in(123);
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<TxIn>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_invalid_bools_are_rejected() {
+    let bytes = gen_tx_in(123).to_bytes();
+    assert!(TxIn::from_bytes(&bytes).is_ok());
+    for offset in TX_IN_BOOL_OFFSETS {
+      let mut bytes = bytes.clone();
+      bytes[offset] = 2;
+      assert!(TxIn::from_bytes(&bytes).is_err());
+    }
+  }
 }
@@ -1,55 +1,53 @@
 use crate::{
   blsct_obj::{self, BlsctObj},
   blsct_serde::BlsctSerde,
+  chain::with_current_chain,
   ffi::{
     buf_to_malloced_hex_c_str, build_tx_out, err_bool, get_tx_out_amount, get_tx_out_blinding_key,
     get_tx_out_destination, get_tx_out_memo, get_tx_out_min_stake, get_tx_out_output_type,
     get_tx_out_subtract_fee_from_amount, get_tx_out_token_id, hex_to_malloced_buf, succ,
     BlsctRetVal, BlsctScalar, BlsctSubAddr, BlsctTokenId, BlsctTxOut, TxOutputType, BLSCT_FAILURE,
+    MEMO_BUF_SIZE, TX_OUT_BOOL_OFFSET, TX_OUT_MEMO_OFFSET, TX_OUT_OUTPUT_TYPE_OFFSET,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_value},
+  macros::{impl_bytes, impl_clone, impl_from_retval, impl_redacted, impl_value},
   scalar::Scalar,
+  secret::SecretScalar,
   sub_addr::SubAddr,
   token_id::TokenId,
+  util::{is_valid_bool, is_valid_hex_of_size},
 };
 use serde::{Deserialize, Serialize};
 use std::{
-  ffi::{c_char, c_void, CStr, CString, NulError},
-  fmt,
+  ffi::{c_char, c_int, c_void, CStr, CString, NulError},
   str::Utf8Error,
 };
+use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum Error<'a> {
-  BlsctObjError(blsct_obj::Error<'a>),
-  FailedToCreateCString(NulError),
-  FailedToConvertCStrToStr(Utf8Error),
-}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
 
-impl<'a> std::error::Error for Error<'a> {}
+  #[error("Failed to create CString")]
+  FailedToCreateCString(#[source] NulError),
 
-impl<'a> fmt::Display for Error<'a> {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    match self {
-      Error::BlsctObjError(e) => write!(f, "BlsctObjError: {e:?}"),
-      Error::FailedToCreateCString(e) => write!(f, "Failed to create CString: {e:?}"),
-      Error::FailedToConvertCStrToStr(e) => {
-        write!(f, "Failed to convert CStr to &str: {e:?}")
-      }
-    }
-  }
+  #[error("Failed to convert CStr to &str")]
+  FailedToConvertCStrToStr(#[source] Utf8Error),
 }
-#[derive(Debug, Deserialize, Serialize, Eq)]
+// holds the secrets of the output, so the buffer is wiped on drop and never printed
+#[derive(Deserialize, Serialize, Eq)]
 pub struct TxOut {
   obj: BlsctObj<TxOut, BlsctTxOut>,
 }
 
+impl_bytes!(TxOut);
 impl_clone!(TxOut);
-impl_display!(TxOut);
+impl_redacted!(TxOut);
 impl_from_retval!(TxOut);
 
 impl TxOut {
-  pub fn new<'a>(
+  pub fn new(
     destination: &SubAddr,
     amount: u64,
     memo: &str,
@@ -58,13 +56,13 @@ impl TxOut {
     min_stake: u64,
     subtract_fee_from_amount: bool,
     opt_blinding_key: Option<&Scalar>,
-  ) -> Result<Self, Error<'a>> {
+  ) -> Result<Self, Error> {
     let memo_c_str = CString::new(memo).map_err(Error::FailedToCreateCString)?;
 
-    let zero = Scalar::new(0).unwrap();
+    let zero = Scalar::new(0).map_err(Error::BlsctObjError)?;
     let blinding_key = opt_blinding_key.unwrap_or(&zero);
 
-    let rv = unsafe {
+    let rv = with_current_chain(|| unsafe {
       build_tx_out(
         destination.value(),
         amount,
@@ -75,7 +73,7 @@ impl TxOut {
         subtract_fee_from_amount,
         blinding_key.value(),
       )
-    };
+    });
     let obj = BlsctObj::from_retval(rv).map_err(Error::BlsctObjError)?;
 
     Ok(obj.into())
@@ -90,7 +88,7 @@ impl TxOut {
     unsafe { get_tx_out_amount(self.value()) }
   }
 
-  pub fn memo(&self) -> Result<String, Error<'_>> {
+  pub fn memo(&self) -> Result<String, Error> {
     let c_str = unsafe {
       let ptr = get_tx_out_memo(self.value());
       CStr::from_ptr(ptr)
@@ -116,32 +114,52 @@ impl TxOut {
     unsafe { get_tx_out_subtract_fee_from_amount(self.value()) }
   }
 
-  pub fn blinding_key(&self) -> Scalar {
+  pub fn blinding_key(&self) -> SecretScalar {
     let obj = unsafe { get_tx_out_blinding_key(self.value()) } as *mut BlsctScalar;
-    BlsctObj::<Scalar, BlsctScalar>::from_c_obj(obj).into()
+    SecretScalar::new(BlsctObj::<Scalar, BlsctScalar>::from_c_obj(obj).into())
   }
 
   impl_value!(BlsctTxOut);
 }
 
 impl BlsctSerde for TxOut {
+  // the memo is read as a C string and the output type as a C enum
+  fn is_valid_bytes(bytes: &[u8]) -> bool {
+    let memo = bytes.get(TX_OUT_MEMO_OFFSET..TX_OUT_MEMO_OFFSET + MEMO_BUF_SIZE);
+    let output_type = bytes
+      .get(TX_OUT_OUTPUT_TYPE_OFFSET..TX_OUT_OUTPUT_TYPE_OFFSET + size_of::<c_int>())
+      .and_then(|x| x.try_into().ok())
+      .map(c_int::from_ne_bytes);
+    let is_valid_output_type = [TxOutputType::Normal, TxOutputType::StakedCommitment]
+      .into_iter()
+      .any(|x| output_type == Some(x as c_int));
+    memo.is_some_and(|x| x.contains(&0))
+      && is_valid_output_type
+      && is_valid_bool(bytes, TX_OUT_BOOL_OFFSET)
+  }
+
   unsafe fn serialize(ptr: *const u8, size: usize) -> *const i8 {
     buf_to_malloced_hex_c_str(ptr, size)
   }
 
   unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
+    let size = std::mem::size_of::<BlsctTxOut>();
+    if !is_valid_hex_of_size(CStr::from_ptr(hex).to_bytes(), size, Self::is_valid_bytes) {
+      return err_bool(BLSCT_FAILURE);
+    }
     let buf = hex_to_malloced_buf(hex);
+    succ(buf as *mut c_void, size)
+  }
+}
 
-    match CStr::from_ptr(hex).to_str() {
-      Err(_) => err_bool(BLSCT_FAILURE),
-      Ok(str) => {
-        let len = str.len() / 2;
-        succ(buf as *mut c_void, len)
-      }
-    }
+impl Drop for TxOut {
+  fn drop(&mut self) {
+    self.obj.zeroize();
   }
 }
 
+impl ZeroizeOnDrop for TxOut {}
+
 impl From<BlsctObj<TxOut, BlsctTxOut>> for TxOut {
   fn from(obj: BlsctObj<TxOut, BlsctTxOut>) -> TxOut {
     TxOut { obj }
@@ -163,7 +181,6 @@ impl PartialEq for TxOut {
 mod tests {
   use super::*;
   use crate::{
-    initializer::init,
     keys::{child_key::ChildKey, public_key::PublicKey},
     sub_addr_id::SubAddrId,
     token_id::TokenId,
@@ -193,7 +210,6 @@ mod tests {
 
   #[test]
   fn test_destination() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let _ = tx_out.destination();
@@ -201,7 +217,6 @@ mod tests {
 
   #[test]
   fn test_amount() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let amount = tx_out.amount();
@@ -210,7 +225,6 @@ mod tests {
 
   #[test]
   fn test_memo() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let memo = tx_out.memo().unwrap();
@@ -219,7 +233,6 @@ mod tests {
 
   #[test]
   fn test_token_id() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let token_id = tx_out.token_id();
@@ -228,7 +241,6 @@ mod tests {
 
   #[test]
   fn test_output_type() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let output_type = tx_out.output_type();
@@ -237,7 +249,6 @@ mod tests {
 
   #[test]
   fn test_min_stake() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let min_stake = tx_out.min_stake();
@@ -246,7 +257,6 @@ mod tests {
 
   #[test]
   fn test_subtract_fee_from_amount() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let b = tx_out.subtract_fee_from_amount();
@@ -255,16 +265,22 @@ mod tests {
 
   #[test]
   fn test_blinding_key() {
-    init();
     let sub_addr_id = SubAddrId::new(123, 456);
     let tx_out = gen_tx_out(&sub_addr_id);
     let _ = tx_out.blinding_key();
   }
 
   #[test]
-  fn test_eq() {
-    init();
+  fn test_redacted() {
+    let tx_out = gen_tx_out(&SubAddrId::new(123, 456));
+    assert_eq!(format!("{tx_out:?}"), "TxOut(<redacted>)");
+    assert_eq!(format!("{tx_out}"), "TxOut(<redacted>)");
+    let unsigned_output = crate::unsigned_output::UnsignedOutput::new(&tx_out).unwrap();
+    assert_eq!(format!("{unsigned_output}"), "UnsignedOutput(<redacted>)");
+  }
 
+  #[test]
+  fn test_eq() {
     let a = {
       let sub_addr_id = SubAddrId::new(123, 456);
       gen_tx_out(&sub_addr_id)
@@ -282,8 +298,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
-
     let a = {
       let sub_addr_id = SubAddrId::new(123, 456);
       gen_tx_out(&sub_addr_id)
@@ -292,4 +306,22 @@ mod tests {
     let b = bincode::deserialize::<TxOut>(&hex).unwrap();
     assert_eq!(a, b);
   }
+
+  #[test]
+  fn test_invalid_bytes_are_rejected() {
+    let bytes = gen_tx_out(&SubAddrId::new(123, 456)).to_bytes();
+    assert!(TxOut::from_bytes(&bytes).is_ok());
+
+    let mut no_nul = bytes.clone();
+    no_nul[TX_OUT_MEMO_OFFSET..TX_OUT_MEMO_OFFSET + MEMO_BUF_SIZE].fill(b'a');
+    assert!(TxOut::from_bytes(&no_nul).is_err());
+
+    let mut bad_output_type = bytes.clone();
+    bad_output_type[TX_OUT_OUTPUT_TYPE_OFFSET] = 2;
+    assert!(TxOut::from_bytes(&bad_output_type).is_err());
+
+    let mut bad_bool = bytes.clone();
+    bad_bool[TX_OUT_BOOL_OFFSET] = 0xff;
+    assert!(TxOut::from_bytes(&bad_bool).is_err());
+  }
 }
@@ -0,0 +1,100 @@
+use crate::{
+  blsct_obj::{self, BlsctObj},
+  blsct_serde::BlsctSerde,
+  ffi::{
+    build_unsigned_input, delete_unsigned_input, deserialize_unsigned_input,
+    serialize_unsigned_input, BlsctRetVal, BlsctUnsignedInput,
+  },
+  macros::{impl_from_retval, impl_redacted, impl_try_clone},
+  tx_in::TxIn,
+};
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_char, c_void};
+
+// holds the gamma and spending key of the input and is never printed. the C++ object is freed
+// by the deleter of libblsct.
+#[derive(Deserialize, Serialize)]
+pub struct UnsignedInput {
+  obj: BlsctObj<UnsignedInput, BlsctUnsignedInput>,
+}
+
+impl_from_retval!(UnsignedInput);
+impl_redacted!(UnsignedInput);
+impl_try_clone!(UnsignedInput);
+
+impl UnsignedInput {
+  pub fn new(tx_in: &TxIn) -> Result<Self, blsct_obj::Error> {
+    Self::from_retval(unsafe { build_unsigned_input(tx_in.value()) })
+  }
+
+  // serialized by libblsct since the object lives on the C++ side
+  pub fn to_bytes(&self) -> Result<Vec<u8>, blsct_obj::Error> {
+    self.obj.to_bytes()
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, blsct_obj::Error> {
+    Ok(BlsctObj::from_bytes(bytes)?.into())
+  }
+
+  pub fn value(&self) -> *const c_void {
+    self.obj.as_ptr() as *const c_void
+  }
+}
+
+impl BlsctSerde for UnsignedInput {
+  unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
+    serialize_unsigned_input(ptr as *const c_void)
+  }
+
+  unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
+    deserialize_unsigned_input(hex)
+  }
+
+  fn deallocator() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    Some(delete_unsigned_input)
+  }
+}
+
+impl From<BlsctObj<UnsignedInput, BlsctUnsignedInput>> for UnsignedInput {
+  fn from(obj: BlsctObj<UnsignedInput, BlsctUnsignedInput>) -> UnsignedInput {
+    UnsignedInput { obj }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ctx_id::CTxId, keys::child_key::ChildKey, out_point::OutPoint, scalar::Scalar,
+    token_id::TokenId,
+  };
+
+  fn gen_unsigned_input() -> UnsignedInput {
+    let spending_key = ChildKey::random().unwrap().to_tx_key().to_spending_key();
+    let out_point = OutPoint::new(&CTxId::random()).unwrap();
+    let tx_in = TxIn::new(
+      250000,
+      &Scalar::new(100).unwrap(),
+      &spending_key,
+      &TokenId::default().unwrap(),
+      &out_point,
+      false,
+      false,
+    )
+    .unwrap();
+    UnsignedInput::new(&tx_in).unwrap()
+  }
+
+  #[test]
+  fn test_new() {
+    let _ = gen_unsigned_input();
+  }
+
+  #[test]
+  fn test_deser() {
+    let a = gen_unsigned_input();
+    let hex = bincode::serialize(&a).unwrap();
+    let b = bincode::deserialize::<UnsignedInput>(&hex).unwrap();
+    assert_eq!(hex, bincode::serialize(&b).unwrap());
+  }
+}
@@ -0,0 +1,104 @@
+use crate::{
+  blsct_obj::{self, BlsctObj},
+  blsct_serde::BlsctSerde,
+  ffi::{
+    build_unsigned_output, delete_unsigned_output, deserialize_unsigned_output,
+    serialize_unsigned_output, BlsctRetVal, BlsctUnsignedOutput,
+  },
+  macros::{impl_from_retval, impl_redacted, impl_try_clone},
+  tx_out::TxOut,
+};
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_char, c_void};
+
+// holds the blinding key of the output and is never printed. the C++ object is freed
+// by the deleter of libblsct.
+#[derive(Deserialize, Serialize)]
+pub struct UnsignedOutput {
+  obj: BlsctObj<UnsignedOutput, BlsctUnsignedOutput>,
+}
+
+impl_from_retval!(UnsignedOutput);
+impl_redacted!(UnsignedOutput);
+impl_try_clone!(UnsignedOutput);
+
+impl UnsignedOutput {
+  pub fn new(tx_out: &TxOut) -> Result<Self, blsct_obj::Error> {
+    Self::from_retval(unsafe { build_unsigned_output(tx_out.value()) })
+  }
+
+  // serialized by libblsct since the object lives on the C++ side
+  pub fn to_bytes(&self) -> Result<Vec<u8>, blsct_obj::Error> {
+    self.obj.to_bytes()
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, blsct_obj::Error> {
+    Ok(BlsctObj::from_bytes(bytes)?.into())
+  }
+
+  pub fn value(&self) -> *const c_void {
+    self.obj.as_ptr() as *const c_void
+  }
+}
+
+impl BlsctSerde for UnsignedOutput {
+  unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
+    serialize_unsigned_output(ptr as *const c_void)
+  }
+
+  unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
+    deserialize_unsigned_output(hex)
+  }
+
+  fn deallocator() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    Some(delete_unsigned_output)
+  }
+}
+
+impl From<BlsctObj<UnsignedOutput, BlsctUnsignedOutput>> for UnsignedOutput {
+  fn from(obj: BlsctObj<UnsignedOutput, BlsctUnsignedOutput>) -> UnsignedOutput {
+    UnsignedOutput { obj }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ffi::TxOutputType, keys::public_key::PublicKey, scalar::Scalar, sub_addr::SubAddr,
+    sub_addr_id::SubAddrId, token_id::TokenId, util::gen_random_view_key,
+  };
+
+  fn gen_unsigned_output() -> UnsignedOutput {
+    let destination = {
+      let view_key = gen_random_view_key().unwrap();
+      let spending_pub_key = PublicKey::random().unwrap();
+      SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(7, 9))
+    };
+    let tx_out = TxOut::new(
+      &destination,
+      10000,
+      "navio",
+      &TokenId::default().unwrap(),
+      TxOutputType::Normal,
+      0,
+      false,
+      Some(&Scalar::random().unwrap()),
+    )
+    .unwrap();
+    UnsignedOutput::new(&tx_out).unwrap()
+  }
+
+  #[test]
+  fn test_new() {
+    let _ = gen_unsigned_output();
+  }
+
+  #[test]
+  fn test_deser() {
+    let a = gen_unsigned_output();
+    let hex = bincode::serialize(&a).unwrap();
+    let b = bincode::deserialize::<UnsignedOutput>(&hex).unwrap();
+    assert_eq!(hex, bincode::serialize(&b).unwrap());
+  }
+}
@@ -0,0 +1,220 @@
+use crate::{
+  blsct_obj::{self, BlsctObj},
+  blsct_serde::BlsctSerde,
+  chain::with_current_chain,
+  ctx::CTx,
+  error::{result_code, ResultCode},
+  ffi::{
+    add_unsigned_transaction_input, add_unsigned_transaction_output, create_unsigned_transaction,
+    delete_unsigned_transaction, deserialize_ctx, deserialize_unsigned_transaction, free_obj,
+    get_unsigned_transaction_fee, get_unsigned_transaction_inputs_size,
+    get_unsigned_transaction_outputs_size, serialize_unsigned_transaction,
+    set_unsigned_transaction_fee, sign_unsigned_transaction, BlsctRetVal, BlsctUnsignedTransaction,
+  },
+  macros::{impl_redacted, impl_try_clone},
+  unsigned_input::UnsignedInput,
+  unsigned_output::UnsignedOutput,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+  cell::Cell,
+  ffi::{c_char, c_void},
+  marker::PhantomData,
+  ptr::NonNull,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+  #[error("Failed to allocate memory for UnsignedTransaction")]
+  FailedToAllocateMemory,
+
+  #[error("Failed to sign unsigned transaction")]
+  FailedToSign(#[source] ResultCode),
+
+  #[error(transparent)]
+  BlsctObjError(#[from] blsct_obj::Error),
+}
+
+// signing takes a mutable pointer to the transaction, so it is not Sync.
+// it holds the secrets of its inputs and is never printed.
+#[derive(Deserialize, Serialize)]
+pub struct UnsignedTransaction {
+  obj: BlsctObj<UnsignedTransaction, BlsctUnsignedTransaction>,
+  #[serde(skip)]
+  _not_sync: PhantomData<Cell<()>>,
+}
+
+impl_redacted!(UnsignedTransaction);
+impl_try_clone!(UnsignedTransaction);
+
+impl UnsignedTransaction {
+  pub fn new() -> Result<Self, Error> {
+    let vp = unsafe { create_unsigned_transaction() };
+    let ptr = NonNull::new(vp as *mut u8).ok_or(Error::FailedToAllocateMemory)?;
+    let obj = BlsctObj::new_with_deallocator(ptr, 0, Self::deallocator()); // size will not be used
+    Ok(obj.into())
+  }
+
+  // not using impl_from_retval!() because of the _not_sync marker
+  pub fn from_retval(rv: *mut BlsctRetVal) -> Result<Self, blsct_obj::Error> {
+    let obj = BlsctObj::from_retval(rv)?;
+    Ok(Self::from(obj))
+  }
+
+  pub fn add_input(&mut self, input: &UnsignedInput) {
+    unsafe { add_unsigned_transaction_input(self.value(), input.value()) }
+  }
+
+  pub fn add_output(&mut self, output: &UnsignedOutput) {
+    unsafe { add_unsigned_transaction_output(self.value(), output.value()) }
+  }
+
+  pub fn set_fee(&mut self, fee: u64) {
+    unsafe { set_unsigned_transaction_fee(self.value(), fee) }
+  }
+
+  pub fn fee(&self) -> u64 {
+    unsafe { get_unsigned_transaction_fee(self.value()) }
+  }
+
+  pub fn inputs_len(&self) -> usize {
+    unsafe { get_unsigned_transaction_inputs_size(self.value()) }
+  }
+
+  pub fn outputs_len(&self) -> usize {
+    unsafe { get_unsigned_transaction_outputs_size(self.value()) }
+  }
+
+  pub fn sign(&self) -> Result<CTx, Error> {
+    let rv = with_current_chain(|| unsafe { sign_unsigned_transaction(self.value()) });
+    if rv.is_null() {
+      return Err(Error::FailedToAllocateMemory);
+    }
+    let (result, hex) = unsafe { ((*rv).result, (*rv).value as *const c_char) };
+    unsafe { free_obj(rv as *mut c_void) };
+
+    if result != 0 {
+      return Err(Error::FailedToSign(result_code(result)));
+    }
+    let ctx_rv = unsafe { deserialize_ctx(hex) };
+    unsafe { free_obj(hex as *mut c_void) };
+
+    let obj = BlsctObj::<CTx, _>::from_retval(ctx_rv).map_err(Error::BlsctObjError)?;
+    Ok(obj.into())
+  }
+
+  // not using impl_value!() to return *mut c_void
+  // to avoid const_cast
+  pub fn value(&self) -> *mut c_void {
+    self.obj.as_ptr() as *mut c_void
+  }
+}
+
+impl BlsctSerde for UnsignedTransaction {
+  unsafe fn serialize(ptr: *const u8, _: usize) -> *const i8 {
+    serialize_unsigned_transaction(ptr as *const c_void)
+  }
+
+  unsafe fn deserialize(hex: *const c_char) -> *mut BlsctRetVal {
+    deserialize_unsigned_transaction(hex)
+  }
+
+  fn deallocator() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    Some(delete_unsigned_transaction)
+  }
+}
+
+impl From<BlsctObj<UnsignedTransaction, BlsctUnsignedTransaction>> for UnsignedTransaction {
+  fn from(obj: BlsctObj<UnsignedTransaction, BlsctUnsignedTransaction>) -> UnsignedTransaction {
+    UnsignedTransaction {
+      obj,
+      _not_sync: PhantomData,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    ctx_id::CTxId, ffi::TxOutputType, keys::public_key::PublicKey, out_point::OutPoint,
+    scalar::Scalar, sub_addr::SubAddr, sub_addr_id::SubAddrId, token_id::TokenId, tx_in::TxIn,
+    tx_out::TxOut, util::gen_random_view_key,
+  };
+
+  fn gen_unsigned_transaction(in_amount: u64, out_amount: u64, fee: u64) -> UnsignedTransaction {
+    let token_id = TokenId::default().unwrap();
+    let tx_in = TxIn::new(
+      in_amount,
+      &Scalar::new(100).unwrap(),
+      &Scalar::new(101).unwrap(),
+      &token_id,
+      &OutPoint::new(&CTxId::random()).unwrap(),
+      false,
+      false,
+    )
+    .unwrap();
+    let destination = {
+      let view_key = gen_random_view_key().unwrap();
+      let spending_pub_key = PublicKey::random().unwrap();
+      SubAddr::new(&view_key, &spending_pub_key, &SubAddrId::new(7, 9))
+    };
+    let tx_out = TxOut::new(
+      &destination,
+      out_amount,
+      "navio",
+      &token_id,
+      TxOutputType::Normal,
+      0,
+      false,
+      Some(&Scalar::new(777).unwrap()),
+    )
+    .unwrap();
+
+    let mut unsigned_tx = UnsignedTransaction::new().unwrap();
+    unsigned_tx.add_input(&UnsignedInput::new(&tx_in).unwrap());
+    unsigned_tx.add_output(&UnsignedOutput::new(&tx_out).unwrap());
+    unsigned_tx.set_fee(fee);
+    unsigned_tx
+  }
+
+  #[test]
+  fn test_send_to_other_thread() {
+    let unsigned_tx = gen_unsigned_transaction(250000, 10000, 1000);
+    let ctx = std::thread::spawn(move || unsigned_tx.sign().unwrap())
+      .join()
+      .unwrap();
+    assert_eq!(ctx.get_ctx_ins().len(), 1);
+  }
+
+  #[test]
+  fn test_add_input_output() {
+    let unsigned_tx = gen_unsigned_transaction(250000, 10000, 1000);
+    assert_eq!(unsigned_tx.inputs_len(), 1);
+    assert_eq!(unsigned_tx.outputs_len(), 1);
+  }
+
+  #[test]
+  fn test_fee() {
+    let unsigned_tx = gen_unsigned_transaction(250000, 10000, 1000);
+    assert_eq!(unsigned_tx.fee(), 1000);
+  }
+
+  #[test]
+  fn test_sign() {
+    let unsigned_tx = gen_unsigned_transaction(250000, 10000, 1000);
+    let ctx = unsigned_tx.sign().unwrap();
+    assert_eq!(ctx.get_ctx_ins().len(), 1);
+  }
+
+  #[test]
+  fn test_deser() {
+    let a = gen_unsigned_transaction(250000, 10000, 1000);
+    let hex = bincode::serialize(&a).unwrap();
+    let b = bincode::deserialize::<UnsignedTransaction>(&hex).unwrap();
+    assert_eq!(a.fee(), b.fee());
+    assert_eq!(a.inputs_len(), b.inputs_len());
+    assert_eq!(a.outputs_len(), b.outputs_len());
+  }
+}
@@ -2,17 +2,21 @@ use crate::{
   blsct_obj,
   ffi::{malloc, BlsctRetVal},
   keys::child_key::ChildKey,
-  scalar::Scalar,
+  secret::SecretScalar,
 };
 use rand::Rng;
 use std::{
   ffi::{c_char, CStr, CString},
   fmt,
+  str::Utf8Error,
 };
+use zeroize::Zeroize;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
   InvalidHexSize(String),
+  InvalidHex(String),
+  FailedToConvertCStrToStr(Utf8Error),
 }
 
 impl std::error::Error for Error {}
@@ -21,23 +25,27 @@ impl fmt::Display for Error {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
       Error::InvalidHexSize(msg) => write!(f, "Invalid hex size: {msg:?}"),
+      Error::InvalidHex(msg) => write!(f, "Invalid hex: {msg:?}"),
+      Error::FailedToConvertCStrToStr(e) => write!(f, "Failed to convert CStr to &str: {e:?}"),
     }
   }
 }
 pub fn c_hex_str_to_array<const N: usize>(raw_hex_c_str: *const c_char) -> Result<[u8; N], Error> {
   let hex_c_str = unsafe { std::ffi::CStr::from_ptr(raw_hex_c_str) };
-  let hex_str = hex_c_str.to_str().unwrap();
+  let hex_str = hex_c_str
+    .to_str()
+    .map_err(Error::FailedToConvertCStrToStr)?;
 
-  let bytes = hex::decode(hex_str).unwrap();
+  let bytes = hex::decode(hex_str).map_err(|e| Error::InvalidHex(format!("{e:?}")))?;
   bytes
     .as_slice()
     .try_into()
     .map_err(|e| Error::InvalidHexSize(format!("{e:?}")))
 }
 
-pub fn build_succ_blsct_ret_val<'a, const N: usize>(
+pub fn build_succ_blsct_ret_val<const N: usize>(
   value: *const u8,
-) -> Result<*mut BlsctRetVal, blsct_obj::Error<'a>> {
+) -> Result<*mut BlsctRetVal, blsct_obj::Error> {
   // allocate memory for BlsctRetVal
   let rv_ptr = unsafe { malloc(std::mem::size_of::<BlsctRetVal>()) as *mut BlsctRetVal };
   if rv_ptr.is_null() {
@@ -65,10 +73,35 @@ pub fn pad_hex_left<T>(hex: *const c_char) -> CString {
     padded.extend_from_slice(&h);
     h = padded;
   }
-  CString::new(h).unwrap()
+  // `h` comes from a CStr and is padded with b'0', so it has no interior NUL
+  unsafe { CString::from_vec_unchecked(h) }
 }
 
-pub fn gen_random_view_key<'a>() -> Result<Scalar, blsct_obj::Error<'a>> {
+// true if `hex` is a hex string encoding exactly `size` bytes
+pub fn is_hex_of_size(hex: &[u8], size: usize) -> bool {
+  hex.len() == size * 2 && hex.iter().all(|c| c.is_ascii_hexdigit())
+}
+
+// true if `hex` encodes exactly `size` bytes that pass `is_valid`. the decoded
+// bytes may hold secrets, so they are wiped afterwards.
+pub fn is_valid_hex_of_size(hex: &[u8], size: usize, is_valid: impl FnOnce(&[u8]) -> bool) -> bool {
+  if !is_hex_of_size(hex, size) {
+    return false;
+  }
+  let Ok(mut bytes) = hex::decode(hex) else {
+    return false;
+  };
+  let res = is_valid(&bytes);
+  bytes.zeroize();
+  res
+}
+
+// a C bool is valid only as 0 or 1
+pub fn is_valid_bool(bytes: &[u8], offset: usize) -> bool {
+  bytes.get(offset).is_some_and(|b| *b <= 1)
+}
+
+pub fn gen_random_view_key() -> Result<SecretScalar, blsct_obj::Error> {
   let child_key = ChildKey::random()?;
   let view_key = child_key.to_tx_key().to_view_key();
   Ok(view_key)
@@ -80,9 +113,37 @@ pub fn gen_random_malloced_buf<const N: usize>() -> *mut [u8; N] {
   rng.fill(&mut buf[..]);
 
   let c_obj = unsafe { libc::malloc(N) as *mut [u8; N] };
-  assert!(!c_obj.is_null(), "malloc failed");
+  if c_obj.is_null() {
+    std::alloc::handle_alloc_error(std::alloc::Layout::new::<[u8; N]>());
+  }
   unsafe {
     std::ptr::copy_nonoverlapping(buf.as_ptr(), c_obj as *mut u8, N);
   }
   c_obj
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_c_hex_str_to_array_garbage() {
+    for hex in ["", "0", "zz", "0011", "é"] {
+      let c_hex = CString::new(hex).unwrap();
+      assert!(c_hex_str_to_array::<4>(c_hex.as_ptr()).is_err());
+    }
+    let c_hex = CString::new("00112233").unwrap();
+    assert_eq!(
+      c_hex_str_to_array::<4>(c_hex.as_ptr()),
+      Ok([0x00, 0x11, 0x22, 0x33])
+    );
+  }
+
+  #[test]
+  fn test_is_hex_of_size() {
+    assert!(is_hex_of_size(b"00ff", 2));
+    assert!(!is_hex_of_size(b"00f", 2));
+    assert!(!is_hex_of_size(b"00fg", 2));
+    assert!(!is_hex_of_size(b"00ff00", 2));
+  }
+}
@@ -5,7 +5,7 @@ use crate::{
     are_vector_predicate_equal, deserialize_vector_predicate, serialize_vector_predicate,
     BlsctRetVal, BlsctVectorPredicate,
   },
-  macros::{impl_clone, impl_display, impl_from_retval, impl_size, impl_value},
+  macros::{impl_bytes, impl_clone, impl_display, impl_from_retval, impl_size, impl_value},
 };
 use serde::{Deserialize, Serialize};
 use std::ffi::c_char;
@@ -17,6 +17,7 @@ pub struct VectorPredicate {
 
 impl_from_retval!(VectorPredicate);
 impl_display!(VectorPredicate);
+impl_bytes!(VectorPredicate);
 impl_clone!(VectorPredicate);
 
 impl VectorPredicate {
@@ -25,6 +26,8 @@ impl VectorPredicate {
 }
 
 impl BlsctSerde for VectorPredicate {
+  const VARIABLE_SIZE: bool = true;
+
   unsafe fn serialize(ptr: *const u8, obj_size: usize) -> *const i8 {
     serialize_vector_predicate(ptr as *const BlsctVectorPredicate, obj_size)
   }
@@ -51,7 +54,6 @@ impl From<BlsctObj<VectorPredicate, BlsctVectorPredicate>> for VectorPredicate {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::initializer::init;
   use std::ffi::c_void;
 
   fn gen_vector_predicate(n: u8) -> VectorPredicate {
@@ -71,7 +73,6 @@ mod tests {
 
   #[test]
   fn test_eq() {
-    init();
     let a = gen_vector_predicate(1);
     let b = gen_vector_predicate(1);
     let c = gen_vector_predicate(2);
@@ -87,7 +88,6 @@ mod tests {
 
   #[test]
   fn test_deser() {
-    init();
     let a = gen_vector_predicate(2);
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<VectorPredicate>(&hex).unwrap();
@@ -15,21 +15,24 @@ impl ViewTag {
     ViewTag { value }
   }
 
-  pub fn random<'a>() -> Result<Self, blsct_obj::Error<'a>> {
+  pub fn random() -> Result<Self, blsct_obj::Error> {
     let blinding_pub_key = PublicKey::random()?;
     let view_key = gen_random_view_key()?;
     Ok(Self::new(&blinding_pub_key, &view_key))
   }
+
+  pub fn value(&self) -> u64 {
+    self.value
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{initializer::init, util::gen_random_view_key};
+  use crate::util::gen_random_view_key;
 
   #[test]
   fn test_new() {
-    init();
     let blinding_pub_key = PublicKey::random().unwrap();
     let view_key = gen_random_view_key().unwrap();
     let _ = ViewTag::new(&blinding_pub_key, &view_key);
@@ -37,13 +40,11 @@ mod tests {
 
   #[test]
   fn test_random() {
-    init();
     let _ = ViewTag::random();
   }
 
   #[test]
   fn test_deser() {
-    init();
     let a = ViewTag::random().unwrap();
     let hex = bincode::serialize(&a).unwrap();
     let b = bincode::deserialize::<ViewTag>(&hex).unwrap();
@@ -0,0 +1,468 @@
+use crate::{
+  blsct_obj,
+  chain_source::{self, ChainSource},
+  ctx::CTx,
+  ctx_out::CTxOut,
+  hash_id::{HashId, SubAddrs},
+  keys::public_key::PublicKey,
+  out_point::OutPoint,
+  scalar::Scalar,
+  secret::{SecretScalar, REDACTED},
+  sub_addr_id::SubAddrId,
+  token_id::TokenId,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt, fs, path::Path};
+use thiserror::Error;
+
+// version 1 did not record the sub-addresses of the wallet
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+#[derive(Error, Debug)]
+pub enum Error {
+  #[error("Expected {expected} out points, but got {actual}")]
+  OutPointsMismatch { expected: usize, actual: usize },
+
+  #[error("Failed to read the transaction: {0}")]
+  FailedToReadCTx(String),
+
+  #[error("Unsupported snapshot version {0}")]
+  UnsupportedVersion(u32),
+
+  #[error("Invalid snapshot: {0}")]
+  InvalidSnapshot(String),
+
+  #[error("The snapshot belongs to a different wallet")]
+  WalletMismatch,
+
+  #[error("Failed to derive the sub-addresses of the wallet")]
+  FailedToDeriveSubAddrs(#[source] blsct_obj::Error),
+
+  #[error("Failed to read or write the snapshot: {0}")]
+  Io(#[from] std::io::Error),
+
+  #[error("Failed to scan the chain: {0}")]
+  ChainSource(#[from] chain_source::Error),
+}
+
+// an output addressed to one of the sub-addresses of the wallet
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OwnedOutput {
+  pub out_point: OutPoint,
+  pub txid: String,
+  pub index: usize,
+  pub token_id: TokenId,
+  pub amount: u64,
+  pub memo: String,
+  pub sub_addr_id: SubAddrId,
+  pub spent_by: Option<String>,
+}
+
+impl OwnedOutput {
+  pub fn is_spent(&self) -> bool {
+    self.spent_by.is_some()
+  }
+}
+
+// what ingesting a CTx changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ingested {
+  pub received: usize,
+  pub spent: usize,
+}
+
+// the state of a wallet without its view key
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Snapshot {
+  version: u32,
+  view_pub_key: PublicKey,
+  spending_pub_key: PublicKey,
+  sub_addr_ids: Vec<SubAddrId>,
+  txids: BTreeSet<String>,
+  outputs: Vec<OwnedOutput>,
+}
+
+// tracks the outputs a wallet receives and spends. CTxs are expected to be
+// ingested in the order they are confirmed, so that an output is known by
+// the time a later CTx spends it.
+pub struct Wallet {
+  view_key: SecretScalar,
+  spending_pub_key: PublicKey,
+  sub_addr_ids: Vec<SubAddrId>,
+  sub_addrs: SubAddrs,
+  txids: BTreeSet<String>,
+  outputs: Vec<OwnedOutput>,
+}
+
+impl fmt::Debug for Wallet {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Wallet")
+      .field("view_key", &REDACTED)
+      .field("spending_pub_key", &self.spending_pub_key)
+      .field("outputs", &self.outputs)
+      .finish()
+  }
+}
+
+fn read_error(e: impl ToString) -> Error {
+  Error::FailedToReadCTx(e.to_string())
+}
+
+impl Wallet {
+  // the wallet owns the outputs sent to the given sub-addresses of the
+  // spending public key. outputs to any other sub-address are ignored even
+  // if the view key can read them.
+  pub fn new(
+    view_key: &Scalar,
+    spending_pub_key: &PublicKey,
+    sub_addr_ids: &[SubAddrId],
+  ) -> Result<Self, Error> {
+    let sub_addrs = SubAddrs::new(view_key, spending_pub_key, sub_addr_ids)
+      .map_err(Error::FailedToDeriveSubAddrs)?;
+    Ok(Wallet {
+      view_key: SecretScalar::new(view_key.clone()),
+      spending_pub_key: spending_pub_key.clone(),
+      sub_addr_ids: sub_addr_ids.to_vec(),
+      sub_addrs,
+      txids: BTreeSet::new(),
+      outputs: vec![],
+    })
+  }
+
+  pub fn spending_pub_key(&self) -> &PublicKey {
+    &self.spending_pub_key
+  }
+
+  pub fn outputs(&self) -> &[OwnedOutput] {
+    &self.outputs
+  }
+
+  // sets the transaction spending the output. it is None when no
+  // transaction that is still valid spends it.
+  pub(crate) fn set_spent_by(&mut self, out_point: &OutPoint, spent_by: Option<String>) {
+    if let Some(output) = self.outputs.iter_mut().find(|x| &x.out_point == out_point) {
+      output.spent_by = spent_by;
+    }
+  }
+
+  pub fn unspent_outputs(&self) -> impl Iterator<Item = &OwnedOutput> {
+    self.outputs.iter().filter(|x| !x.is_spent())
+  }
+
+  pub fn balance(&self, token_id: &TokenId) -> u64 {
+    self
+      .unspent_outputs()
+      .filter(|x| &x.token_id == token_id)
+      .fold(0u64, |acc, x| acc.saturating_add(x.amount))
+  }
+
+  // the balances of the tokens the wallet holds in the order first received
+  pub fn balances(&self) -> Vec<(TokenId, u64)> {
+    let mut balances: Vec<(TokenId, u64)> = vec![];
+    for output in self.unspent_outputs() {
+      match balances.iter_mut().find(|(x, _)| x == &output.token_id) {
+        Some((_, balance)) => *balance = balance.saturating_add(output.amount),
+        None => balances.push((output.token_id.clone(), output.amount)),
+      }
+    }
+    balances
+  }
+
+  // the out point of each output, i.e. the hash a later CTxIn spends it by,
+  // is given in the order of the outputs as computed by CTx::out_points or
+  // reported by the node. ingesting a CTx again has no effect.
+  pub fn ingest(&mut self, ctx: &CTx, out_points: &[OutPoint]) -> Result<Ingested, Error> {
+    let ctx_outs = ctx.get_ctx_outs();
+    if ctx_outs.len() != out_points.len() {
+      return Err(Error::OutPointsMismatch {
+        expected: ctx_outs.len(),
+        actual: out_points.len(),
+      });
+    }
+    let txid = ctx.get_ctx_id().map_err(read_error)?.to_hex();
+    if self.txids.contains(&txid) {
+      return Ok(Ingested::default());
+    }
+
+    let mut received = vec![];
+    for (i, out_point) in out_points.iter().enumerate() {
+      let ctx_out = ctx_outs.at(i).map_err(read_error)?;
+      if let Some(output) = self.recover(&ctx_out, out_point, &txid, i)? {
+        received.push(output);
+      }
+    }
+
+    let mut spent = 0;
+    let ctx_ins = ctx.get_ctx_ins();
+    for i in 0..ctx_ins.len() {
+      let ctx_in = ctx_ins.get_ctx_in_at(i).map_err(read_error)?;
+      let spent_output = self
+        .outputs
+        .iter_mut()
+        .find(|x| !x.is_spent() && ctx_in.spends(&x.out_point));
+      if let Some(output) = spent_output {
+        output.spent_by = Some(txid.clone());
+        spent += 1;
+      }
+    }
+
+    let ingested = Ingested {
+      received: received.len(),
+      spent,
+    };
+    self.outputs.extend(received);
+    self.txids.insert(txid);
+    Ok(ingested)
+  }
+
+  // ingests the CTxs of the blocks from `from_height` up to the tip and
+  // returns the height to continue from
+  pub fn scan<S: ChainSource + ?Sized>(
+    &mut self,
+    source: &S,
+    from_height: u32,
+  ) -> Result<u32, Error> {
+    let tip = source.tip_height()?;
+    for height in from_height..=tip {
+      for tx in source.block_at(height)?.tx {
+        self.ingest(&tx.to_ctx()?, &tx.out_points()?)?;
+      }
+    }
+    Ok(from_height.max(tip + 1))
+  }
+
+  // returns None if the output is not addressed to the wallet
+  fn recover(
+    &self,
+    ctx_out: &CTxOut,
+    out_point: &OutPoint,
+    txid: &str,
+    index: usize,
+  ) -> Result<Option<OwnedOutput>, Error> {
+    // the view key alone recovers the amounts of outputs sent to any wallet
+    // sharing it, so the output has to be sent to one of our sub-addresses
+    let key_id = HashId::new(
+      &ctx_out.blsct_data_blinding_pub_key(),
+      &ctx_out.blsct_data_spending_pub_key(),
+      &self.view_key,
+    );
+    let Some(sub_addr_id) = self.sub_addrs.get(&key_id) else {
+      return Ok(None);
+    };
+    let Some(res) = ctx_out.recover_amount(&self.view_key).map_err(read_error)? else {
+      return Ok(None);
+    };
+    Ok(Some(OwnedOutput {
+      out_point: out_point.clone(),
+      txid: txid.to_string(),
+      index,
+      token_id: ctx_out.token_id(),
+      amount: res.amount,
+      memo: res.msg,
+      sub_addr_id: sub_addr_id.clone(),
+      spent_by: None,
+    }))
+  }
+
+  pub fn snapshot(&self) -> Snapshot {
+    Snapshot {
+      version: SNAPSHOT_VERSION,
+      view_pub_key: PublicKey::from(self.view_key.expose_secret()),
+      spending_pub_key: self.spending_pub_key.clone(),
+      sub_addr_ids: self.sub_addr_ids.clone(),
+      txids: self.txids.clone(),
+      outputs: self.outputs.clone(),
+    }
+  }
+
+  // the view key is not part of the snapshot and has to match the one the
+  // snapshot was taken with
+  pub fn restore(view_key: &Scalar, snapshot: Snapshot) -> Result<Self, Error> {
+    if snapshot.version != SNAPSHOT_VERSION {
+      return Err(Error::UnsupportedVersion(snapshot.version));
+    }
+    if PublicKey::from(view_key) != snapshot.view_pub_key {
+      return Err(Error::WalletMismatch);
+    }
+    let mut wallet = Wallet::new(view_key, &snapshot.spending_pub_key, &snapshot.sub_addr_ids)?;
+    wallet.txids = snapshot.txids;
+    wallet.outputs = snapshot.outputs;
+    Ok(wallet)
+  }
+
+  pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(&self.snapshot())
+      .map_err(|e| Error::InvalidSnapshot(e.to_string()))?;
+    fs::write(path, json)?;
+    Ok(())
+  }
+
+  pub fn load(path: impl AsRef<Path>, view_key: &Scalar) -> Result<Self, Error> {
+    let snapshot = serde_json::from_str(&fs::read_to_string(path)?)
+      .map_err(|e| Error::InvalidSnapshot(e.to_string()))?;
+    Self::restore(view_key, snapshot)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    chain_source::{Block, BlockTx, DirSource, RpcSource},
+    ctx_id::CTxId,
+    fee::{calc_fee, DEFAULT_FEE_RATE},
+    rpc::RpcClient,
+    test_util::{gen_ctx_paying, gen_out_point, owned_out_point, serve_blocks, TestKeys},
+  };
+
+  // a CTx spending `prev_out` and sending `amount` to `keys`
+  fn gen_ctx(prev_out: &OutPoint, amount: u64, keys: &TestKeys) -> CTx {
+    let fee = calc_fee(1, 1, DEFAULT_FEE_RATE);
+    let destination = keys.sub_addr(1);
+    gen_ctx_paying(
+      prev_out,
+      amount + fee,
+      &[(&destination, amount, "navio")],
+      None,
+    )
+  }
+
+  #[test]
+  fn test_receive_and_spend() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let token_id = TokenId::default().unwrap();
+
+    let ctx1 = gen_ctx(&gen_out_point(), 5000, &keys);
+    let ingested = wallet.ingest(&ctx1, &ctx1.out_points().unwrap()).unwrap();
+    assert_eq!(ingested.received, 1);
+    assert_eq!(wallet.balance(&token_id), 5000);
+
+    // ingesting the same CTx again changes nothing
+    let ingested = wallet.ingest(&ctx1, &ctx1.out_points().unwrap()).unwrap();
+    assert_eq!(ingested, Ingested::default());
+
+    // spends the received output and sends 3000 back to the wallet
+    let txid1 = ctx1.get_ctx_id().unwrap().to_hex();
+    let ctx2 = gen_ctx(&owned_out_point(&wallet, &txid1), 3000, &keys);
+    let ingested = wallet.ingest(&ctx2, &ctx2.out_points().unwrap()).unwrap();
+    assert_eq!(ingested.spent, 1);
+    assert_eq!(ingested.received, 1);
+    assert_eq!(wallet.balance(&token_id), 3000);
+    assert_eq!(wallet.balances(), vec![(token_id, 3000)]);
+    assert_eq!(wallet.unspent_outputs().count(), 1);
+  }
+
+  #[test]
+  fn test_foreign_outputs_are_ignored() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let ctx = gen_ctx(&gen_out_point(), 5000, &TestKeys::random());
+    let ingested = wallet.ingest(&ctx, &ctx.out_points().unwrap()).unwrap();
+    assert_eq!(ingested.received, 0);
+    assert!(wallet.outputs().is_empty());
+
+    assert!(matches!(
+      wallet.ingest(&ctx, &[]),
+      Err(Error::OutPointsMismatch { .. })
+    ));
+  }
+
+  #[test]
+  fn test_other_spending_key_is_ignored() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+
+    // a wallet with the same view key but another spending key can read
+    // the amounts, but the outputs are not ours
+    let other = TestKeys {
+      view_key: keys.view_key.clone(),
+      spending_pub_key: PublicKey::random().unwrap(),
+    };
+    let ctx = gen_ctx(&gen_out_point(), 5000, &other);
+    let ctx_out = ctx.get_ctx_outs().at(0).unwrap();
+    assert!(ctx_out.recover_amount(&keys.view_key).unwrap().is_some());
+
+    let ingested = wallet.ingest(&ctx, &ctx.out_points().unwrap()).unwrap();
+    assert_eq!(ingested.received, 0);
+    assert!(wallet.outputs().is_empty());
+
+    let ctx = gen_ctx(&gen_out_point(), 5000, &keys);
+    wallet.ingest(&ctx, &ctx.out_points().unwrap()).unwrap();
+    assert_eq!(wallet.outputs()[0].sub_addr_id, SubAddrId::new(0, 1));
+  }
+
+  #[test]
+  fn test_snapshot() {
+    let keys = TestKeys::random();
+    let mut wallet = keys.wallet();
+    let ctx = gen_ctx(&gen_out_point(), 5000, &keys);
+    wallet.ingest(&ctx, &ctx.out_points().unwrap()).unwrap();
+
+    let path = std::env::temp_dir().join(format!("wallet-{}.json", rand::random::<u64>()));
+    wallet.save(&path).unwrap();
+    let restored = Wallet::load(&path, &keys.view_key);
+    let wrong_key = Wallet::load(&path, &TestKeys::random().view_key);
+    fs::remove_file(&path).unwrap();
+
+    let restored = restored.unwrap();
+    assert_eq!(restored.snapshot(), wallet.snapshot());
+    assert!(matches!(wrong_key, Err(Error::WalletMismatch)));
+
+    let bytes = bincode::serialize(&wallet.snapshot()).unwrap();
+    let snapshot: Snapshot = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(snapshot, wallet.snapshot());
+  }
+
+  // a chain whose first block pays the wallet 5000 and whose second block
+  // spends that and pays 3000 back
+  fn gen_chain(keys: &TestKeys) -> Vec<Block> {
+    let ctx1 = gen_ctx(&gen_out_point(), 5000, keys);
+    let out_points1 = ctx1.out_points().unwrap();
+    let mut wallet = keys.wallet();
+    wallet.ingest(&ctx1, &out_points1).unwrap();
+    let txid1 = ctx1.get_ctx_id().unwrap().to_hex();
+    let ctx2 = gen_ctx(&owned_out_point(&wallet, &txid1), 3000, keys);
+
+    [
+      (ctx1, out_points1),
+      (ctx2.try_clone().unwrap(), ctx2.out_points().unwrap()),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(height, (ctx, out_points))| Block {
+      hash: hex::encode(CTxId::random().to_bytes()),
+      height: height as u32,
+      tx: vec![BlockTx::new(ctx, out_points).unwrap()],
+    })
+    .collect()
+  }
+
+  fn assert_scan(source: &dyn ChainSource, keys: &TestKeys) {
+    let token_id = TokenId::default().unwrap();
+    let mut wallet = keys.wallet();
+    assert_eq!(wallet.scan(source, 0).unwrap(), 2);
+    assert_eq!(wallet.balance(&token_id), 3000);
+    assert_eq!(wallet.outputs().len(), 2);
+    assert_eq!(wallet.scan(source, 2).unwrap(), 2);
+  }
+
+  #[test]
+  fn test_scan() {
+    let keys = TestKeys::random();
+    let blocks = gen_chain(&keys);
+
+    let dir = std::env::temp_dir().join(format!("wallet-blocks-{}", rand::random::<u64>()));
+    fs::create_dir(&dir).unwrap();
+    let dir_source = DirSource::new(&dir);
+    for block in &blocks {
+      dir_source.write_block(block).unwrap();
+    }
+    assert_scan(&dir_source, &keys);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let server = serve_blocks(blocks);
+    let rpc_source = RpcSource::new(RpcClient::new(&server.url()).unwrap());
+    assert_scan(&rpc_source, &keys);
+  }
+}